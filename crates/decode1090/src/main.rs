@@ -1,8 +1,13 @@
 #![doc = include_str!("../readme.md")]
 
 use clap::Parser;
-use rs1090::decode::cpr::{decode_position, AircraftState, Position, UpdateIf};
-use rs1090::decode::SensorMetadata;
+use rs1090::decode::cpr::{
+    decode_airborne_position, decode_position, AircraftState, Position,
+    PositionFilter, UpdateIf,
+};
+use rs1090::decode::derived::DerivedQuantities;
+use rs1090::decode::inference::CommBInference;
+use rs1090::decode::{MilitaryApplication, SensorMetadata};
 use rs1090::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
@@ -36,12 +41,31 @@ struct Options {
     #[arg(long, short, default_value = "400")]
     deduplication: u128,
 
+    /// Correct up to BITS bit errors (0, 1 or 2) in DF17 and DF18 messages based on the CRC (at most 1 in DF11 messages)
+    #[arg(long, value_name = "BITS", default_value = "0")]
+    fix: u8,
+
+    /// Reject positions implying a speed above MAX_SPEED (in kts)
+    #[arg(long, value_name = "MAX_SPEED", default_value = "1000")]
+    max_speed: f64,
+
+    /// Reject positions further than MAX_RANGE from the reference (in km)
+    #[arg(long, value_name = "MAX_RANGE", default_value=None)]
+    max_range: Option<f64>,
+
+    /// Reject positions with a NUCp (or NIC) below MIN_NUCP
+    #[arg(long, value_name = "MIN_NUCP", default_value = "0")]
+    min_nucp: u8,
+
     /// Individual messages to decode
     msgs: Vec<String>,
 }
 
-// We create this struct because it is too troublesome to have Deserialize for
-// Message at this point.
+// Only the raw frame of each entry is kept: it is decoded again with the
+// current decoder, after bit correction and with the context of the previous
+// messages. Deserializing a TimedMessage would also parse the decoded fields,
+// which are discarded here and may not match the current structures in files
+// written by older versions (e.g. with the legacy `rssi` field).
 #[derive(Serialize, Deserialize)]
 struct JSONEntry {
     timestamp: f64,
@@ -53,6 +77,8 @@ struct JSONEntry {
     frame: Vec<u8>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     metadata: Vec<SensorMetadata>,
+    #[serde(skip)]
+    corrected_bits: Option<u8>,
 }
 
 #[tokio::main]
@@ -80,6 +106,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut reference = options.reference;
     let mut aircraft: BTreeMap<ICAO, AircraftState> = BTreeMap::new();
+    let mut inference = CommBInference::new(30.);
+    let mut derived = DerivedQuantities::new(10.);
 
     if let Some(mut file) = input_file {
         let mut contents = vec![];
@@ -104,6 +132,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         })
             as Box<dyn Fn(&AirbornePosition) -> bool>);
 
+        let filter = PositionFilter {
+            max_speed: options.max_speed,
+            max_range: options.max_range,
+            min_nuc_p: options.min_nucp,
+            ..PositionFilter::default()
+        };
+
         // Print the JSON objects
         for mut json in json_objects.into_iter().flatten() {
             // In case there is a rssi field (older version), create a source
@@ -117,6 +152,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     name: None,
                 })
             }
+            // Correct the frame first, so that corrupted and clean copies of a
+            // message received by different sensors are grouped together
+            if let Ok(corrected @ 1..) =
+                Message::fix_bit_errors(&mut json.frame, options.fix)
+            {
+                json.corrected_bits = Some(corrected);
+            }
             let timestamp_ms = (json.timestamp * 1e3) as u128;
             let frame = json.frame.clone();
 
@@ -144,8 +186,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     let _ = process_entries(
                         entries,
                         &mut aircraft,
+                        &mut inference,
+                        &mut derived,
                         &mut reference,
                         &update_reference,
+                        &filter,
                         &mut output_file,
                     )
                     .await;
@@ -158,8 +203,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let _ = process_entries(
                     entries,
                     &mut aircraft,
+                    &mut inference,
+                    &mut derived,
                     &mut reference,
                     &update_reference,
+                    &filter,
                     &mut output_file,
                 )
                 .await;
@@ -185,32 +233,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 // Helper function to merge entries into a single output
+#[allow(clippy::too_many_arguments)]
 async fn process_entries(
-    mut entries: Vec<JSONEntry>,
+    entries: Vec<JSONEntry>,
     aircraft: &mut BTreeMap<ICAO, AircraftState>,
+    inference: &mut CommBInference,
+    derived: &mut DerivedQuantities,
     reference: &mut Option<Position>,
     update_reference: &UpdateIf,
+    filter: &PositionFilter,
     mut output_file: &mut Option<File>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let merged_metadata: Vec<SensorMetadata> = entries
         .iter()
         .flat_map(|entry| entry.metadata.clone())
         .collect();
-    let json = entries.first_mut().unwrap();
-
-    let message = if let Ok((_, msg)) = Message::from_bytes((&json.frame, 0)) {
-        Some(msg)
-    } else {
-        None
-    };
+    // Not corrected if at least one sensor received a clean copy
+    let corrected_bits = entries
+        .iter()
+        .map(|entry| entry.corrected_bits)
+        .min()
+        .flatten();
+    let json = entries.first().unwrap();
+    let message = Message::decode(&json.frame).ok();
 
     // If old fashioned file, include the data in a metadata entry
     let mut msg = TimedMessage {
         timestamp: json.timestamp,
         frame: json.frame.clone(),
         message,
+        modeac: None,
         metadata: merged_metadata,
         decode_time: None,
+        corrected_bits,
     };
     if let Some(message) = &mut msg.message {
         match &mut message.df {
@@ -221,6 +276,7 @@ async fn process_entries(
                 aircraft,
                 reference,
                 update_reference,
+                filter,
             ),
             ExtendedSquitterTisB { cf, .. } => decode_position(
                 &mut cf.me,
@@ -229,21 +285,52 @@ async fn process_entries(
                 aircraft,
                 reference,
                 update_reference,
+                filter,
             ),
-            CommBAltitudeReply { bds, .. } => {
-                if let (Some(_), Some(_)) = (&bds.bds50, &bds.bds60) {
-                    bds.bds50 = None;
-                    bds.bds60 = None
+            ExtendedSquitterMilitary {
+                payload: MilitaryApplication::ExtendedSquitter(es),
+                ..
+            } => decode_position(
+                &mut es.me,
+                msg.timestamp,
+                &es.aa,
+                aircraft,
+                reference,
+                update_reference,
+                filter,
+            ),
+            CommBAltitudeReply { bds, ap, .. } => {
+                if let Some(bds05) = &mut bds.bds05 {
+                    decode_airborne_position(
+                        bds05,
+                        msg.timestamp,
+                        &(*ap).into(),
+                        aircraft,
+                        reference,
+                        update_reference,
+                        filter,
+                    )
                 }
             }
-            CommBIdentityReply { bds, .. } => {
-                if let (Some(_), Some(_)) = (&bds.bds50, &bds.bds60) {
-                    bds.bds50 = None;
-                    bds.bds60 = None
+            CommBIdentityReply { bds, ap, .. } => {
+                if let Some(bds05) = &mut bds.bds05 {
+                    decode_airborne_position(
+                        bds05,
+                        msg.timestamp,
+                        &(*ap).into(),
+                        aircraft,
+                        reference,
+                        update_reference,
+                        filter,
+                    )
                 }
             }
             _ => {}
         }
+        // Select between ambiguous BDS 5,0 and 6,0 Comm-B replies
+        inference.push(msg.timestamp, message);
+        // Fill in true headings
+        derived.push(msg.timestamp, message);
         let json = serde_json::to_string(&msg).unwrap();
         if let Some(file) = &mut output_file {
             file.write_all(json.as_bytes()).await?;
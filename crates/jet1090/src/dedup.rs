@@ -1,9 +1,11 @@
+use crate::Jet1090;
 use rs1090::prelude::*;
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
 use std::time::SystemTime;
-use tokio::sync::mpsc;
-use tracing::info;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, info};
 
 /**
  * A basic message deduplication algorithm.
@@ -11,6 +13,11 @@ use tracing::info;
  * Reads messages from a MPSC and sends deduplicated messages to another one.
  *
  * Identical messages are grouped for a duration of `dedup_threshold`.
+ * Up to `max_fix` bit errors are corrected in DF11, DF17 and DF18 messages
+ * before grouping, so that corrupted and clean copies of a message received
+ * by different sensors are merged together.
+ * Mode A/C replies, already decoded by the source, are passed as is.
+ * Frames which cannot be decoded are counted for each sensor, by reason.
  *
  * Future versions should check for average gap between sensors for a better
  * synchronisation.
@@ -19,12 +26,21 @@ pub async fn deduplicate_messages(
     mut rx: mpsc::Receiver<TimedMessage>,
     tx: mpsc::Sender<TimedMessage>,
     dedup_threshold: u32,
+    max_fix: u8,
+    app: Arc<Mutex<Jet1090>>,
 ) {
     let mut cache: HashMap<Vec<u8>, Vec<TimedMessage>> = HashMap::new();
     let mut expiration_heap: BinaryHeap<Reverse<(u128, Vec<u8>)>> =
         BinaryHeap::new();
 
-    while let Some(msg) = rx.recv().await {
+    while let Some(mut msg) = rx.recv().await {
+        if msg.modeac.is_none() {
+            if let Ok(corrected @ 1..) =
+                Message::fix_bit_errors(&mut msg.frame, max_fix)
+            {
+                msg.corrected_bits = Some(corrected);
+            }
+        }
         let timestamp_ms = (msg.timestamp * 1e3) as u128;
         let frame = msg.frame.clone();
 
@@ -53,28 +69,58 @@ pub async fn deduplicate_messages(
                     .iter()
                     .flat_map(|entry| entry.metadata.clone())
                     .collect();
+                // Not corrected if at least one sensor received a clean copy
+                let corrected_bits =
+                    entries.iter().map(|entry| entry.corrected_bits).min();
 
                 let mut tmsg = entries.remove(0);
                 tmsg.metadata = merged_metadata;
+                tmsg.corrected_bits = corrected_bits.flatten();
 
                 let start = SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .expect("SystemTime before unix epoch")
                     .as_secs_f64();
 
-                if let Ok((_, msg)) = Message::from_bytes((&tmsg.frame, 0)) {
-                    tmsg.decode_time = Some(
-                        SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .expect("SystemTime before unix epoch")
-                            .as_secs_f64()
-                            - start,
-                    );
-                    tmsg.message = Some(msg);
-
+                if tmsg.modeac.is_some() {
                     if let Err(e) = tx.send(tmsg).await {
                         info!("Failed to send deduplicated entries: {}", e);
                     }
+                    continue;
+                }
+                match Message::decode(&tmsg.frame) {
+                    Ok(msg) => {
+                        tmsg.decode_time = Some(
+                            SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .expect("SystemTime before unix epoch")
+                                .as_secs_f64()
+                                - start,
+                        );
+                        tmsg.message = Some(msg);
+
+                        if let Err(e) = tx.send(tmsg).await {
+                            info!("Failed to send deduplicated entries: {}", e);
+                        }
+                    }
+                    Err(e) => {
+                        debug!(
+                            "Rejected frame {}: {}",
+                            hex::encode(&tmsg.frame),
+                            e
+                        );
+                        let mut app = app.lock().await;
+                        for meta in &tmsg.metadata {
+                            if let Some(sensor) =
+                                app.sensors.get_mut(&meta.serial)
+                            {
+                                *sensor
+                                    .rejected
+                                    .entry(e.reason().to_string())
+                                    .or_default() += 1;
+                            }
+                        }
+                    }
                 }
             }
         }
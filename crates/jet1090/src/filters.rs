@@ -1,4 +1,4 @@
-use rs1090::decode::{TimedMessage, ICAO};
+use rs1090::decode::{MilitaryApplication, TimedMessage, ICAO};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,6 +33,12 @@ impl Filters {
     }
 
     pub fn is_in(filter: &Filters, msg: &TimedMessage) -> bool {
+        if let Some(modeac) = &msg.modeac {
+            return match &modeac.icao24 {
+                Some(icao24) => Self::aircraft_in(filter, icao24),
+                None => filter.aircraft_filter.is_none(),
+            } && Self::df_in(filter, "modeac");
+        }
         if let Some(msg) = &msg.message {
             match &msg.df {
                 rs1090::decode::DF::ShortAirAirSurveillance { ap, .. } => {
@@ -74,6 +80,14 @@ impl Filters {
                         return Self::df_in(filter, "18");
                     }
                 }
+                rs1090::decode::DF::ExtendedSquitterMilitary {
+                    payload: MilitaryApplication::ExtendedSquitter(es),
+                    ..
+                } => {
+                    if Self::aircraft_in(filter, &es.aa) {
+                        return Self::df_in(filter, "19");
+                    }
+                }
                 rs1090::decode::DF::ExtendedSquitterMilitary { .. } => {
                     return Self::df_in(filter, "19");
                 }
@@ -87,8 +101,8 @@ impl Filters {
                         return Self::df_in(filter, "21");
                     }
                 }
-                rs1090::decode::DF::CommDExtended { parity, .. } => {
-                    if Self::aircraft_in(filter, parity) {
+                rs1090::decode::DF::CommDExtended { ap, .. } => {
+                    if Self::aircraft_in(filter, ap) {
                         return Self::df_in(filter, "24");
                     }
                 }
@@ -110,8 +124,10 @@ mod tests {
             timestamp: 0.,
             frame: hex::decode("8c4841753a9a153237aef0f275be").unwrap(),
             message: None,
+            modeac: None,
             metadata: vec![],
             decode_time: None,
+            corrected_bits: None,
         };
         tmsg.message = Message::try_from(tmsg.frame.as_slice()).ok();
 
@@ -164,8 +180,10 @@ mod tests {
             timestamp: 1735943148.353877,
             frame: hex::decode("02c18c3b323e4f").unwrap(),
             message: None,
+            modeac: None,
             metadata: vec![],
             decode_time: None,
+            corrected_bits: None,
         };
         tmsg.message = Message::try_from(tmsg.frame.as_slice()).ok();
 
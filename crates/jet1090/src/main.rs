@@ -3,6 +3,8 @@
 mod aircraftdb;
 mod dedup;
 mod filters;
+mod position;
+mod sbs;
 mod sensor;
 mod shell;
 mod snapshot;
@@ -11,15 +13,19 @@ mod table;
 mod tui;
 mod web;
 
+use crate::position::{PositionDecoder, Positioned};
 use crate::tui::Event;
 use crate::web::TrackQuery;
-use clap::{Command, CommandFactory, Parser, ValueHint};
+use clap::{Command, CommandFactory, Parser, ValueEnum, ValueHint};
 use clap_complete::{generate, Generator};
 use crossterm::event::KeyCode;
 use ratatui::widgets::*;
 use redis::AsyncCommands;
-use rs1090::decode::cpr::{decode_position, AircraftState};
-use rs1090::decode::serialize_config;
+use rs1090::decode::cpr::PositionFilter;
+use rs1090::decode::derived::DerivedQuantities;
+use rs1090::decode::inference::CommBInference;
+use rs1090::decode::modeac::ModeACCorrelator;
+use rs1090::decode::{serialize_config, MilitaryApplication};
 use rs1090::prelude::*;
 use sensor::Sensor;
 use serde::Deserialize;
@@ -32,6 +38,7 @@ use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
+use tracing::error;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 use warp::Filter;
 
@@ -47,10 +54,14 @@ struct Options {
     #[arg(short, long, default_value = "false")]
     verbose: bool,
 
-    /// Dump a copy of the received messages as .jsonl
+    /// Dump a copy of the received messages to a file (see --output-format)
     #[arg(short, long, default_value=None, value_hint=ValueHint::FilePath)]
     output: Option<String>,
 
+    /// Format of the output file: JSON lines (default) or SBS-1 records
+    #[arg(long, value_name = "FORMAT")]
+    output_format: Option<OutputFormat>,
+
     /// Display a table in interactive mode (not compatible with verbose)
     #[arg(short, long, default_value = "false")]
     interactive: bool,
@@ -59,6 +70,18 @@ struct Options {
     #[arg(long, default_value=None)]
     serve_port: Option<u16>,
 
+    /// Serve the deduplicated messages in Beast format on BEAST_PORT (on 0.0.0.0)
+    #[arg(long, value_name = "BEAST_PORT")]
+    beast_port: Option<u16>,
+
+    /// Serve the decoded messages as SBS-1 (BaseStation) records on SBS_PORT (on 0.0.0.0)
+    #[arg(long, value_name = "SBS_PORT")]
+    sbs_port: Option<u16>,
+
+    /// Push the deduplicated messages in Beast format to a remote host (host:port)
+    #[arg(long, value_name = "ADDRESS")]
+    beast_push: Option<String>,
+
     /// How much history to expire (in minutes), 0 for no history
     #[arg(long, short = 'x')]
     history_expire: Option<u64>,
@@ -83,6 +106,26 @@ struct Options {
     #[arg(long, default_value = "450")]
     deduplication: Option<u32>,
 
+    /// Correct up to BITS bit errors (0, 1 or 2) in DF17 and DF18 messages based on the CRC (at most 1 in DF11 messages)
+    #[arg(long, value_name = "BITS")]
+    fix: Option<u8>,
+
+    /// Reject positions implying a speed above MAX_SPEED (in kts, default 1000)
+    #[arg(long, value_name = "MAX_SPEED")]
+    max_speed: Option<f64>,
+
+    /// Reject positions further than MAX_RANGE from the receiver (in km)
+    #[arg(long, value_name = "MAX_RANGE")]
+    max_range: Option<f64>,
+
+    /// Reject positions with a NUCp (or NIC) below MIN_NUCP
+    #[arg(long, value_name = "MIN_NUCP")]
+    min_nucp: Option<u8>,
+
+    /// Give up reconnecting to TCP and WebSocket sources after N attempts (default: never)
+    #[arg(long, value_name = "N")]
+    max_reconnect: Option<u32>,
+
     #[arg(long)]
     stats: Option<bool>,
 
@@ -119,6 +162,17 @@ struct Options {
     redis_topic: Option<String>,
 }
 
+/// The format of the messages dumped to the output file
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum OutputFormat {
+    /// Decoded messages, as JSON lines
+    #[default]
+    Jsonl,
+    /// SBS-1 (BaseStation) records
+    Sbs,
+}
+
 fn expanduser(path: PathBuf) -> PathBuf {
     // Check if the path starts with "~"
     if let Some(stripped) = path.to_str().and_then(|p| p.strip_prefix("~")) {
@@ -172,12 +226,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if cli_options.output.is_some() {
         options.output = cli_options.output;
     }
+    if cli_options.output_format.is_some() {
+        options.output_format = cli_options.output_format;
+    }
     if cli_options.interactive {
         options.interactive = true;
     }
     if cli_options.serve_port.is_some() {
         options.serve_port = cli_options.serve_port;
     }
+    if cli_options.beast_port.is_some() {
+        options.beast_port = cli_options.beast_port;
+    }
+    if cli_options.beast_push.is_some() {
+        options.beast_push = cli_options.beast_push;
+    }
+    if cli_options.sbs_port.is_some() {
+        options.sbs_port = cli_options.sbs_port;
+    }
     if cli_options.history_expire.is_some() {
         options.history_expire = cli_options.history_expire;
     }
@@ -208,6 +274,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if cli_options.deduplication.is_some() {
         options.deduplication = cli_options.deduplication;
     }
+    if cli_options.fix.is_some() {
+        options.fix = cli_options.fix;
+    }
+    if cli_options.max_speed.is_some() {
+        options.max_speed = cli_options.max_speed;
+    }
+    if cli_options.max_range.is_some() {
+        options.max_range = cli_options.max_range;
+    }
+    if cli_options.min_nucp.is_some() {
+        options.min_nucp = cli_options.min_nucp;
+    }
+    if cli_options.max_reconnect.is_some() {
+        options.max_reconnect = cli_options.max_reconnect;
+    }
     if options.stats.unwrap_or(false) {
         serialize_config(true);
     }
@@ -262,6 +343,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         aircraft_filter: options.aircraft_filter,
     };
 
+    // SBS-1 records instead of JSON in the output file
+    let sbs_output = options.output_format == Some(OutputFormat::Sbs);
     let mut file = if let Some(output_path) = options.output {
         let output_path = expanduser(PathBuf::from(output_path));
         Some(
@@ -291,7 +374,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         false => None,
     };
 
-    let mut aircraft: BTreeMap<ICAO, AircraftState> = BTreeMap::new();
+    let mut inference = CommBInference::new(30.);
+    let mut derived = DerivedQuantities::new(10.);
+    let mut correlator = ModeACCorrelator::new(60.);
 
     let terminal = if options.interactive {
         Some(tui::init()?)
@@ -449,19 +534,93 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let (tx_dedup, mut rx_dedup) =
         tokio::sync::mpsc::channel(100 * multiplier + 1);
 
+    // Report the state of the connection to each source
+    let (tx_state, mut rx_state) = tokio::sync::mpsc::channel(multiplier + 1);
+    let app_state = app_dec.clone();
+    tokio::spawn(async move {
+        while let Some((serial, state)) = rx_state.recv().await {
+            let mut app = app_state.lock().await;
+            if let Some(sensor) = app.sensors.get_mut(&serial) {
+                sensor.connection = Some(state);
+            }
+        }
+    });
+
+    // Report the status of Beast receivers (0x34 frames)
+    let (tx_status, mut rx_status) = tokio::sync::mpsc::channel(multiplier + 1);
+    let app_status = app_dec.clone();
+    tokio::spawn(async move {
+        while let Some((serial, status)) = rx_status.recv().await {
+            let mut app = app_status.lock().await;
+            if let Some(sensor) = app.sensors.get_mut(&serial) {
+                sensor.status = Some(status);
+            }
+        }
+    });
+
+    let backoff = beast::Backoff {
+        max_attempts: options.max_reconnect,
+        ..Default::default()
+    };
     for source in options.sources.into_iter() {
         let serial = source.serial();
         let tx_copy = tx.clone();
+        let tx_state = tx_state.clone();
+        let tx_status = tx_status.clone();
         tokio::spawn(async move {
-            source.receiver(tx_copy, serial, source.name.clone()).await;
+            source
+                .receiver(
+                    tx_copy,
+                    serial,
+                    source.name.clone(),
+                    backoff,
+                    tx_state,
+                    tx_status,
+                )
+                .await;
         });
     }
 
+    // Re-publish the deduplicated messages in Beast format
+    let (tx_beast, _) = tokio::sync::broadcast::channel(100 * multiplier + 1);
+    if let Some(port) = options.beast_port {
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+        let tx_beast = tx_beast.clone();
+        tokio::spawn(async move {
+            if let Err(e) = beast::serve(listener, tx_beast).await {
+                error!("{}", e.to_string());
+            }
+        });
+    }
+    if let Some(address) = options.beast_push {
+        let tx_beast = tx_beast.clone();
+        tokio::spawn(async move {
+            if let Err(e) = beast::push(address, tx_beast, backoff).await {
+                error!("{}", e.to_string());
+            }
+        });
+    }
+
+    // Serve the decoded messages as SBS-1 records
+    let (tx_sbs, _) = tokio::sync::broadcast::channel(100 * multiplier + 1);
+    if let Some(port) = options.sbs_port {
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+        let tx_sbs = tx_sbs.clone();
+        tokio::spawn(async move {
+            if let Err(e) = sbs::serve(listener, tx_sbs).await {
+                error!("{}", e.to_string());
+            }
+        });
+    }
+
+    let app_dedup = app_dec.clone();
     tokio::spawn(async move {
         dedup::deduplicate_messages(
             rx,
             tx_dedup,
             options.deduplication.unwrap_or(450),
+            options.fix.unwrap_or(0),
+            app_dedup,
         )
         .await;
     });
@@ -475,6 +634,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         false => None,
     };
 
+    let default_filter = PositionFilter::default();
+    let filter = PositionFilter {
+        max_speed: options.max_speed.unwrap_or(default_filter.max_speed),
+        max_range: options.max_range,
+        min_nuc_p: options.min_nucp.unwrap_or(default_filter.min_nuc_p),
+        ..default_filter
+    };
+
+    let mut positions = PositionDecoder {
+        references,
+        aircraft: BTreeMap::new(),
+        update_reference,
+        filter,
+    };
+
     let mut first_msg = true;
     while let Some(mut msg) = rx_dedup.recv().await {
         if first_msg {
@@ -487,73 +661,96 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             first_msg = false;
         }
 
+        if tx_beast.receiver_count() > 0 {
+            if let Some(data) = beast::encode(&msg) {
+                let _ = tx_beast.send(data);
+            }
+        }
+
+        // Link Mode A/C replies to Mode S aircraft before filtering
+        correlator.push(&mut msg);
+
+        let mut meteo = None;
         if let Some(message) = &mut msg.message {
+            let metadata = &msg.metadata;
+            let timestamp = msg.timestamp;
             match &mut message.df {
-                ExtendedSquitterADSB(adsb) => match adsb.message {
-                    ME::BDS05(_) | ME::BDS06(_) => {
-                        let serial = msg
-                            .metadata
-                            .first()
-                            .map(|meta| meta.serial)
-                            .unwrap();
-                        let mut reference = references[&serial];
-
-                        decode_position(
-                            &mut adsb.message,
-                            msg.timestamp,
-                            &adsb.icao24,
-                            &mut aircraft,
-                            &mut reference,
-                            &update_reference,
-                        );
-
-                        // References may have been modified.
-                        // With static receivers, we don't care; for dynamic ones, we may
-                        // want to update the reference position.
-                        if options.update_position {
-                            for meta in &msg.metadata {
-                                let _ =
-                                    references.insert(meta.serial, reference);
-                            }
-                        }
+                ExtendedSquitterADSB(adsb) => positions.decode(
+                    Positioned::Squitter(&mut adsb.message),
+                    timestamp,
+                    &adsb.icao24,
+                    metadata,
+                ),
+                ExtendedSquitterTisB { cf, .. } => positions.decode(
+                    Positioned::Squitter(&mut cf.me),
+                    timestamp,
+                    &cf.aa,
+                    metadata,
+                ),
+                ExtendedSquitterMilitary {
+                    payload: MilitaryApplication::ExtendedSquitter(es),
+                    ..
+                } => positions.decode(
+                    Positioned::Squitter(&mut es.me),
+                    timestamp,
+                    &es.aa,
+                    metadata,
+                ),
+                CommBAltitudeReply { bds, ap, .. } => {
+                    if let Some(bds05) = &mut bds.bds05 {
+                        positions.decode(
+                            Positioned::CommB(bds05),
+                            timestamp,
+                            &(*ap).into(),
+                            metadata,
+                        )
                     }
-                    _ => {}
-                },
-                ExtendedSquitterTisB { cf, .. } => match cf.me {
-                    ME::BDS05(_) | ME::BDS06(_) => {
-                        let serial = msg
-                            .metadata
-                            .first()
-                            .map(|meta| meta.serial)
-                            .unwrap();
-
-                        let mut reference = references[&serial];
-
-                        decode_position(
-                            &mut cf.me,
-                            msg.timestamp,
-                            &cf.aa,
-                            &mut aircraft,
-                            &mut reference,
-                            &update_reference,
+                }
+                CommBIdentityReply { bds, ap, .. } => {
+                    if let Some(bds05) = &mut bds.bds05 {
+                        positions.decode(
+                            Positioned::CommB(bds05),
+                            timestamp,
+                            &(*ap).into(),
+                            metadata,
                         )
                     }
-                    _ => {}
-                },
+                }
                 _ => {}
             }
+            // Select between ambiguous BDS 5,0 and 6,0 Comm-B replies
+            inference.push(msg.timestamp, message);
+            // Estimate wind and temperature
+            meteo = derived.push(msg.timestamp, message);
         };
 
-        snapshot::update_snapshot(&app_dec, &mut msg, &aircraftdb).await;
+        snapshot::update_snapshot(&app_dec, &mut msg, meteo, &aircraftdb).await;
 
         let is_in = filters::Filters::is_in(&filters, &msg);
 
+        // Positions are resolved at this stage
+        let sbs_wanted = sbs_output || tx_sbs.receiver_count() > 0;
+        let record = match is_in && sbs_wanted {
+            true => sbs::Record::new(&msg).map(|r| format!("{r}\r\n")),
+            false => None,
+        };
+        if let Some(record) = &record {
+            if tx_sbs.receiver_count() > 0 {
+                let _ = tx_sbs.send(record.clone());
+            }
+            if sbs_output {
+                if let Some(file) = &mut file {
+                    file.write_all(record.as_bytes()).await?;
+                }
+            }
+        }
+
         if let Ok(json) = serde_json::to_string(&msg) {
             if options.verbose & is_in {
                 println!("{}", json);
             }
 
-            if is_in {
+            if is_in && !sbs_output {
                 if let Some(file) = &mut file {
                     file.write_all(json.as_bytes()).await?;
                     file.write_all("\n".as_bytes()).await?;
@@ -711,7 +908,7 @@ fn print_completions<G: Generator>(gen: G, cmd: &mut Command) {
 #[cfg(test)]
 mod tests {
 
-    use crate::Options;
+    use crate::{Options, OutputFormat};
 
     #[test]
     fn test_config() {
@@ -723,6 +920,7 @@ mod tests {
             expire = 1
             prevent_sleep = false
             update_position = false
+            output_format = "sbs"
 
             [[sources]]
             udp = "0.0.0.0:1234"
@@ -737,6 +935,7 @@ mod tests {
         .unwrap();
 
         assert!(options.interactive);
+        assert_eq!(options.output_format, Some(OutputFormat::Sbs));
         assert_eq!(options.sources.len(), 2);
     }
 }
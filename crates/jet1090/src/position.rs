@@ -0,0 +1,135 @@
+/**
+* Positions are decoded from extended squitters (ADS-B, TIS-B and military
+* DF19 messages) and from BDS 0,5 registers in Comm-B replies, with the
+* reference position of the sensor which received the message first.
+*
+* When the reference positions are updated (`update_position` option), the
+* reference of every sensor which received the message is updated, whatever
+* the origin of the position.
+*/
+use rs1090::decode::cpr::{
+    decode_airborne_position, decode_position, AircraftState, PositionFilter,
+    UpdateIf,
+};
+use rs1090::prelude::*;
+use std::collections::BTreeMap;
+
+/// A message carrying a position, or data related to the position
+pub enum Positioned<'a> {
+    /// The content of an extended squitter
+    Squitter(&'a mut ME),
+    /// An airborne position (BDS 0,5) in a Comm-B reply
+    CommB(&'a mut AirbornePosition),
+}
+
+/// The state needed to decode positions (see module documentation)
+pub struct PositionDecoder {
+    /// The reference position of each sensor, by serial number
+    pub references: BTreeMap<u64, Option<Position>>,
+    pub aircraft: BTreeMap<ICAO, AircraftState>,
+    pub update_reference: UpdateIf,
+    pub filter: PositionFilter,
+}
+
+impl PositionDecoder {
+    /// Decode the position in `message`, received at time `timestamp` by
+    /// the sensors in `metadata`
+    pub fn decode(
+        &mut self,
+        message: Positioned<'_>,
+        timestamp: f64,
+        icao24: &ICAO,
+        metadata: &[SensorMetadata],
+    ) {
+        let mut reference = metadata
+            .first()
+            .and_then(|meta| self.references.get(&meta.serial))
+            .copied()
+            .flatten();
+
+        match message {
+            Positioned::Squitter(me) => decode_position(
+                me,
+                timestamp,
+                icao24,
+                &mut self.aircraft,
+                &mut reference,
+                &self.update_reference,
+                &self.filter,
+            ),
+            Positioned::CommB(bds05) => decode_airborne_position(
+                bds05,
+                timestamp,
+                icao24,
+                &mut self.aircraft,
+                &mut reference,
+                &self.update_reference,
+                &self.filter,
+            ),
+        }
+
+        // References may have been modified: with static receivers, we
+        // don't care; for dynamic ones, we want to update them.
+        if self.update_reference.is_some() {
+            for meta in metadata {
+                self.references.insert(meta.serial, reference);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rs1090::decode::DF;
+
+    fn metadata(serial: u64) -> SensorMetadata {
+        SensorMetadata {
+            system_timestamp: 0.,
+            gnss_timestamp: None,
+            nanoseconds: None,
+            rssi: None,
+            serial,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_references() {
+        let reference = Position {
+            latitude: 43.6,
+            longitude: 1.4,
+        };
+        let mut positions = PositionDecoder {
+            references: BTreeMap::from([(1, Some(reference)), (2, None)]),
+            aircraft: BTreeMap::new(),
+            update_reference: Some(Box::new(|_| false)),
+            filter: PositionFilter::default(),
+        };
+        let frame = hex::decode("8d40058b58c901375147efd09357").unwrap();
+        let mut msg = Message::decode(&frame).unwrap();
+        let DF::ExtendedSquitterADSB(adsb) = &mut msg.df else {
+            unreachable!()
+        };
+
+        // No sensor: no reference, but no panic either
+        positions.decode(
+            Positioned::Squitter(&mut adsb.message),
+            0.,
+            &adsb.icao24,
+            &[],
+        );
+
+        // The reference of the first sensor is shared with the others
+        let ME::BDS05(bds05) = &mut adsb.message else {
+            unreachable!()
+        };
+        positions.decode(
+            Positioned::CommB(bds05),
+            1.,
+            &adsb.icao24,
+            &[metadata(1), metadata(2)],
+        );
+        assert_eq!(positions.references[&2], Some(reference));
+    }
+}
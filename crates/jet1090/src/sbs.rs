@@ -0,0 +1,358 @@
+/**
+* Conversion of decoded messages into SBS-1 (BaseStation) records, as served
+* by dump1090 on port 30003 and consumed by legacy tools (Virtual Radar
+* Server, PlanePlotter, etc.)
+*
+* Each record is a line of 22 comma separated fields:
+*
+* `MSG,<type>,1,1,<icao24>,1,<date>,<time>,<date>,<time>,<callsign>,<altitude>,<groundspeed>,<track>,<latitude>,<longitude>,<vertical rate>,<squawk>,<alert>,<emergency>,<spi>,<on ground>`
+*
+* with the following transmission types:
+*
+* 1. ES identification and category (BDS 0,8)
+* 2. ES surface position (BDS 0,6)
+* 3. ES airborne position (BDS 0,5)
+* 4. ES airborne velocity (BDS 0,9)
+* 5. Surveillance altitude reply (DF 4 and 20)
+* 6. Surveillance identity reply (DF 21 and 5)
+* 7. Air-to-air message (DF 0 and 16)
+* 8. All-call reply (DF 11)
+*
+* Positions are only filled in once they are resolved by `decode_position`.
+*/
+use std::fmt;
+use std::io;
+
+use chrono::prelude::*;
+use rs1090::decode::bds::bds05::SurveillanceStatus;
+use rs1090::decode::bds::bds09::AirborneVelocitySubType::GroundSpeedDecoding;
+use rs1090::decode::{
+    AC13Field, Capability, FlightStatus, IdentityCode, MilitaryApplication,
+};
+use rs1090::prelude::*;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+/// A SBS-1 record, empty fields are not relevant for the transmission type
+#[derive(Debug, Default, PartialEq)]
+pub struct Record {
+    pub transmission: u8,
+    pub icao24: u32,
+    /// The timestamp of the message (in s)
+    pub timestamp: f64,
+    pub callsign: Option<String>,
+    pub altitude: Option<u16>,
+    pub groundspeed: Option<f64>,
+    pub track: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub vertical_rate: Option<i16>,
+    pub squawk: Option<IdentityCode>,
+    pub alert: Option<bool>,
+    pub emergency: Option<bool>,
+    pub spi: Option<bool>,
+    pub on_ground: Option<bool>,
+}
+
+impl Record {
+    /// Convert a decoded message, None if there is no matching record
+    pub fn new(msg: &TimedMessage) -> Option<Self> {
+        let message = msg.message.as_ref()?;
+        let record = match &message.df {
+            ShortAirAirSurveillance { vs, ac, ap, .. }
+            | LongAirAirSurveillance { vs, ac, ap, .. } => Record {
+                transmission: 7,
+                icao24: ap.0,
+                altitude: altitude(ac),
+                on_ground: Some(*vs == 1),
+                ..Default::default()
+            },
+            SurveillanceAltitudeReply { fs, ac, ap, .. }
+            | CommBAltitudeReply { fs, ac, ap, .. } => Record {
+                transmission: 5,
+                icao24: ap.0,
+                altitude: altitude(ac),
+                ..Record::flight_status(fs)
+            },
+            SurveillanceIdentityReply { fs, id, ap, .. }
+            | CommBIdentityReply { fs, id, ap, .. } => Record {
+                transmission: 6,
+                icao24: ap.0,
+                squawk: Some(*id),
+                emergency: Some(is_emergency(id)),
+                ..Record::flight_status(fs)
+            },
+            AllCallReply {
+                capability, icao, ..
+            } => Record {
+                transmission: 8,
+                icao24: icao.0,
+                on_ground: match capability {
+                    Capability::AG_GROUND => Some(true),
+                    Capability::AG_AIRBORNE => Some(false),
+                    _ => None,
+                },
+                ..Default::default()
+            },
+            ExtendedSquitterADSB(adsb) => Record {
+                icao24: adsb.icao24.0,
+                ..Record::extended_squitter(&adsb.message)?
+            },
+            ExtendedSquitterTisB { cf, .. } => Record {
+                icao24: cf.aa.0,
+                ..Record::extended_squitter(&cf.me)?
+            },
+            ExtendedSquitterMilitary {
+                payload: MilitaryApplication::ExtendedSquitter(es),
+                ..
+            } => Record {
+                icao24: es.aa.0,
+                ..Record::extended_squitter(&es.me)?
+            },
+            _ => return None,
+        };
+        Some(Record {
+            timestamp: msg.timestamp,
+            ..record
+        })
+    }
+
+    /// The flags in surveillance replies
+    fn flight_status(fs: &FlightStatus) -> Self {
+        use FlightStatus::*;
+        let alert = matches!(
+            fs,
+            AlertNoSpiAirborne | AlertNoSpiOnGround | AlertSpiAirborneGround
+        );
+        let spi =
+            matches!(fs, AlertSpiAirborneGround | NoAlertSpiAirborneGround);
+        let on_ground = match fs {
+            NoAlertNoSpiOnGround | AlertNoSpiOnGround => Some(true),
+            NoAlertNoSpiAirborne | AlertNoSpiAirborne => Some(false),
+            _ => None,
+        };
+        Record {
+            alert: Some(alert),
+            spi: Some(spi),
+            on_ground,
+            ..Default::default()
+        }
+    }
+
+    /// The content of extended squitters (ADS-B, TIS-B or military)
+    fn extended_squitter(me: &ME) -> Option<Self> {
+        let record = match me {
+            ME::BDS08(bds08) if !bds08.callsign.contains('#') => Record {
+                transmission: 1,
+                callsign: Some(bds08.callsign.trim().to_string()),
+                ..Default::default()
+            },
+            ME::BDS06(bds06) => Record {
+                transmission: 2,
+                groundspeed: bds06.groundspeed,
+                track: bds06.track,
+                latitude: bds06.latitude,
+                longitude: bds06.longitude,
+                on_ground: Some(true),
+                ..Default::default()
+            },
+            ME::BDS05(bds05) => Record {
+                transmission: 3,
+                altitude: bds05.alt,
+                latitude: bds05.latitude,
+                longitude: bds05.longitude,
+                alert: Some(matches!(
+                    bds05.ss,
+                    SurveillanceStatus::PermanentAlert
+                        | SurveillanceStatus::TemporaryAlert
+                )),
+                emergency: None,
+                spi: Some(bds05.ss == SurveillanceStatus::SPICondition),
+                on_ground: Some(false),
+                ..Default::default()
+            },
+            ME::BDS09(bds09) => {
+                let (groundspeed, track) = match &bds09.velocity {
+                    GroundSpeedDecoding(spd) => {
+                        (Some(spd.groundspeed), Some(spd.track))
+                    }
+                    _ => (None, None),
+                };
+                Record {
+                    transmission: 4,
+                    groundspeed,
+                    track,
+                    vertical_rate: bds09.vertical_rate,
+                    ..Default::default()
+                }
+            }
+            _ => return None,
+        };
+        Some(record)
+    }
+}
+
+/// Squawk codes for hijacking, radio failure and emergency
+fn is_emergency(squawk: &IdentityCode) -> bool {
+    matches!(squawk.0, 0x7500 | 0x7600 | 0x7700)
+}
+
+/// The altitude in an AC field, None when not available (null field)
+fn altitude(ac: &AC13Field) -> Option<u16> {
+    (ac.0 != 0).then_some(ac.0)
+}
+
+/// Format an optional value, an empty field if None
+fn field<T: fmt::Display>(value: &Option<T>) -> String {
+    value.as_ref().map_or(String::new(), |v| v.to_string())
+}
+
+/// Flags are -1 (true) or 0 (false)
+fn flag(value: &Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "-1",
+        Some(false) => "0",
+        None => "",
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let generated = DateTime::from_timestamp(
+            self.timestamp.trunc() as i64,
+            (self.timestamp.fract() * 1e9) as u32,
+        )
+        .unwrap_or_default();
+        let logged = Utc::now();
+        let format = "%Y/%m/%d,%H:%M:%S%.3f";
+        write!(
+            f,
+            "MSG,{},1,1,{:06X},1,{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.transmission,
+            self.icao24,
+            generated.format(format),
+            logged.format(format),
+            field(&self.callsign),
+            field(&self.altitude),
+            field(&self.groundspeed.map(|v| v.round())),
+            field(&self.track.map(|v| v.round())),
+            field(&self.latitude.map(|v| format!("{v:.5}"))),
+            field(&self.longitude.map(|v| format!("{v:.5}"))),
+            field(&self.vertical_rate),
+            field(&self.squawk),
+            flag(&self.alert),
+            flag(&self.emergency),
+            flag(&self.spi),
+            flag(&self.on_ground),
+        )
+    }
+}
+
+/**
+ * Serve SBS-1 records to all clients connecting to the `listener`.
+ *
+ * Each client receives the records sent to `tx` after the connection; slow
+ * clients may miss records.
+ */
+pub async fn serve(
+    listener: TcpListener,
+    tx: broadcast::Sender<String>,
+) -> io::Result<()> {
+    loop {
+        let (mut socket, peer) = listener.accept().await?;
+        info!("New SBS client: {}", peer);
+        let mut rx = tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(record) => {
+                        if let Err(e) =
+                            socket.write_all(record.as_bytes()).await
+                        {
+                            info!("SBS client {} disconnected: {}", peer, e);
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("SBS client {} missed {} records", peer, n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timed_message(frame: &str) -> TimedMessage {
+        let frame = hex::decode(frame).unwrap();
+        TimedMessage {
+            timestamp: 1704067200.5,
+            message: Message::decode(&frame).ok(),
+            frame,
+            modeac: None,
+            metadata: vec![],
+            decode_time: None,
+            corrected_bits: None,
+        }
+    }
+
+    #[test]
+    fn test_identification() {
+        let msg = timed_message("8D406B902015A678D4D220AA4BDA");
+        let record = Record::new(&msg).unwrap();
+        assert_eq!(record.transmission, 1);
+        assert_eq!(record.callsign.as_deref(), Some("EZY85MH"));
+        let line = record.to_string();
+        assert!(line.starts_with("MSG,1,1,1,406B90,1,2024/01/01,00:00:00.500,"));
+        assert!(line.ends_with(",EZY85MH,,,,,,,,,,,"));
+        assert_eq!(line.split(',').count(), 22);
+    }
+
+    #[test]
+    fn test_velocity() {
+        let msg = timed_message("8D485020994409940838175B284F");
+        let record = Record::new(&msg).unwrap();
+        assert_eq!(record.transmission, 4);
+        assert_eq!(record.vertical_rate, Some(-832));
+        let line = record.to_string();
+        assert!(line.ends_with(",,,159,183,,,-832,,,,,"));
+    }
+
+    #[test]
+    fn test_military() {
+        let msg = timed_message("983c4b2658c901375147ef514490");
+        let record = Record::new(&msg).unwrap();
+        assert_eq!(record.transmission, 3);
+        assert_eq!(record.icao24, 0x3c4b26);
+        assert_eq!(record.altitude, Some(39000));
+        // No emergency information in airborne positions
+        assert_eq!(record.emergency, None);
+        assert_eq!(record.to_string().split(',').nth(19), Some(""));
+    }
+
+    #[test]
+    fn test_surveillance() {
+        let msg = timed_message("28001a1a0de0a1");
+        let record = Record::new(&msg).unwrap();
+        assert_eq!(record.transmission, 6);
+        assert!(record.to_string().ends_with(",,,,,,,,3611,0,0,0,0"));
+
+        let msg = timed_message("20001838ca3804");
+        let record = Record::new(&msg).unwrap();
+        assert_eq!(record.transmission, 5);
+        assert_eq!(record.squawk, None);
+        assert_eq!(record.on_ground, Some(false));
+
+        // Altitude not available
+        let msg = timed_message("20000000ca3804");
+        let record = Record::new(&msg).unwrap();
+        assert_eq!(record.altitude, None);
+        assert_eq!(record.to_string().split(',').nth(11), Some(""));
+    }
+}
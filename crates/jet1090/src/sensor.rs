@@ -3,6 +3,7 @@ use rs1090::prelude::*;
 #[cfg(feature = "sero")]
 use rs1090::source::sero;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tracing::debug;
 
 use crate::source::{Address, Source};
@@ -24,6 +25,12 @@ pub struct Sensor {
     pub aircraft_count: u64,
     /// The timestamp for the last seen message
     pub last_timestamp: u64,
+    /// How many frames could not be decoded, by reason (see `decode::Error`)
+    pub rejected: BTreeMap<String, u64>,
+    /// The state of the connection (for TCP, UDP and WebSocket sources)
+    pub connection: Option<beast::ConnectionState>,
+    /// The last status reported by the receiver (Beast 0x34 frames)
+    pub status: Option<beast::ReceiverStatus>,
 }
 
 /**
@@ -34,6 +41,7 @@ pub async fn sensors(value: &Source) -> Vec<Sensor> {
         Address::Tcp(_)
         | Address::Udp(_)
         | Address::Websocket(_)
+        | Address::Avr(_)
         | Address::Rtlsdr(_) => {
             vec![Sensor {
                 serial: value.serial(),
@@ -42,6 +50,9 @@ pub async fn sensors(value: &Source) -> Vec<Sensor> {
                 altitude: value.altitude,
                 aircraft_count: 0,
                 last_timestamp: 0,
+                rejected: BTreeMap::new(),
+                connection: None,
+                status: None,
             }]
         }
         Address::Sero(params) => {
@@ -69,6 +80,9 @@ pub async fn sensors(value: &Source) -> Vec<Sensor> {
                         name: Some(elt.alias.to_string()),
                         aircraft_count: 0,
                         last_timestamp: 0,
+                        rejected: BTreeMap::new(),
+                        connection: None,
+                        status: None,
                     })
                     .collect()
             }
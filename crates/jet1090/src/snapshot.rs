@@ -4,13 +4,22 @@ use rs1090::decode::bds::bds09::AirborneVelocitySubType::{
     AirspeedSubsonic, GroundSpeedDecoding,
 };
 use rs1090::decode::bds::bds09::AirspeedType::{IAS, TAS};
-use rs1090::decode::{IdentityCode, SensorMetadata};
+use rs1090::decode::bds::bds53::AirReferencedStateVector;
+use rs1090::decode::bds::bds65::{ADSBVersionAirborne, ADSBVersionSurface};
+use rs1090::decode::derived::Meteo;
+use rs1090::decode::integrity::Integrity;
+use rs1090::decode::interrogator::{interrogator, InterrogatorCode};
+use rs1090::decode::{IdentityCode, MilitaryApplication, SensorMetadata};
 use rs1090::prelude::*;
 use serde::Serialize;
 use tokio::sync::Mutex;
 
 use crate::{aircraftdb, Jet1090};
 
+/// Interrogators are no longer reported as active after this duration (in
+/// seconds), i.e. after several rotations of the radar antenna
+const INTERROGATOR_TIMEOUT: u64 = 60;
+
 /**
  * A state vector with the most up-to-date information about an aircraft
  */
@@ -54,8 +63,26 @@ pub struct Snapshot {
     pub roll: Option<f64>,
     /// The magnetic heading of the aircraft in degrees with respect to the magnetic North
     pub heading: Option<f64>,
+    /// The true heading of the aircraft in degrees, corrected with the magnetic declination
+    pub true_heading: Option<f64>,
     /// The NAC position indicator, for uncertainty
     pub nacp: Option<u8>,
+    /// The ADS-B version of the transponder
+    pub version: Option<u8>,
+    /// The Navigation Integrity Category (NIC) of the latest position
+    pub nic: Option<u8>,
+    /// The radius of containment of the latest position, in meters
+    pub rc: Option<f64>,
+    /// The NAC velocity indicator, for uncertainty
+    pub nacv: Option<u8>,
+    /// The Surveillance Integrity Level (SIL)
+    pub sil: Option<u8>,
+    /// Wind and temperature, estimated or reported in BDS 4,4
+    #[serde(flatten)]
+    pub meteo: Meteo,
+    /// Codes of the radars actively interrogating the aircraft, with the
+    /// timestamp of the last reply to each of them
+    pub interrogators: BTreeMap<InterrogatorCode, u64>,
     /// Number of messages received for the aircraft
     pub count: usize,
     /// Metadata information from the sensors seeing the aircraft
@@ -114,7 +141,15 @@ impl StateVectors {
             mach: None,
             roll: None,
             heading: None,
+            true_heading: None,
             nacp: None,
+            version: None,
+            nic: None,
+            rc: None,
+            nacv: None,
+            sil: None,
+            meteo: Meteo::default(),
+            interrogators: BTreeMap::new(),
             count: 0,
             metadata: vec![],
         };
@@ -134,15 +169,38 @@ fn icao24(msg: &Message) -> Option<String> {
         LongAirAirSurveillance { ap, .. } => Some(ap.to_string()),
         ExtendedSquitterADSB(ADSB { icao24, .. }) => Some(icao24.to_string()),
         ExtendedSquitterTisB { cf, .. } => Some(cf.aa.to_string()),
+        ExtendedSquitterMilitary {
+            payload: MilitaryApplication::ExtendedSquitter(es),
+            ..
+        } => Some(es.aa.to_string()),
         CommBAltitudeReply { ap, .. } => Some(ap.to_string()),
         CommBIdentityReply { ap, .. } => Some(ap.to_string()),
         _ => None,
     }
 }
 
+fn update_interrogators(snapshot: &mut Snapshot, message: &Message) {
+    let Ok(icao24) = snapshot.icao24.parse::<ICAO>() else {
+        return;
+    };
+    match (&message.df, interrogator(message, &icao24)) {
+        // Acquisition squitters cannot be told apart from replies to II=0
+        (AllCallReply { .. }, Some(InterrogatorCode::II(0))) => {}
+        (_, Some(code)) => {
+            snapshot.interrogators.insert(code, snapshot.lastseen);
+        }
+        _ => {}
+    }
+    let lastseen = snapshot.lastseen;
+    snapshot
+        .interrogators
+        .retain(|_, ts| lastseen.saturating_sub(*ts) <= INTERROGATOR_TIMEOUT);
+}
+
 pub async fn update_snapshot(
     states: &Mutex<Jet1090>,
     msg: &mut TimedMessage,
+    meteo: Option<Meteo>,
     aircraftdb: &BTreeMap<String, aircraftdb::Aircraft>,
 ) {
     if let TimedMessage {
@@ -164,6 +222,15 @@ pub async fn update_snapshot(
                     ));
             aircraft.cur.lastseen = *timestamp as u64;
             aircraft.cur.metadata = metadata.to_vec();
+            if let Some(meteo) = meteo {
+                aircraft.cur.meteo = meteo;
+            }
+
+            // Only trust interrogator codes for aircraft seen before, as
+            // corrupted replies result in random addresses
+            if aircraft.cur.count > 0 {
+                update_interrogators(&mut aircraft.cur, message);
+            }
             aircraft.cur.count += 1;
 
             match &mut message.df {
@@ -173,78 +240,13 @@ pub async fn update_snapshot(
                 SurveillanceAltitudeReply { ac, .. } => {
                     aircraft.cur.altitude = Some(ac.0);
                 }
-                ExtendedSquitterADSB(adsb) => match &adsb.message {
-                    ME::BDS05(bds05) => {
-                        aircraft.cur.latitude = bds05.latitude;
-                        aircraft.cur.longitude = bds05.longitude;
-                        aircraft.cur.altitude = bds05.alt;
-                    }
-                    ME::BDS06(bds06) => {
-                        aircraft.cur.latitude = bds06.latitude;
-                        aircraft.cur.longitude = bds06.longitude;
-                        aircraft.cur.track = bds06.track;
-                        aircraft.cur.groundspeed = bds06.groundspeed;
-                        aircraft.cur.altitude = None;
-                    }
-                    ME::BDS08(bds08) => {
-                        if !bds08.callsign.contains("#") {
-                            aircraft.cur.callsign =
-                                Some(bds08.callsign.to_string())
-                        }
-                    }
-                    ME::BDS09(bds09) => {
-                        aircraft.cur.vertical_rate = bds09.vertical_rate;
-                        match &bds09.velocity {
-                            GroundSpeedDecoding(spd) => {
-                                aircraft.cur.groundspeed =
-                                    Some(spd.groundspeed);
-                                aircraft.cur.track = Some(spd.track)
-                            }
-                            AirspeedSubsonic(spd) => {
-                                match spd.airspeed_type {
-                                    IAS => aircraft.cur.ias = spd.airspeed,
-                                    TAS => aircraft.cur.tas = spd.airspeed,
-                                }
-                                aircraft.cur.heading = spd.heading;
-                            }
-                            _ => {}
-                        }
-                    }
-                    ME::BDS61(bds61) => {
-                        aircraft.cur.squawk = Some(bds61.squawk);
-                    }
-                    ME::BDS62(bds62) => {
-                        aircraft.cur.selected_altitude =
-                            bds62.selected_altitude;
-                        aircraft.cur.nacp = Some(bds62.nac_p);
-                    }
-                    ME::BDS65(bds65) => match bds65 {
-                        AircraftOperationStatus::Airborne(st) => {
-                            match st.version {
-                                rs1090::decode::bds::bds65::ADSBVersionAirborne::DOC9871AppendixB(v) => {
-                                    aircraft.cur.nacp = Some(v.nac_p)
-                                }
-                                rs1090::decode::bds::bds65::ADSBVersionAirborne::DOC9871AppendixC(v) => {
-                                    aircraft.cur.nacp = Some(v.nac_p)
-                                }
-                                _ => {}
-                            }
-                        }
-                        AircraftOperationStatus::Surface(st) => {
-                            match st.version {
-                                rs1090::decode::bds::bds65::ADSBVersionSurface::DOC9871AppendixB(v) => {
-                                    aircraft.cur.nacp = Some(v.nac_p)
-                                }
-                                rs1090::decode::bds::bds65::ADSBVersionSurface::DOC9871AppendixC(v) => {
-                                    aircraft.cur.nacp = Some(v.nac_p)
-                                }
-                                _ => {}
-                            }
-                        }
-                        _ => {}
-                    },
-                    _ => {}
-                },
+                ExtendedSquitterADSB(adsb) => {
+                    update_adsb(&mut aircraft.cur, &adsb.message)
+                }
+                ExtendedSquitterMilitary {
+                    payload: MilitaryApplication::ExtendedSquitter(es),
+                    ..
+                } => update_adsb(&mut aircraft.cur, &es.me),
                 ExtendedSquitterTisB { cf, .. } => {
                     aircraft.cur.typecode = Some("GRND".to_string());
                     match &cf.me {
@@ -268,11 +270,6 @@ pub async fn update_snapshot(
                     }
                 }
                 CommBAltitudeReply { bds, .. } => {
-                    // Invalidate data if marked as both BDS50 and BDS60
-                    if let (Some(_), Some(_)) = (&bds.bds50, &bds.bds60) {
-                        bds.bds50 = None;
-                        bds.bds60 = None
-                    }
                     if let Some(bds20) = &bds.bds20 {
                         if !bds20.callsign.contains("#") {
                             aircraft.cur.callsign =
@@ -294,18 +291,41 @@ pub async fn update_snapshot(
                         aircraft.cur.ias = bds60.indicated_airspeed;
                         aircraft.cur.mach = bds60.mach_number;
                         aircraft.cur.heading = bds60.magnetic_heading;
+                        aircraft.cur.true_heading = bds60.true_heading;
                         if bds60.inertial_vertical_velocity.is_some() {
                             aircraft.cur.vertical_rate =
                                 bds60.inertial_vertical_velocity;
                         }
                     }
+                    if let Some(bds05) = &bds.bds05 {
+                        if bds05.latitude.is_some() {
+                            aircraft.cur.latitude = bds05.latitude;
+                            aircraft.cur.longitude = bds05.longitude;
+                        }
+                    }
+                    if let Some(bds51) = &bds.bds51 {
+                        if let (Some(lat), Some(lon)) =
+                            (bds51.latitude, bds51.longitude)
+                        {
+                            aircraft.cur.latitude = Some(lat);
+                            aircraft.cur.longitude = Some(lon);
+                        }
+                    }
+                    if let Some(bds52) = &bds.bds52 {
+                        if let (Some(lat), Some(lon)) =
+                            (aircraft.cur.latitude, aircraft.cur.longitude)
+                        {
+                            if let Some((lat, lon)) = bds52.position(lat, lon) {
+                                aircraft.cur.latitude = Some(lat);
+                                aircraft.cur.longitude = Some(lon);
+                            }
+                        }
+                    }
+                    if let Some(bds53) = &bds.bds53 {
+                        update_airspeeds(&mut aircraft.cur, bds53);
+                    }
                 }
                 CommBIdentityReply { bds, .. } => {
-                    // Invalidate data if marked as both BDS50 and BDS60
-                    if let (Some(_), Some(_)) = (&bds.bds50, &bds.bds60) {
-                        bds.bds50 = None;
-                        bds.bds60 = None
-                    }
                     if let Some(bds20) = &bds.bds20 {
                         if !bds20.callsign.contains("#") {
                             aircraft.cur.callsign =
@@ -327,11 +347,21 @@ pub async fn update_snapshot(
                         aircraft.cur.ias = bds60.indicated_airspeed;
                         aircraft.cur.mach = bds60.mach_number;
                         aircraft.cur.heading = bds60.magnetic_heading;
+                        aircraft.cur.true_heading = bds60.true_heading;
                         if bds60.inertial_vertical_velocity.is_some() {
                             aircraft.cur.vertical_rate =
                                 bds60.inertial_vertical_velocity;
                         }
                     }
+                    if let Some(bds05) = &bds.bds05 {
+                        if bds05.latitude.is_some() {
+                            aircraft.cur.latitude = bds05.latitude;
+                            aircraft.cur.longitude = bds05.longitude;
+                        }
+                    }
+                    if let Some(bds53) = &bds.bds53 {
+                        update_airspeeds(&mut aircraft.cur, bds53);
+                    }
                 }
                 _ => {}
             };
@@ -339,6 +369,108 @@ pub async fn update_snapshot(
     }
 }
 
+/// Update the state vector with an ADS-B message, from a DF17/18 extended
+/// squitter or a DF19 military extended squitter
+fn update_adsb(snapshot: &mut Snapshot, me: &ME) {
+    match me {
+        ME::BDS05(bds05) => {
+            snapshot.latitude = bds05.latitude;
+            snapshot.longitude = bds05.longitude;
+            snapshot.altitude = bds05.alt;
+            update_integrity(snapshot, &bds05.integrity);
+        }
+        ME::BDS06(bds06) => {
+            snapshot.latitude = bds06.latitude;
+            snapshot.longitude = bds06.longitude;
+            snapshot.track = bds06.track;
+            snapshot.groundspeed = bds06.groundspeed;
+            snapshot.altitude = None;
+            update_integrity(snapshot, &bds06.integrity);
+        }
+        ME::BDS08(bds08) if !bds08.callsign.contains("#") => {
+            snapshot.callsign = Some(bds08.callsign.to_string())
+        }
+        ME::BDS09(bds09) => {
+            snapshot.vertical_rate = bds09.vertical_rate;
+            match &bds09.velocity {
+                GroundSpeedDecoding(spd) => {
+                    snapshot.groundspeed = Some(spd.groundspeed);
+                    snapshot.track = Some(spd.track)
+                }
+                AirspeedSubsonic(spd) => {
+                    match spd.airspeed_type {
+                        IAS => snapshot.ias = spd.airspeed,
+                        TAS => snapshot.tas = spd.airspeed,
+                    }
+                    snapshot.heading = spd.heading;
+                    snapshot.true_heading = spd.true_heading;
+                }
+                _ => {}
+            }
+        }
+        ME::BDS61(bds61) => {
+            if let Some(squawk) = bds61.squawk() {
+                snapshot.squawk = Some(squawk);
+            }
+        }
+        ME::BDS62(bds62) => {
+            snapshot.selected_altitude = bds62.selected_altitude;
+            snapshot.nacp = Some(bds62.nac_p);
+        }
+        ME::BDS65(bds65) => match bds65 {
+            AircraftOperationStatus::Airborne(st) => match st.version {
+                ADSBVersionAirborne::DOC9871AppendixB(v) => {
+                    snapshot.nacp = Some(v.nac_p)
+                }
+                ADSBVersionAirborne::DOC9871AppendixC(v) => {
+                    snapshot.nacp = Some(v.nac_p)
+                }
+                _ => {}
+            },
+            AircraftOperationStatus::Surface(st) => match st.version {
+                ADSBVersionSurface::DOC9871AppendixB(v) => {
+                    snapshot.nacp = Some(v.nac_p)
+                }
+                ADSBVersionSurface::DOC9871AppendixC(v) => {
+                    snapshot.nacp = Some(v.nac_p)
+                }
+                _ => {}
+            },
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Air-referenced state vectors (BDS 5,3) are an alternative source of
+/// airspeeds for aircraft not equipped with ADS-B
+fn update_airspeeds(snapshot: &mut Snapshot, bds53: &AirReferencedStateVector) {
+    if bds53.indicated_airspeed.is_some() {
+        snapshot.ias = bds53.indicated_airspeed;
+    }
+    if bds53.mach_number.is_some() {
+        snapshot.mach = bds53.mach_number;
+    }
+    if let Some(tas) = bds53.true_airspeed {
+        snapshot.tas = Some(tas.round() as u16);
+    }
+    if bds53.magnetic_heading.is_some() {
+        snapshot.heading = bds53.magnetic_heading;
+        snapshot.true_heading = bds53.true_heading;
+    }
+}
+
+/// The integrity of the latest ADS-B position, given the ADS-B version
+fn update_integrity(snapshot: &mut Snapshot, integrity: &Option<Integrity>) {
+    if let Some(integrity) = integrity {
+        snapshot.version = Some(integrity.version);
+        snapshot.nic = integrity.nic;
+        snapshot.rc = integrity.rc;
+        snapshot.nacv = integrity.nac_v;
+        snapshot.sil = integrity.sil;
+    }
+}
+
 pub async fn store_history(
     states: &Mutex<Jet1090>,
     msg: TimedMessage,
@@ -349,6 +481,7 @@ pub async fn store_history(
         message: Some(message),
         metadata,
         decode_time,
+        corrected_bits,
         ..
     } = msg
     {
@@ -366,14 +499,20 @@ pub async fn store_history(
             match message.df {
                 ExtendedSquitterADSB(_)
                 | ExtendedSquitterTisB { .. }
+                | ExtendedSquitterMilitary {
+                    payload: MilitaryApplication::ExtendedSquitter(_),
+                    ..
+                }
                 | CommBAltitudeReply { .. }
                 | CommBIdentityReply { .. } => {
                     aircraft.hist.push(TimedMessage {
                         timestamp,
                         frame: vec![],
                         message: Some(message),
+                        modeac: None,
                         metadata,
                         decode_time,
+                        corrected_bits,
                     })
                 }
                 _ => {}
@@ -24,6 +24,8 @@ use url::Url;
 *   setting on Radarcape devices; otherwise, see socat.
 * - When the Beast format is sent as UDP, it can be dispatched again as a
 *   websocket service: see wsbroad.
+* - Some receivers and tools (rtl_adsb, dump1090 with `--net-ro-port`, usually
+*   on port 30002, modesdeco) only emit the AVR text format.
 *
 * ## Example code for setting things up
 *
@@ -50,6 +52,8 @@ pub enum Address {
     Udp(String),
     /// Address to a websocket feed, e.g. `ws://localhost:9876/1234`
     Websocket(String),
+    /// Address to a TCP feed for AVR format (typically port 30002), e.g. `avr://localhost:30002`
+    Avr(String),
     /// A RTL-SDR dongle (require feature `rtlsdr`): the parameter can be empty, or use other specifiers, e.g. `rtlsdr://serial=00000001`
     Rtlsdr(Option<String>),
     /// A token-based access to Sero Systems (require feature `sero`).
@@ -115,6 +119,11 @@ impl FromStr for Source {
                 url.host_str().unwrap_or("0.0.0.0"),
                 url.port_or_known_default().unwrap()
             )),
+            "avr" => Address::Avr(format!(
+                "{}:{}",
+                url.host_str().unwrap_or("0.0.0.0"),
+                url.port().unwrap_or(30002)
+            )),
             "rtlsdr" => Address::Rtlsdr(url.host_str().map(|s| s.to_string())),
             "ws" => Address::Websocket(format!(
                 "ws://{}:{}/{}",
@@ -146,6 +155,7 @@ impl Source {
             Address::Tcp(name) => build_serial(name),
             Address::Udp(name) => build_serial(name),
             Address::Websocket(name) => build_serial(name),
+            Address::Avr(name) => build_serial(name),
             Address::Rtlsdr(reference) => {
                 let name = reference.clone().unwrap_or("rtlsdr".to_string());
                 build_serial(&name)
@@ -165,6 +175,9 @@ impl Source {
         tx: Sender<TimedMessage>,
         serial: u64,
         name: Option<String>,
+        backoff: beast::Backoff,
+        state: Sender<(u64, beast::ConnectionState)>,
+        status: Sender<(u64, beast::ReceiverStatus)>,
     ) {
         match &self.address {
             Address::Rtlsdr(args) => {
@@ -189,6 +202,20 @@ impl Source {
                     sero::receiver(sero::SeroClient::from(sero), tx).await
                 }
             }
+            Address::Avr(address) => {
+                if let Err(e) = avr::receiver(
+                    address.to_owned(),
+                    tx,
+                    serial,
+                    name,
+                    backoff,
+                    Some(state),
+                )
+                .await
+                {
+                    error!("{}", e.to_string());
+                }
+            }
             _ => {
                 let server_address = match &self.address {
                     Address::Tcp(s) => beast::BeastSource::Tcp(s.to_owned()),
@@ -198,8 +225,16 @@ impl Source {
                     }
                     _ => unreachable!(),
                 };
-                if let Err(e) =
-                    beast::receiver(server_address, tx, serial, name).await
+                if let Err(e) = beast::receiver(
+                    server_address,
+                    tx,
+                    serial,
+                    name,
+                    backoff,
+                    Some(state),
+                    Some(status),
+                )
+                .await
                 {
                     error!("{}", e.to_string());
                 }
@@ -300,6 +335,18 @@ mod test {
             assert_eq!(pos.longitude, 1.367263);
         }
 
+        let source = Source::from_str("avr://localhost?LFBO");
+        assert!(source.is_ok());
+        if let Ok(Source {
+            address,
+            reference: Some(pos),
+            ..
+        }) = source
+        {
+            assert_eq!(address, Address::Avr("localhost:30002".to_string()));
+            assert_eq!(pos.latitude, 43.628101);
+        }
+
         let source = Source::from_str("ws://1.2.3.4:4003/get?LFBO");
         assert!(source.is_ok());
         if let Ok(Source {
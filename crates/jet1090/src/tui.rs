@@ -56,10 +56,8 @@ impl EventHandler {
                     match maybe_event {
                       Some(Ok(evt)) => {
                         match evt {
-                          crossterm::event::Event::Key(key) => {
-                            if key.kind == crossterm::event::KeyEventKind::Press {
-                              tx.send(Event::Key(key)).unwrap();
-                            }
+                          crossterm::event::Event::Key(key) if key.kind == crossterm::event::KeyEventKind::Press => {
+                            tx.send(Event::Key(key)).unwrap();
                           },
                           crossterm::event::Event::Resize(col,_) => {width = col},
                           crossterm::event::Event::Mouse(event) => {
@@ -94,8 +92,9 @@ impl EventHandler {
     }
 
     pub async fn next(&mut self) -> Result<Event, io::Error> {
-        self.rx.recv().await.ok_or_else(|| {
-            io::Error::new(io::ErrorKind::Other, "Unable to get event")
-        })
+        self.rx
+            .recv()
+            .await
+            .ok_or_else(|| io::Error::other("Unable to get event"))
     }
 }
@@ -12,10 +12,14 @@ use rs1090::decode::bds::bds20::AircraftIdentification;
 use rs1090::decode::bds::bds21::AircraftAndAirlineRegistrationMarkings;
 use rs1090::decode::bds::bds30::ACASResolutionAdvisory;
 use rs1090::decode::bds::bds40::SelectedVerticalIntention;
+use rs1090::decode::bds::bds41::NextWaypointIdentifier;
+use rs1090::decode::bds::bds42::NextWaypointPosition;
+use rs1090::decode::bds::bds43::NextWaypointInformation;
 use rs1090::decode::bds::bds44::MeteorologicalRoutineAirReport;
 use rs1090::decode::bds::bds45::MeteorologicalHazardReport;
 use rs1090::decode::bds::bds50::TrackAndTurnReport;
 use rs1090::decode::bds::bds60::HeadingAndSpeedReport;
+use rs1090::decode::Error;
 use rs1090::prelude::*;
 use utils::set_panic_hook;
 use wasm_bindgen::prelude::*;
@@ -26,7 +30,13 @@ pub fn run() -> Result<(), JsValue> {
     Ok(())
 }
 
-struct DecodeError(DekuError);
+struct DecodeError(Error);
+
+impl DecodeError {
+    fn hypothesis(bds: &'static str, error: DekuError) -> Self {
+        DecodeError(Error::hypothesis(bds, error))
+    }
+}
 
 impl From<DecodeError> for JsError {
     fn from(error: DecodeError) -> Self {
@@ -37,7 +47,7 @@ impl From<DecodeError> for JsError {
 #[wasm_bindgen]
 pub fn decode(msg: &str) -> Result<JsValue, JsError> {
     let bytes = hex::decode(msg)?;
-    match Message::try_from(bytes.as_slice()) {
+    match Message::decode(&bytes) {
         Ok(msg) => {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(Object::from_entries(&map_result).unwrap().into())
@@ -56,14 +66,15 @@ pub fn decode_bds05(msg: &str) -> Result<JsValue, JsError> {
                 let map_result = serde_wasm_bindgen::to_value(&msg)?;
                 Ok(map_result)
             }
-            Err(e) => Err(DecodeError(e).into()),
+            Err(e) => Err(DecodeError::hypothesis("05", e).into()),
         }
     } else {
-        let msg = format!(
-            "Invalid typecode {} for BDS 0,5 (9 to 18 or 20 to 22)",
-            tc
-        );
-        Err(DecodeError(DekuError::Assertion(msg.into())).into())
+        let reason = Error::UnknownTypecode { tc, subtype: None };
+        Err(DecodeError(Error::Hypothesis {
+            bds: "05",
+            reason: Box::new(reason),
+        })
+        .into())
     }
 }
 
@@ -75,7 +86,7 @@ pub fn decode_bds10(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("10", e).into()),
     }
 }
 
@@ -87,7 +98,7 @@ pub fn decode_bds17(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("17", e).into()),
     }
 }
 
@@ -99,7 +110,7 @@ pub fn decode_bds18(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("18", e).into()),
     }
 }
 
@@ -111,7 +122,7 @@ pub fn decode_bds19(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("19", e).into()),
     }
 }
 
@@ -123,7 +134,7 @@ pub fn decode_bds20(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("20", e).into()),
     }
 }
 
@@ -135,7 +146,7 @@ pub fn decode_bds21(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("21", e).into()),
     }
 }
 
@@ -147,7 +158,7 @@ pub fn decode_bds30(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("30", e).into()),
     }
 }
 
@@ -159,7 +170,43 @@ pub fn decode_bds40(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("40", e).into()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn decode_bds41(msg: &str) -> Result<JsValue, JsError> {
+    let bytes = hex::decode(msg)?;
+    match NextWaypointIdentifier::from_bytes((&bytes[4..], 0)) {
+        Ok((_, msg)) => {
+            let map_result = serde_wasm_bindgen::to_value(&msg)?;
+            Ok(map_result)
+        }
+        Err(e) => Err(DecodeError::hypothesis("41", e).into()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn decode_bds42(msg: &str) -> Result<JsValue, JsError> {
+    let bytes = hex::decode(msg)?;
+    match NextWaypointPosition::from_bytes((&bytes[4..], 0)) {
+        Ok((_, msg)) => {
+            let map_result = serde_wasm_bindgen::to_value(&msg)?;
+            Ok(map_result)
+        }
+        Err(e) => Err(DecodeError::hypothesis("42", e).into()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn decode_bds43(msg: &str) -> Result<JsValue, JsError> {
+    let bytes = hex::decode(msg)?;
+    match NextWaypointInformation::from_bytes((&bytes[4..], 0)) {
+        Ok((_, msg)) => {
+            let map_result = serde_wasm_bindgen::to_value(&msg)?;
+            Ok(map_result)
+        }
+        Err(e) => Err(DecodeError::hypothesis("43", e).into()),
     }
 }
 
@@ -171,7 +218,7 @@ pub fn decode_bds44(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("44", e).into()),
     }
 }
 
@@ -183,7 +230,7 @@ pub fn decode_bds45(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("45", e).into()),
     }
 }
 
@@ -195,7 +242,7 @@ pub fn decode_bds50(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("50", e).into()),
     }
 }
 
@@ -207,7 +254,7 @@ pub fn decode_bds60(msg: &str) -> Result<JsValue, JsError> {
             let map_result = serde_wasm_bindgen::to_value(&msg)?;
             Ok(map_result)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("60", e).into()),
     }
 }
 
@@ -223,15 +270,19 @@ pub fn decode_bds65(msg: &str) -> Result<JsValue, JsError> {
                     let map_result = serde_wasm_bindgen::to_value(&msg)?;
                     Ok(map_result)
                 }
-                Err(e) => Err(DecodeError(e).into()),
+                Err(e) => Err(DecodeError::hypothesis("65", e).into()),
             }
         }
         _ => {
-            let msg = format!(
-                "Invalid typecode {} (31) or category {} (0 or 1) for BDS 6,5",
-                tc, enum_id
-            );
-            Err(DecodeError(DekuError::Assertion(msg.into())).into())
+            let reason = Error::UnknownTypecode {
+                tc,
+                subtype: (tc == 31).then_some(enum_id),
+            };
+            Err(DecodeError(Error::Hypothesis {
+                bds: "65",
+                reason: Box::new(reason),
+            })
+            .into())
         }
     }
 }
@@ -1,5 +1,5 @@
 use rayon::prelude::*;
-use rs1090::decode::cpr::{decode_positions, Position};
+use rs1090::decode::cpr::{decode_positions, Position, PositionFilter};
 use rs1090::prelude::*;
 
 use std::env;
@@ -69,8 +69,10 @@ fn main() -> io::Result<()> {
                     timestamp,
                     frame: bytes,
                     message: Some(msg),
+                    modeac: None,
                     metadata: vec![],
                     decode_time: None,
+                    corrected_bits: None,
                 });
             }
             res
@@ -80,7 +82,12 @@ fn main() -> io::Result<()> {
 
     // println!("{} messages processed", res.len());
 
-    decode_positions(&mut res, Some(reference), &None);
+    decode_positions(
+        &mut res,
+        Some(reference),
+        &None,
+        &PositionFilter::default(),
+    );
 
     println!("{}", serde_json::to_string(&res).unwrap());
     Ok(())
@@ -1,3 +1,4 @@
 pub mod airports;
 pub mod patterns;
 pub mod tail;
+pub mod wmm;
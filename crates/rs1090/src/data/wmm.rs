@@ -0,0 +1,273 @@
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+/**
+ * The World Magnetic Model (WMM), used to convert magnetic headings into
+ * true headings.
+ *
+ * The model is a spherical harmonic expansion (up to degree 12) of the main
+ * geomagnetic field, with coefficients published by NOAA and the British
+ * Geological Survey in the `WMM.COF` file. Each model is valid for five
+ * years after its epoch: a warning is logged when dates outside this range
+ * are requested, as the declination is then less accurate.
+ */
+pub struct MagneticModel {
+    /// The reference epoch of the model (decimal year)
+    pub epoch: f64,
+    /// Gauss coefficients g(n, m) and h(n, m), in nT
+    g: [[f64; DEGREE + 1]; DEGREE + 1],
+    h: [[f64; DEGREE + 1]; DEGREE + 1],
+    /// Secular variation of the coefficients, in nT/year
+    g_dot: [[f64; DEGREE + 1]; DEGREE + 1],
+    h_dot: [[f64; DEGREE + 1]; DEGREE + 1],
+}
+
+/// The maximum degree of the spherical harmonic expansion
+const DEGREE: usize = 12;
+/// The number of years a model is valid for, after its epoch
+pub const VALIDITY: f64 = 5.;
+
+/// The geomagnetic reference radius (km)
+const RADIUS: f64 = 6371.2;
+/// WGS84 semi-major axis (km)
+const WGS84_A: f64 = 6378.137;
+/// WGS84 flattening
+const WGS84_F: f64 = 1. / 298.257223563;
+
+const WMM_COF: &str = include_str!("../../data/WMM.COF");
+pub static WMM: Lazy<MagneticModel> =
+    Lazy::new(|| MagneticModel::from_cof(WMM_COF));
+/// Only warn once about dates outside the validity of the model
+static OUTDATED: AtomicBool = AtomicBool::new(false);
+
+impl MagneticModel {
+    /// Parse a coefficient file in the format distributed with the WMM
+    pub fn from_cof(content: &str) -> Self {
+        let mut lines = content.lines();
+        let epoch = lines
+            .next()
+            .and_then(|header| header.split_whitespace().next())
+            .and_then(|epoch| epoch.parse().ok())
+            .expect("Invalid header in coefficient file");
+        let mut g = [[0.; DEGREE + 1]; DEGREE + 1];
+        let mut h = [[0.; DEGREE + 1]; DEGREE + 1];
+        let mut g_dot = [[0.; DEGREE + 1]; DEGREE + 1];
+        let mut h_dot = [[0.; DEGREE + 1]; DEGREE + 1];
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                // The file ends with two lines of 9s
+                break;
+            }
+            let n: usize = fields[0].parse().unwrap();
+            let m: usize = fields[1].parse().unwrap();
+            if n > DEGREE || m > n {
+                continue;
+            }
+            g[n][m] = fields[2].parse().unwrap();
+            h[n][m] = fields[3].parse().unwrap();
+            g_dot[n][m] = fields[4].parse().unwrap();
+            h_dot[n][m] = fields[5].parse().unwrap();
+        }
+        Self {
+            epoch,
+            g,
+            h,
+            g_dot,
+            h_dot,
+        }
+    }
+
+    /// True if the date (decimal year) is within the validity of the model
+    pub fn is_valid(&self, year: f64) -> bool {
+        (self.epoch..self.epoch + VALIDITY).contains(&year)
+    }
+
+    /**
+     * The magnetic declination (in degrees, positive East) at a given
+     * position, altitude (in ft above the WGS84 ellipsoid) and date (decimal
+     * year, see [`decimal_year`]).
+     *
+     * Add the declination to a magnetic heading to get a true heading.
+     * The coefficients are extrapolated linearly from the epoch of the
+     * model: the accuracy degrades beyond its five years of validity.
+     */
+    pub fn declination(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+        year: f64,
+    ) -> f64 {
+        // Geodetic to geocentric coordinates
+        let phi = latitude.to_radians();
+        let height = altitude * 0.3048 / 1000.;
+        let e2 = WGS84_F * (2. - WGS84_F);
+        let rc = WGS84_A / (1. - e2 * phi.sin().powi(2)).sqrt();
+        let p = (rc + height) * phi.cos();
+        let z = (rc * (1. - e2) + height) * phi.sin();
+        let r = p.hypot(z);
+        let phi_c = (z / r).asin();
+
+        // Schmidt semi-normalised associated Legendre functions of the
+        // colatitude, and their derivatives with respect to the colatitude
+        let (x, s) = (phi_c.sin(), phi_c.cos());
+        let mut pnm = [[0.; DEGREE + 1]; DEGREE + 1];
+        let mut dpnm = [[0.; DEGREE + 1]; DEGREE + 1];
+        pnm[0][0] = 1.;
+        for n in 1..=DEGREE {
+            let nf = n as f64;
+            if n == 1 {
+                pnm[1][1] = s;
+                dpnm[1][1] = x;
+            } else {
+                let k = ((2. * nf - 1.) / (2. * nf)).sqrt();
+                pnm[n][n] = k * s * pnm[n - 1][n - 1];
+                dpnm[n][n] =
+                    k * (x * pnm[n - 1][n - 1] + s * dpnm[n - 1][n - 1]);
+            }
+            for m in 0..n {
+                let mf = m as f64;
+                let a = (nf * nf - mf * mf).sqrt();
+                let b = ((nf - 1.).powi(2) - mf * mf).max(0.).sqrt();
+                let (p2, dp2) = match n >= 2 {
+                    true => (pnm[n - 2][m], dpnm[n - 2][m]),
+                    false => (0., 0.),
+                };
+                pnm[n][m] = ((2. * nf - 1.) * x * pnm[n - 1][m] - b * p2) / a;
+                dpnm[n][m] = ((2. * nf - 1.)
+                    * (x * dpnm[n - 1][m] - s * pnm[n - 1][m])
+                    - b * dp2)
+                    / a;
+            }
+        }
+
+        // Field components in the geocentric frame (North, East, Down)
+        let dt = year - self.epoch;
+        let lambda = longitude.to_radians();
+        let (mut bx, mut by, mut bz) = (0., 0., 0.);
+        for n in 1..=DEGREE {
+            let ratio = (RADIUS / r).powi(n as i32 + 2);
+            for m in 0..=n {
+                let g = self.g[n][m] + dt * self.g_dot[n][m];
+                let h = self.h[n][m] + dt * self.h_dot[n][m];
+                let (sin_m, cos_m) = (m as f64 * lambda).sin_cos();
+                let gh = g * cos_m + h * sin_m;
+                bx += ratio * gh * dpnm[n][m];
+                by += ratio * m as f64 * (g * sin_m - h * cos_m) * pnm[n][m];
+                bz -= ratio * (n as f64 + 1.) * gh * pnm[n][m];
+            }
+        }
+        by /= s.max(1e-10);
+
+        // Rotate back to the geodetic frame: only the North component changes
+        let psi = phi_c - phi;
+        let north = bx * psi.cos() - bz * psi.sin();
+        by.atan2(north).to_degrees()
+    }
+}
+
+/**
+ * The magnetic declination (in degrees, positive East) according to the WMM,
+ * at a given position, altitude (in ft) and date (decimal year).
+ *
+ * A warning is logged the first time the date is outside the validity of the
+ * model.
+ */
+pub fn declination(
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    year: f64,
+) -> f64 {
+    if !WMM.is_valid(year) && !OUTDATED.swap(true, Ordering::Relaxed) {
+        warn!(
+            "The World Magnetic Model is only valid between {} and {}: \
+             magnetic declinations in {:.1} may be inaccurate",
+            WMM.epoch,
+            WMM.epoch + VALIDITY,
+            year
+        );
+    }
+    WMM.declination(latitude, longitude, altitude, year)
+}
+
+/// Convert a UNIX timestamp (in seconds) into a decimal year
+pub fn decimal_year(timestamp: f64) -> f64 {
+    let days = (timestamp / 86400.).floor() as i64;
+    // Start from an approximate year, then adjust
+    let mut year = 1970 + days.div_euclid(365) as i32;
+    while days_from_epoch(year) > days {
+        year -= 1;
+    }
+    while days_from_epoch(year + 1) <= days {
+        year += 1;
+    }
+    let start = days_from_epoch(year) as f64 * 86400.;
+    let end = days_from_epoch(year + 1) as f64 * 86400.;
+    year as f64 + (timestamp - start) / (end - start)
+}
+
+/// The number of days between 1970-01-01 and January 1st of a given year
+fn days_from_epoch(year: i32) -> i64 {
+    let y = year as i64 - 1;
+    let leap_days =
+        |y: i64| y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400);
+    365 * (year as i64 - 1970) + leap_days(y) - leap_days(1969)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_declination() {
+        // Test values from the WMM2025 report
+        let d = declination(80., 0., 0., 2025.);
+        assert_abs_diff_eq!(d, 1.28, epsilon = 0.01);
+        let d = declination(0., 120., 0., 2025.);
+        assert_abs_diff_eq!(d, -0.16, epsilon = 0.01);
+        let d = declination(-80., 240., 0., 2025.);
+        assert_abs_diff_eq!(d, 68.78, epsilon = 0.01);
+
+        // The declination changes with altitude and time
+        let d = declination(-80., 240., 100. / 0.3048 * 1000., 2025.);
+        assert_abs_diff_eq!(d, 68.21, epsilon = 0.01);
+        let d = declination(-80., 240., 0., 2027.5);
+        assert_abs_diff_eq!(d, 68.49, epsilon = 0.01);
+        let d = declination(80., 0., 100. / 0.3048 * 1000., 2027.5);
+        assert_abs_diff_eq!(d, 2.16, epsilon = 0.01);
+
+        // Toulouse, New York, Sydney and Tokyo
+        assert_abs_diff_eq!(
+            declination(43.6, 1.4, 0., 2026.),
+            1.8,
+            epsilon = 1.
+        );
+        let d = declination(40.7, -74.0, 35000., 2026.);
+        assert_abs_diff_eq!(d, -12.5, epsilon = 1.);
+        let d = declination(-33.9, 151.2, 0., 2026.);
+        assert_abs_diff_eq!(d, 12.8, epsilon = 1.);
+        let d = declination(35.7, 139.7, 0., 2026.);
+        assert_abs_diff_eq!(d, -7.9, epsilon = 1.);
+    }
+
+    #[test]
+    fn test_validity() {
+        assert!(WMM.is_valid(WMM.epoch));
+        assert!(WMM.is_valid(WMM.epoch + 4.9));
+        assert!(!WMM.is_valid(WMM.epoch + VALIDITY));
+        assert!(!WMM.is_valid(WMM.epoch - 0.1));
+    }
+
+    #[test]
+    fn test_decimal_year() {
+        assert_abs_diff_eq!(decimal_year(0.), 1970.);
+        // 2024-01-01T00:00:00Z
+        assert_abs_diff_eq!(decimal_year(1704067200.), 2024.);
+        // 2024-07-02T00:00:00Z (183 days in a leap year)
+        assert_abs_diff_eq!(decimal_year(1719878400.), 2024.5, epsilon = 1e-6);
+    }
+}
@@ -1,7 +1,7 @@
 use super::bds::{bds05, bds06, bds08, bds09, bds61, bds62, bds65};
-use super::{Capability, ICAO};
+use super::{as_hex, from_hex, Capability, IdentityCode, ICAO};
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /**
@@ -15,7 +15,9 @@ use std::fmt;
  *
  */
 
-#[derive(Debug, PartialEq, DekuRead, Clone, Serialize)]
+#[derive(
+    Debug, PartialEq, DekuRead, DekuWrite, Clone, Serialize, Deserialize,
+)]
 pub struct ADSB {
     /// The transponder capability
     #[serde(skip)]
@@ -55,23 +57,29 @@ impl fmt::Display for ADSB {
 * | 9..=18   | [`bds05::AirbornePosition`] (barometric altitude) |
 * | 19       | [`bds09::AirborneVelocity`]                       |
 * | 20..=22  | [`bds05::AirbornePosition`] (GNSS height)         |
-* | 23       | [`ME::Reserved0`]                                 |
-* | 24       | [`ME::SurfaceSystemStatus`]                       |
+* | 23       | [`TestMessage`]                                   |
+* | 24       | [`SurfaceSystemStatus`]                           |
 * | 25..=27  | [`ME::Reserved1`]                                 |
 * | 28       | [`bds61::AircraftStatus`]                         |
 * | 29       | [`bds62::TargetStateAndStatusInformation`]        |
-* | 30       | [`ME::AircraftOperationalCoordination`]           |
+* | 30       | [`AircraftOperationalCoordination`]               |
 * | 31       | [`bds65::AircraftOperationStatus`]                |
 */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 pub struct Unused {
-    #[deku(skip, pad_bits_after = "48", default = "true")]
+    /// The content of the ME field after the typecode, kept as is so that
+    /// the message is encoded back identically
+    #[deku(bits = "51")]
     #[serde(skip)]
-    unused: bool,
+    pub payload: u64,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 #[deku(id_type = "u8", bits = "5")]
 //#[serde(untagged)]
 #[serde(tag = "bds")]
@@ -97,15 +105,20 @@ pub enum ME {
 
     #[deku(id = "23")]
     #[serde(rename = "id23")]
-    Reserved0(Unused),
+    TestMessage(TestMessage),
 
     #[deku(id = "24")]
     #[serde(rename = "id24")]
-    SurfaceSystemStatus(Unused),
+    SurfaceSystemStatus(SurfaceSystemStatus),
 
     #[deku(id_pat = "25..=27")]
     #[serde(rename = "id25_27")]
-    Reserved1 { unused: u8 },
+    Reserved1 {
+        unused: u8,
+        #[deku(bits = "48")]
+        #[serde(skip)]
+        payload: u64,
+    },
 
     #[deku(id = "28")]
     #[serde(rename = "61")]
@@ -117,21 +130,40 @@ pub enum ME {
 
     #[deku(id = "30")]
     #[serde(rename = "id30")]
-    AircraftOperationalCoordination(Unused),
+    AircraftOperationalCoordination(AircraftOperationalCoordination),
 
     #[deku(id = "31")]
     #[serde(rename = "65")]
     BDS65(bds65::AircraftOperationStatus),
 }
 
+/// The register (BDS) of the ADS-B messages with typecode `tc`, if any
+pub fn register(tc: u8) -> Option<&'static str> {
+    match tc {
+        1..=4 => Some("08"),
+        5..=8 => Some("06"),
+        9..=18 | 20..=22 => Some("05"),
+        19 => Some("09"),
+        28 => Some("61"),
+        29 => Some("62"),
+        31 => Some("65"),
+        _ => None,
+    }
+}
+
 impl fmt::Display for ME {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ME::NoPosition { .. }
-            | ME::Reserved0 { .. }
-            | ME::Reserved1 { .. }
-            | ME::SurfaceSystemStatus { .. }
-            | ME::AircraftOperationalCoordination { .. } => Ok(()),
+            ME::NoPosition { .. } | ME::Reserved1 { .. } => Ok(()),
+            ME::TestMessage(me) => {
+                write!(f, "{}", me)
+            }
+            ME::SurfaceSystemStatus(me) => {
+                write!(f, "{}", me)
+            }
+            ME::AircraftOperationalCoordination(me) => {
+                write!(f, "{}", me)
+            }
             ME::BDS05(me) => {
                 write!(f, "{}", me)
             }
@@ -157,6 +189,128 @@ impl fmt::Display for ME {
     }
 }
 
+/**
+ * ## Test message (TC=23)
+ *
+ * The content of test messages is not specified, except for subtype 7 which
+ * carries the 13-bit identity code (squawk) in the same format as DF=5.
+ *
+ * | TC  | ST  | ME  |
+ * | --- | --- | --- |
+ * | 5   | 3   | 48  |
+ */
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+pub struct TestMessage {
+    /// The subtype of the test message
+    #[deku(bits = "3")]
+    pub subtype: u8,
+    /// The content of the test message, based on the subtype
+    #[serde(flatten)]
+    #[deku(ctx = "*subtype")]
+    pub content: TestMessageContent,
+}
+
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+#[deku(ctx = "subtype: u8", id = "subtype")]
+#[serde(untagged)]
+pub enum TestMessageContent {
+    /// Subtype 7: test message with the 13-bit identity code (squawk)
+    #[deku(id = "7")]
+    Squawk {
+        #[deku(pad_bits_after = "35")] // reserved
+        squawk: IdentityCode,
+    },
+
+    /// Other subtypes: unspecified content (48 bits)
+    #[deku(id_pat = "_")]
+    Unspecified {
+        #[deku(count = "6")]
+        #[serde(serialize_with = "as_hex", deserialize_with = "from_hex")]
+        payload: Vec<u8>,
+    },
+}
+
+impl fmt::Display for TestMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Test message (TC 23)")?;
+        writeln!(f, "  Subtype:       {}", &self.subtype)?;
+        match &self.content {
+            TestMessageContent::Squawk { squawk } => {
+                writeln!(f, "  Squawk:        {squawk}")
+            }
+            TestMessageContent::Unspecified { payload } => {
+                writeln!(f, "  Payload:       {}", hex::encode(payload))
+            }
+        }
+    }
+}
+
+/**
+ * ## Surface system status (TC=24)
+ *
+ * Reserved for the status of surface systems (e.g. multilateration systems);
+ * the content is not specified in DO-260B.
+ *
+ * | TC  | ST  | ME  |
+ * | --- | --- | --- |
+ * | 5   | 3   | 48  |
+ */
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+pub struct SurfaceSystemStatus {
+    /// The subtype of the message
+    #[deku(bits = "3")]
+    pub subtype: u8,
+    /// The unspecified content of the message (48 bits)
+    #[deku(count = "6")]
+    #[serde(serialize_with = "as_hex", deserialize_with = "from_hex")]
+    pub payload: Vec<u8>,
+}
+
+impl fmt::Display for SurfaceSystemStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Surface system status (TC 24)")?;
+        writeln!(f, "  Subtype:       {}", &self.subtype)?;
+        writeln!(f, "  Payload:       {}", hex::encode(&self.payload))
+    }
+}
+
+/**
+ * ## Aircraft operational coordination (TC=30)
+ *
+ * Reserved for the coordination of operations between aircraft; the content
+ * is not specified in DO-260B.
+ *
+ * | TC  | ST  | ME  |
+ * | --- | --- | --- |
+ * | 5   | 3   | 48  |
+ */
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+pub struct AircraftOperationalCoordination {
+    /// The subtype of the message
+    #[deku(bits = "3")]
+    pub subtype: u8,
+    /// The unspecified content of the message (48 bits)
+    #[deku(count = "6")]
+    #[serde(serialize_with = "as_hex", deserialize_with = "from_hex")]
+    pub payload: Vec<u8>,
+}
+
+impl fmt::Display for AircraftOperationalCoordination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Aircraft operational coordination (TC 30)")?;
+        writeln!(f, "  Subtype:       {}", &self.subtype)?;
+        writeln!(f, "  Payload:       {}", hex::encode(&self.payload))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -172,4 +326,44 @@ mod tests {
         }
         unreachable!();
     }
+
+    #[test]
+    fn test_test_message() {
+        let bytes = hex!("8d3c4b26bf555000000000ff5ae8");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(
+            format!("{msg}"),
+            r#" DF17. Extended Squitter
+  Address:       3c4b26
+  Air/Ground:    airborne
+  Test message (TC 23)
+  Subtype:       7
+  Squawk:        7700
+"#
+        );
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"df":"17","icao24":"3c4b26","bds":"id23","subtype":7,"squawk":"7700"}"#
+        );
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_reserved_typecodes() {
+        let bytes = hex!("8d3c4b26c1010203040506c53994");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"df":"17","icao24":"3c4b26","bds":"id24","subtype":1,"payload":"010203040506"}"#
+        );
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+
+        let bytes = hex!("8d3c4b26f00a0b0c0d0e0f7130b7");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"df":"17","icao24":"3c4b26","bds":"id30","subtype":0,"payload":"0a0b0c0d0e0f"}"#
+        );
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+    }
 }
@@ -0,0 +1,51 @@
+/*!
+* Conversions between airspeeds in the International Standard Atmosphere
+* (ISA), used to check the consistency of Comm-B replies and to derive
+* quantities (true airspeed, temperature) from them.
+*
+* Altitudes are pressure altitudes in ft, airspeeds are in kts.
+*/
+
+/// Speed of sound at sea level in the standard atmosphere (kts)
+pub const A0: f64 = 661.4786;
+/// Temperature at sea level in the standard atmosphere (K)
+pub const T0: f64 = 288.15;
+
+/// Temperature (K) and pressure ratio in the standard atmosphere (altitude in ft)
+pub fn isa(altitude: f64) -> (f64, f64) {
+    let h = altitude * 0.3048;
+    if h < 11000. {
+        let temperature = T0 - 0.0065 * h;
+        (temperature, (temperature / T0).powf(5.2559))
+    } else {
+        let ratio = 0.22336 * (-(h - 11000.) / 6341.6).exp();
+        (216.65, ratio)
+    }
+}
+
+/// Convert a Mach number to a true airspeed
+pub fn mach2tas(mach: f64, altitude: f64) -> f64 {
+    let (temperature, _) = isa(altitude);
+    mach * A0 * (temperature / T0).sqrt()
+}
+
+/// Convert a calibrated (or indicated) airspeed to a true airspeed
+pub fn cas2tas(cas: f64, altitude: f64) -> f64 {
+    let (_, ratio) = isa(altitude);
+    let qc = (1. + 0.2 * (cas / A0).powi(2)).powf(3.5) - 1.;
+    let mach = (5. * ((qc / ratio + 1.).powf(2. / 7.) - 1.)).sqrt();
+    mach2tas(mach, altitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_conversions() {
+        assert_relative_eq!(mach2tas(0.8, 35000.), 461., max_relative = 1e-2);
+        assert_relative_eq!(cas2tas(250., 0.), 250., max_relative = 1e-3);
+        assert_relative_eq!(cas2tas(250., 30000.), 394., max_relative = 1e-2);
+    }
+}
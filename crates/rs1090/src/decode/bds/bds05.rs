@@ -1,7 +1,8 @@
-use crate::decode::cpr::CPRFormat;
-use crate::decode::{decode_id13, gray2alt};
+use crate::decode::cpr::{CPRFormat, Rejection};
+use crate::decode::integrity::Integrity;
+use crate::decode::{alt2gray, decode_id13, encode_id13, gray2alt};
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /**
@@ -14,10 +15,12 @@ use std::fmt;
  * | 5  | 2  |  1  | 12  | 1 | 1 |   17    |   17    |
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct AirbornePosition {
     #[deku(bits = 5)]
-    tc: u8,
+    pub tc: u8,
 
     #[deku(
         skip,
@@ -42,20 +45,24 @@ pub struct AirbornePosition {
         bits = "1",
         map = "|v| -> Result<_, DekuError> {
             if *tc < 19 { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "saf_or_nicb.unwrap_or(0).to_writer(deku::writer, deku::ctx::BitSize(1))"
     )]
     #[serde(rename = "NICb", skip_serializing_if = "Option::is_none")]
     /// Single Antenna Flag in ADSB v0 or v1,
     /// Navigation Integrity Category Supplement-b (NICb) in ADSB v2
     pub saf_or_nicb: Option<u8>,
 
-    #[deku(reader = "decode_ac12(deku::reader)")]
+    #[deku(
+        reader = "decode_ac12(deku::reader)",
+        writer = "encode_ac12(deku::writer, alt)"
+    )]
     #[serde(rename = "altitude")]
     /// Decode the altitude in feet, encoded on 12 bits.
     /// None if not available.
     pub alt: Option<u16>,
 
-    #[deku(reader = "read_source(*tc)")]
+    #[deku(skip, default = "read_source(*tc)?")]
     /// Decode the altitude source (GNSS or barometric),
     /// most commonly equal to barometric
     pub source: Source,
@@ -80,6 +87,14 @@ pub struct AirbornePosition {
     #[deku(skip, default = "None")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub longitude: Option<f64>,
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Set if the decoded position failed the reasonableness checks
+    pub rejected: Option<Rejection>,
+    #[deku(skip, default = "None")]
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    /// NIC, radius of containment, NACv and SIL, based on the ADS-B version
+    pub integrity: Option<Integrity>,
 }
 
 /// Decode altitude value encoded on 12 bits
@@ -112,6 +127,30 @@ fn decode_ac12<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     }
 }
 
+/// Encode altitude value on 12 bits, with a 25 ft resolution (Q bit set)
+/// when possible, or as a Gillham code above 50,175 ft
+fn encode_ac12<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    alt: &Option<u16>,
+) -> Result<(), DekuError> {
+    let num = match *alt {
+        None => 0,
+        Some(alt) => {
+            let n = (alt as u32 + 1000 + 12) / 25;
+            if n < 2048 {
+                let n = n as u16;
+                ((n & 0x07f0) << 1) | 0x0010 | (n & 0x000f)
+            } else {
+                let gray = alt2gray((alt as i32 + 50) / 100)
+                    .map_err(|e| DekuError::InvalidParam(e.into()))?;
+                let n = encode_id13(gray);
+                ((n & 0x1f80) >> 1) | (n & 0x003f)
+            }
+        }
+    };
+    num.to_writer(writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(12)))
+}
+
 fn read_source(tc: u8) -> Result<Source, DekuError> {
     let source = if tc < 19 {
         Source::Barometric
@@ -137,16 +176,17 @@ impl fmt::Display for AirbornePosition {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone, Default)]
 #[deku(id_type = "u8", bits = "2")]
 pub enum SurveillanceStatus {
+    #[default]
     NoCondition = 0,
     PermanentAlert = 1,
     TemporaryAlert = 2,
     SPICondition = 3,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Copy, Clone)]
 pub enum Source {
     #[serde(rename = "barometric")]
     Barometric = 0,
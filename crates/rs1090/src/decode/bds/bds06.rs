@@ -1,8 +1,9 @@
 #![allow(clippy::suspicious_else_formatting)]
 
-use super::super::cpr::CPRFormat;
+use super::super::cpr::{CPRFormat, Rejection};
+use super::super::integrity::Integrity;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use tracing::debug;
 
@@ -22,7 +23,9 @@ use tracing::debug;
  *
  */
 
-#[derive(Debug, PartialEq, DekuRead, Serialize, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, DekuRead, DekuWrite, Serialize, Deserialize, Copy, Clone,
+)]
 pub struct SurfacePosition {
     #[deku(bits = 5)]
     pub tc: u8,
@@ -32,7 +35,10 @@ pub struct SurfacePosition {
     /// Navigation Uncertainty Category (position), based on the typecode
     pub nuc_p: u8,
 
-    #[deku(reader = "read_groundspeed(deku::reader)")]
+    #[deku(
+        reader = "read_groundspeed(deku::reader)",
+        writer = "write_groundspeed(deku::writer, groundspeed)"
+    )]
     /// The groundspeed in kts, None if not available
     pub groundspeed: Option<f64>,
 
@@ -49,7 +55,10 @@ pub struct SurfacePosition {
             } else {
                 Ok(None)
             }
-        }"
+        }",
+        writer = "track
+            .map_or(0, |track| libm::round(track * 128. / 360.) as u8 % 128)
+            .to_writer(deku::writer, deku::ctx::BitSize(7))"
     )]
     /// The track angle in degrees, relative to the true North, None if not available
     pub track: Option<f64>,
@@ -74,6 +83,14 @@ pub struct SurfacePosition {
     #[deku(skip, default = "None")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub longitude: Option<f64>,
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Set if the decoded position failed the reasonableness checks
+    pub rejected: Option<Rejection>,
+    #[deku(skip, default = "None")]
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    /// NIC, radius of containment, NACv and SIL, based on the ADS-B version
+    pub integrity: Option<Integrity>,
 }
 
 /**
@@ -94,7 +111,7 @@ fn read_groundspeed<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         1 => Some(0.),
         2..=8 => Some(0.125 + (mov - 2) as f64 * 0.125),
         9..=12 => Some(1. + (mov - 9) as f64 * 0.25),
-        13..=38 => Some(2. + (mov - 13) as f64 * 0.25),
+        13..=38 => Some(2. + (mov - 13) as f64 * 0.5),
         39..=93 => Some(15. + (mov - 39) as f64 * 1.),
         94..=108 => Some(70. + (mov - 94) as f64 * 2.),
         109..=123 => Some(100. + (mov - 109) as f64 * 5.),
@@ -105,7 +122,27 @@ fn read_groundspeed<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     Ok(value)
 }
 
-#[derive(Debug, PartialEq, DekuRead, Copy, Clone)]
+/// Encode the groundspeed in the movement field, with the closest quantized
+/// value (see [`read_groundspeed`])
+fn write_groundspeed<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    groundspeed: &Option<f64>,
+) -> Result<(), DekuError> {
+    let mov = match *groundspeed {
+        None => 0,
+        Some(gs) if gs < 0.125 => 1,
+        Some(gs) if gs < 1. => 2 + libm::round((gs - 0.125) / 0.125) as u8,
+        Some(gs) if gs < 2. => 9 + libm::round((gs - 1.) / 0.25) as u8,
+        Some(gs) if gs < 15. => 13 + libm::round((gs - 2.) / 0.5) as u8,
+        Some(gs) if gs < 70. => 39 + libm::round(gs - 15.) as u8,
+        Some(gs) if gs < 100. => 94 + libm::round((gs - 70.) / 2.) as u8,
+        Some(gs) if gs < 175. => 109 + libm::round((gs - 100.) / 5.) as u8,
+        Some(_) => 124,
+    };
+    mov.to_writer(writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(7)))
+}
+
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum StatusForGroundTrack {
     Invalid = 0,
@@ -153,6 +190,18 @@ mod tests {
         unreachable!();
     }
 
+    #[test]
+    fn test_groundspeed() {
+        // TC=5, movement=20: 2 kts + 7 steps of 0.5 kts
+        let bytes = hex!("29400000000000");
+        let (_, me) = ME::from_bytes((&bytes, 0)).unwrap();
+        if let ME::BDS06(SurfacePosition { groundspeed, .. }) = me {
+            assert_eq!(groundspeed, Some(5.5));
+            return;
+        }
+        unreachable!();
+    }
+
     #[test]
     fn test_format() {
         let bytes = hex!("8c4841753a9a153237aef0f275be");
@@ -1,5 +1,5 @@
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use tracing::{debug, trace};
 
@@ -16,7 +16,9 @@ use tracing::{debug, trace};
  * TC: Type code CA: Aircraft category C*: A character
  */
 
-#[derive(Debug, PartialEq, DekuRead, Serialize, Clone)]
+#[derive(
+    Debug, PartialEq, DekuRead, DekuWrite, Serialize, Deserialize, Clone,
+)]
 //#[deku(ctx = "id: u8")]
 pub struct AircraftIdentification {
     #[deku(bits = 5)]
@@ -33,11 +35,14 @@ pub struct AircraftIdentification {
     pub ca: u8,
 
     /// Both typecode and category define a wake wortex category.
-    #[deku(reader = "wake_vortex(*tc, *ca)")]
+    #[deku(skip, default = "wake_vortex(*tc, *ca)?")]
     pub wake_vortex: WakeVortex,
 
     /// Callsign
-    #[deku(reader = "callsign_read(deku::reader)")]
+    #[deku(
+        reader = "callsign_read(deku::reader)",
+        writer = "callsign_write(deku::writer, callsign)"
+    )]
     pub callsign: String,
 }
 
@@ -100,7 +105,7 @@ impl TryFrom<u8> for Typecode {
 * - ICAO WTC M (Medium) is equivalent to ADS-B (TC=4, CA=2 or CA=3).
 * - ICAO WTC H (Heavy) or J (Super) is equivalent to ADS-B (TC=4, CA=5).
 */
-#[derive(Debug, PartialEq, Serialize, Copy, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Copy, Clone)]
 pub enum WakeVortex {
     Reserved,
 
@@ -216,6 +221,32 @@ pub fn callsign_read<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     Ok(encoded)
 }
 
+/// Encode the callsign on 8 characters of 6 bits, padded with spaces
+pub fn callsign_write<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    callsign: &str,
+) -> Result<(), DekuError> {
+    if callsign.len() > 8 {
+        return Err(DekuError::InvalidParam(
+            format!("Callsign {callsign} is longer than 8 characters").into(),
+        ));
+    }
+    for c in format!("{callsign:<8}").bytes() {
+        let code = match c {
+            b'A'..=b'Z' | b' ' | b'0'..=b'9' => {
+                CHAR_LOOKUP.iter().position(|&x| x == c).unwrap_or(32) as u8
+            }
+            _ => {
+                return Err(DekuError::InvalidParam(
+                    format!("Invalid character in callsign {callsign}").into(),
+                ))
+            }
+        };
+        code.to_writer(writer, deku::ctx::BitSize(6))?;
+    }
+    Ok(())
+}
+
 impl fmt::Display for AircraftIdentification {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "  Aircraft identification and category (BDS 0,8)")?;
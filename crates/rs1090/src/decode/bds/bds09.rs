@@ -2,7 +2,7 @@
 
 use deku::prelude::*;
 use serde::ser::SerializeStruct;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /**
@@ -25,7 +25,9 @@ use std::fmt;
  * subtypes 2 and 4 at this moment.
  *
  */
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 pub struct AirborneVelocity {
     #[deku(bits = "3")]
     #[serde(skip)]
@@ -58,6 +60,9 @@ pub struct AirborneVelocity {
     pub vrate_src: VerticalRateSource,
 
     #[serde(skip)]
+    #[deku(
+        writer = "Sign::of(f64::from(vertical_rate.unwrap_or(0))).to_writer(deku::writer, ())"
+    )]
     /// The sign of the vertical rate value
     pub vrate_sign: Sign,
     #[deku(
@@ -68,7 +73,10 @@ pub struct AirborneVelocity {
             else {
                 Ok(Some(vrate_sign.value() * (v as i16 - 1)  * 64))
             }
-        }"
+        }",
+        writer = "vertical_rate
+            .map_or(0, |v| (v.unsigned_abs() / 64 + 1).min(511))
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(9)))"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The vertical rate value in ft/mn, None if unavailable
@@ -79,10 +87,16 @@ pub struct AirborneVelocity {
     pub reserved: u8,
 
     #[serde(skip)]
+    #[deku(
+        writer = "Sign::of(f64::from(geo_minus_baro.unwrap_or(0))).to_writer(deku::writer, ())"
+    )]
     /// The sign of the difference between the GNSS height and the barometric altitude
     pub gnss_sign: Sign,
 
-    #[deku(reader = "read_geobaro(deku::reader, *gnss_sign)")]
+    #[deku(
+        reader = "read_geobaro(deku::reader, *gnss_sign)",
+        writer = "write_geobaro(deku::writer, geo_minus_baro)"
+    )]
     /// The signed difference between the GNSS height and the barometric altitude
     pub geo_minus_baro: Option<i16>,
 }
@@ -106,7 +120,18 @@ fn read_geobaro<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     Ok(value)
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+fn write_geobaro<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    geo_minus_baro: &Option<i16>,
+) -> Result<(), DekuError> {
+    let value = geo_minus_baro
+        .map_or(0, |v| (v.unsigned_abs() / 25 + 1).min(127) as u8);
+    value.to_writer(writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(7)))
+}
+
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 #[deku(ctx = "subtype: u8", id = "subtype")]
 #[serde(untagged)]
 pub enum AirborneVelocitySubType {
@@ -125,14 +150,23 @@ pub enum AirborneVelocitySubType {
     Reserved1(#[deku(bits = "22")] u32),
 }
 
-#[derive(Debug, PartialEq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Copy, Clone, Default)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum Sign {
+    #[default]
     Positive = 0,
     Negative = 1,
 }
 
 impl Sign {
+    fn of(value: f64) -> Self {
+        if value < 0. {
+            Self::Negative
+        } else {
+            Self::Positive
+        }
+    }
+
     #[must_use]
     pub fn value(&self) -> i16 {
         match self {
@@ -155,20 +189,29 @@ impl fmt::Display for Sign {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct GroundSpeedDecoding {
     #[serde(skip)]
+    #[deku(
+        writer = "Sign::of(east_velocity(*groundspeed, *track)).to_writer(deku::writer, ())"
+    )]
     pub ew_sign: Sign,
     #[deku(
         endian = "big",
         bits = "10",
         map = "|val: u16| -> Result<_, DekuError> {
             Ok(f64::from((val as i16 - 1) * ew_sign.value()))
-        }"
+        }",
+        writer = "write_velocity(deku::writer, east_velocity(*groundspeed, *track))"
     )]
     #[serde(skip)]
     pub ew_vel: f64,
     #[serde(skip)]
+    #[deku(
+        writer = "Sign::of(north_velocity(*groundspeed, *track)).to_writer(deku::writer, ())"
+    )]
     pub ns_sign: Sign,
     #[serde(skip)]
     #[deku(
@@ -176,7 +219,8 @@ pub struct GroundSpeedDecoding {
         bits = "10",
         map = "|val: u16| -> Result<_, DekuError> {
             Ok(f64::from((val as i16 - 1) * ns_sign.value()))
-        }"
+        }",
+        writer = "write_velocity(deku::writer, north_velocity(*groundspeed, *track))"
     )]
     pub ns_vel: f64,
     #[deku(
@@ -195,7 +239,25 @@ pub struct GroundSpeedDecoding {
     pub track: f64,
 }
 
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+/// The velocity components are encoded from the groundspeed and track angle,
+/// as they are the only serialized fields.
+fn east_velocity(groundspeed: f64, track: f64) -> f64 {
+    libm::round(groundspeed * libm::sin(track.to_radians()))
+}
+
+fn north_velocity(groundspeed: f64, track: f64) -> f64 {
+    libm::round(groundspeed * libm::cos(track.to_radians()))
+}
+
+fn write_velocity<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    velocity: f64,
+) -> Result<(), DekuError> {
+    let value = (libm::fabs(velocity) as u16 + 1).min(1023);
+    value.to_writer(writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(10)))
+}
+
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct AirspeedSubsonicDecoding {
     #[deku(bits = "1")]
     pub status_heading: bool,
@@ -205,7 +267,10 @@ pub struct AirspeedSubsonicDecoding {
         bits = "10",
         map = "|val: u16| -> Result<_, DekuError> {
             Ok(if *status_heading { Some(val as f64 * 360. / 1024.) } else { None })
-        }"
+        }",
+        writer = "heading
+            .map_or(0, |h| libm::round(h * 1024. / 360.) as u16 % 1024)
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(10)))"
     )]
     pub heading: Option<f64>,
 
@@ -217,9 +282,16 @@ pub struct AirspeedSubsonicDecoding {
         map = "|value: u16| -> Result<_, DekuError> {
             if value == 0 { return Ok(None) }
             Ok(Some(value - 1))
-        }"
+        }",
+        writer = "airspeed
+            .map_or(0, |v| (v + 1).min(1023))
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(10)))"
     )]
     pub airspeed: Option<u16>,
+
+    #[deku(skip, default = "None")]
+    /// The true heading (in degrees), if the position of the aircraft is known
+    pub true_heading: Option<f64>,
 }
 
 impl Serialize for AirspeedSubsonicDecoding {
@@ -231,6 +303,9 @@ impl Serialize for AirspeedSubsonicDecoding {
         if let Some(heading) = &self.heading {
             state.serialize_field("heading", heading)?;
         }
+        if let Some(true_heading) = &self.true_heading {
+            state.serialize_field("true_heading", true_heading)?;
+        }
         if let Some(airspeed) = &self.airspeed {
             match &self.airspeed_type {
                 AirspeedType::IAS => {
@@ -245,7 +320,24 @@ impl Serialize for AirspeedSubsonicDecoding {
     }
 }
 
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+impl<'de> Deserialize<'de> for AirspeedSubsonicDecoding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let fields = AirspeedFields::deserialize(deserializer)?;
+        let (airspeed_type, airspeed) = fields.airspeed();
+        Ok(Self {
+            status_heading: fields.heading.is_some(),
+            heading: fields.heading,
+            airspeed_type,
+            airspeed,
+            true_heading: fields.true_heading,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct AirspeedSupersonicDecoding {
     #[deku(bits = "1")]
     pub status_heading: bool,
@@ -255,7 +347,10 @@ pub struct AirspeedSupersonicDecoding {
         bits = "10",
         map = "|val: u16| -> Result<_, DekuError> {
             Ok(if *status_heading { Some(val as f32 * 360. / 1024.) } else { None })
-        }"
+        }",
+        writer = "heading
+            .map_or(0, |h| libm::roundf(h * 1024. / 360.) as u16 % 1024)
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(10)))"
     )]
     pub heading: Option<f32>,
 
@@ -267,9 +362,16 @@ pub struct AirspeedSupersonicDecoding {
         map = "|value: u16| -> Result<_, DekuError> {
             if value == 0 { return Ok(None) }
             Ok(Some(4*(value - 1)))
-        }"
+        }",
+        writer = "airspeed
+            .map_or(0, |v| (v / 4 + 1).min(1023))
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(10)))"
     )]
     pub airspeed: Option<u16>,
+
+    #[deku(skip, default = "None")]
+    /// The true heading (in degrees), if the position of the aircraft is known
+    pub true_heading: Option<f64>,
 }
 
 impl Serialize for AirspeedSupersonicDecoding {
@@ -281,6 +383,9 @@ impl Serialize for AirspeedSupersonicDecoding {
         if let Some(heading) = &self.heading {
             state.serialize_field("heading", heading)?;
         }
+        if let Some(true_heading) = &self.true_heading {
+            state.serialize_field("true_heading", true_heading)?;
+        }
         if let Some(airspeed) = &self.airspeed {
             match &self.airspeed_type {
                 AirspeedType::IAS => {
@@ -295,7 +400,45 @@ impl Serialize for AirspeedSupersonicDecoding {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, DekuRead)]
+impl<'de> Deserialize<'de> for AirspeedSupersonicDecoding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let fields = AirspeedFields::deserialize(deserializer)?;
+        let (airspeed_type, airspeed) = fields.airspeed();
+        Ok(Self {
+            status_heading: fields.heading.is_some(),
+            heading: fields.heading.map(|h| h as f32),
+            airspeed_type,
+            airspeed,
+            true_heading: fields.true_heading,
+        })
+    }
+}
+
+/// Intermediate representation of the serialized airspeed subtypes, where
+/// the airspeed type is encoded in the field name (IAS or TAS)
+#[derive(Deserialize)]
+struct AirspeedFields {
+    heading: Option<f64>,
+    true_heading: Option<f64>,
+    #[serde(rename = "IAS")]
+    ias: Option<u16>,
+    #[serde(rename = "TAS")]
+    tas: Option<u16>,
+}
+
+impl AirspeedFields {
+    fn airspeed(&self) -> (AirspeedType, Option<u16>) {
+        match (self.ias, self.tas) {
+            (_, Some(tas)) => (AirspeedType::TAS, Some(tas)),
+            (ias, None) => (AirspeedType::IAS, ias),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, DekuRead, DekuWrite)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum AirspeedType {
     IAS = 0,
@@ -315,21 +458,23 @@ impl fmt::Display for AirspeedType {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, DekuRead, DekuWrite)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum DirectionEW {
     WestToEast = 0,
     EastToWest = 1,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, DekuRead, DekuWrite)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum DirectionNS {
     SouthToNorth = 0,
     NorthToSouth = 1,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum VerticalRateSource {
     #[serde(rename = "barometric")]
@@ -1,5 +1,6 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## Data link Capability Report (BDS 1,0)
@@ -11,7 +12,7 @@ use serde::Serialize;
  * number, which is 1,0, or 0001 0000 in binary format.
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "10")]
 pub struct DataLinkCapability {
     #[deku(bits = "8", map = "fail_if_not10")]
@@ -106,18 +107,22 @@ fn fail_if_not0(value: u8) -> Result<u8, DekuError> {
     if value == 0 {
         Ok(value)
     } else {
-        Err(DekuError::Assertion(
-            "Reserved bits must be 0 in BDS 1,0".into(),
-        ))
+        Err(Error::Reserved {
+            field: "reserved",
+            value: value as u64,
+        }
+        .raise())
     }
 }
 fn fail_if_not10(value: u8) -> Result<u8, DekuError> {
     if value == 0x10 {
         Ok(value)
     } else {
-        Err(DekuError::Assertion(
-            "First bits must be 0x10 in BDS 1,0".into(),
-        ))
+        Err(Error::Reserved {
+            field: "bds",
+            value: value as u64,
+        }
+        .raise())
     }
 }
 
@@ -1,5 +1,6 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## Common usage GICB capability report (BDS 1,7)
@@ -10,126 +11,126 @@ use serde::Serialize;
  *
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Copy, Clone)]
 #[serde(tag = "bds", rename = "17")]
 pub struct CommonUsageGICBCapabilityReport {
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Extended squitter airborne position
     pub bds05: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Extended squitter surface position
     pub bds06: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Extended squitter status
     pub bds07: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Extended squitter identification and category
     pub bds08: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Extended squitter airborne velocity information
     pub bds09: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Extended squitter event-driven information
     pub bds0a: bool,
 
-    #[deku(bits = "1", map = "fail_if_false")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_false(v, \"bds20\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Aircraft identification
     pub bds20: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Aircraft registration number
     pub bds21: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Selected vertical intention
     pub bds40: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Next waypoint identifier
     pub bds41: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Next waypoint position
     pub bds42: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Next waypoint information
     pub bds43: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Meteorological routine report
     pub bds44: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Meteorological hazard report
     pub bds45: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// VHF channel report
     pub bds48: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Track and turn report
     pub bds50: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Position coarse
     pub bds51: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Position fine
     pub bds52: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Air-referenced state vector
     pub bds53: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Waypoint 1
     pub bds54: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Waypoint 2
     pub bds55: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Waypoint 3
     pub bds56: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Quasi-static parameter monitoring
     pub bds5f: bool,
 
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Heading and speed report
     pub bds60: bool,
 
@@ -146,13 +147,12 @@ fn is_false(value: &bool) -> bool {
     !*value
 }
 
-fn fail_if_false(value: bool) -> Result<bool, DekuError> {
+/// BDS 2,0 is always valid in BDS 1,7
+fn fail_if_false(value: bool, field: &'static str) -> Result<bool, DekuError> {
     if value {
         Ok(value)
     } else {
-        Err(DekuError::Assertion(
-            "BDS 2,0 is always valid in BDS 1,7".into(),
-        ))
+        Err(Error::Reserved { field, value: 0 }.raise())
     }
 }
 
@@ -168,9 +168,11 @@ fn check_zeros<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
             ),
         )?;
         if value != 0 {
-            return Err(DekuError::InvalidParam(
-                "BDS 1,7 must have all final bits set to 0".into(),
-            ));
+            return Err(Error::Reserved {
+                field: "reserved",
+                value: value as u64,
+            }
+            .raise());
         }
     }
     Ok(true)
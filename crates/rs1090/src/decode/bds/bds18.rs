@@ -1,5 +1,6 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## GICB capability report (1 of 5) (BDS 1,8)
@@ -10,176 +11,176 @@ use serde::Serialize;
  *
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Copy, Clone)]
 #[serde(tag = "bds", rename = "18")]
 pub struct GICBCapabilityReportPart1 {
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds38\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds38: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds37\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds37: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds36\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds36: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds35\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds35: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds34\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds34: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds33\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds33: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds32\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds32: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds31\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds31: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds30: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds2f\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds2f: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds2e\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds2e: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds2d\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds2d: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds2c\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds2c: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds2b\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds2b: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds2a\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds2a: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds29\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds29: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds28\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds28: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds27\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds27: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds26\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds26: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds25\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds25: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds24\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds24: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds23\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds23: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds22\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds22: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds21: bool,
-    #[deku(bits = "1", map = "fail_if_false")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_false(v, \"bds20\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds20: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds1f: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds1e: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds1d: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds1c: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds1b: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds1a: bool,
-    #[deku(bits = "1", map = "fail_if_false")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_false(v, \"bds19\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds19: bool,
-    #[deku(bits = "1", map = "fail_if_false")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_false(v, \"bds18\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds18: bool,
-    #[deku(bits = "1", map = "fail_if_false")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_false(v, \"bds17\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds17: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds16\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds16: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds15\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds15: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds14\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds14: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds13\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds13: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds12\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds12: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds11\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds11: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds10: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds0f: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds0e\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds0e: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds0d\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds0d: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds0c\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds0c: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds0b: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds0a: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds09: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds08: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds07: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds06: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds05: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds04\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds04: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds03\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds03: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds02\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds02: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds01\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds01: bool,
 }
 
@@ -187,21 +188,19 @@ fn is_false(value: &bool) -> bool {
     !*value
 }
 
-fn fail_if_false(value: bool) -> Result<bool, DekuError> {
+/// BDS 1,7, 1,8, 1,9 and 2,0 are always valid in BDS 1,8
+fn fail_if_false(value: bool, field: &'static str) -> Result<bool, DekuError> {
     if value {
         Ok(value)
     } else {
-        Err(DekuError::Assertion(
-            "BDS 1,7, 1,8, 1,9 and 2,0 are always valid in BDS 1,8".into(),
-        ))
+        Err(Error::Reserved { field, value: 0 }.raise())
     }
 }
 
-fn fail_if_true(value: bool) -> Result<bool, DekuError> {
+/// These registers are most probably not supported
+fn fail_if_true(value: bool, field: &'static str) -> Result<bool, DekuError> {
     if value {
-        Err(DekuError::Assertion(
-            "Field is most probably false in BDS 1,8".into(),
-        ))
+        Err(Error::Reserved { field, value: 1 }.raise())
     } else {
         Ok(value)
     }
@@ -1,5 +1,6 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## GICB capability report (2 of 5) (BDS 1,9)
@@ -10,176 +11,176 @@ use serde::Serialize;
  *
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Copy, Clone)]
 #[serde(tag = "bds", rename = "19")]
 pub struct GICBCapabilityReportPart2 {
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds70\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds70: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds6f\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds6f: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds6e\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds6e: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds6d\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds6d: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds6c\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds6c: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds6b\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds6b: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds6a\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds6a: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds69\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds69: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds68\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds68: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds67\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds67: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds66\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds66: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds65: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds64\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds64: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds63\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds63: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds62: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds61: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds60: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds5f: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds5e\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds5e: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds5d\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds5d: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds5c\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds5c: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds5b\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds5b: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds5a\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds5a: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds59\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds59: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds58\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds58: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds57\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds57: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds56\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds56: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds55\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds55: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds54\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds54: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds53: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds52: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds51: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds50: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds4f\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds4f: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds4e\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds4e: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds4d\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds4d: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds4c\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds4c: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds4b\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds4b: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds4a\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds4a: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds49\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds49: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds48\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds48: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds47\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds47: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds46\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds46: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds45: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds44: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds43\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds43: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds42\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds42: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds41\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds41: bool,
     #[deku(bits = "1")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds40: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds3f\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds3f: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds3e\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds3e: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds3d\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds3d: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds3c\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds3c: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds3b\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds3b: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds3a\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds3a: bool,
-    #[deku(bits = "1", map = "fail_if_true")]
-    #[serde(skip_serializing_if = "is_false")]
+    #[deku(bits = "1", map = "|v: bool| fail_if_true(v, \"bds39\")")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub bds39: bool,
 }
 
@@ -187,11 +188,10 @@ fn is_false(value: &bool) -> bool {
     !*value
 }
 
-fn fail_if_true(value: bool) -> Result<bool, DekuError> {
+/// These registers are most probably not supported
+fn fail_if_true(value: bool, field: &'static str) -> Result<bool, DekuError> {
     if value {
-        Err(DekuError::Assertion(
-            "Field is most probably false in BDS 1,9".into(),
-        ))
+        Err(Error::Reserved { field, value: 1 }.raise())
     } else {
         Ok(value)
     }
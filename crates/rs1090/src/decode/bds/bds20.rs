@@ -1,6 +1,7 @@
 use super::bds08;
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## Aircraft identification (BDS 2,0)
@@ -9,7 +10,7 @@ use serde::Serialize;
  * aircraft can be decoded from BDS 2,0 messages.
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "20")]
 pub struct AircraftIdentification {
     #[deku(bits = "8", map = "fail_if_not20")]
@@ -25,9 +26,11 @@ fn fail_if_not20(value: u8) -> Result<u8, DekuError> {
     if value == 0x20 {
         Ok(value)
     } else {
-        Err(DekuError::Assertion(
-            "First bits must be 0x20 in BDS 2,0".into(),
-        ))
+        Err(Error::Reserved {
+            field: "bds",
+            value: value as u64,
+        }
+        .raise())
     }
 }
 
@@ -1,6 +1,7 @@
+use crate::decode::Error;
 use deku::prelude::*;
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, trace};
 
 /**
@@ -10,7 +11,7 @@ use tracing::{debug, trace};
  * necessity of compiling and maintaining continuously updated data banks.
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "21")]
 pub struct AircraftAndAirlineRegistrationMarkings {
     #[deku(bits = "1")]
@@ -50,8 +51,8 @@ pub fn aircraft_registration_read<
 
     let all_zeros = chars.iter().all(|&x| x == 0);
     let encoded = chars
-        .into_iter()
-        .map(|b| CHAR_LOOKUP[b as usize] as char)
+        .iter()
+        .map(|&b| CHAR_LOOKUP[b as usize] as char)
         .collect::<String>();
     debug!("Decoded registration: {}", encoded);
 
@@ -60,20 +61,21 @@ pub fn aircraft_registration_read<
         if re.is_match(&encoded) {
             Ok(Some(encoded))
         } else {
-            Err(DekuError::Assertion(
-                format!("Invalid aircraft registration {}", encoded).into(),
-            ))
+            Err(Error::Text {
+                field: "aircraft_registration",
+                value: encoded,
+            }
+            .raise())
         }
     } else if all_zeros {
         Ok(None)
     } else {
-        Err(DekuError::Assertion(
-            format!(
-                "Non-null value after invalid aircraft registration status: {}",
-                encoded
-            )
-            .into(),
-        ))
+        let value = chars.iter().fold(0, |acc, &c| acc << 6 | c as u64);
+        Err(Error::Status {
+            field: "aircraft_registration",
+            value,
+        }
+        .raise())
     }
 }
 
@@ -92,30 +94,23 @@ pub fn airline_registration_read<
         }
     }
     let all_zeros = chars.iter().all(|&x| x == 0);
-    let encoded = chars
-        .into_iter()
-        .map(|b| CHAR_LOOKUP[b as usize] as char)
-        .collect::<String>();
 
     if status {
-        // Ok((inside_rest, Some(encoded)))
-        Err(DekuError::Assertion(
-            format!(
-                "Most transponders don't implement this field. (value = {})",
-                encoded
-            )
-            .into(),
-        ))
+        // Most transponders don't implement this field
+        Err(Error::Reserved {
+            field: "al_status",
+            value: 1,
+        }
+        .raise())
     } else if all_zeros {
         Ok(None)
     } else {
-        Err(DekuError::Assertion(
-            format!(
-                "Non-null value after invalid airline registration status: {}",
-                encoded
-            )
-            .into(),
-        ))
+        let value = chars.iter().fold(0, |acc, &c| acc << 6 | c as u64);
+        Err(Error::Status {
+            field: "airline_registration",
+            value,
+        }
+        .raise())
     }
 }
 
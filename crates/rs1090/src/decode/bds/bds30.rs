@@ -1,5 +1,7 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use crate::decode::{AC13Field, ICAO};
 
@@ -10,7 +12,9 @@ use crate::decode::{AC13Field, ICAO};
  * ACAS equipment.
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 #[serde(tag = "bds", rename = "30")]
 pub struct ACASResolutionAdvisory {
     #[deku(bits = "8", map = "fail_if_not30")]
@@ -18,6 +22,23 @@ pub struct ACASResolutionAdvisory {
     /// The first eight bits indicate the BDS code 0011 0000 (3,0 in hexadecimal).
     pub bds: u8,
 
+    /// The resolution advisory
+    #[serde(flatten)]
+    pub ra: ResolutionAdvisory,
+}
+
+/**
+ * The content of a resolution advisory (RA), common to BDS 3,0 messages and
+ * to ACAS RA broadcasts in ADS-B (BDS 6,1, subtype 2):
+ *
+ * | ARA | RAC | RAT | MTE | TTI | TID |
+ * | --- | --- | --- | --- | --- | --- |
+ * | 14  | 4   | 1   | 1   | 2   | 26  |
+ */
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+pub struct ResolutionAdvisory {
     #[deku(bits = "1")]
     /// Active resolution advisories.
     /// False if no RA or multiple thread/different directions.
@@ -27,7 +48,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, corrective)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Active resolution advisories: corrective/preventive
@@ -37,7 +59,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, downward_sense)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Active resolution advisories: downward/upward
@@ -47,7 +70,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, increased_rate)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Active resolution advisories:
@@ -57,7 +81,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, sense_reversal)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Active resolution advisories:
@@ -67,7 +92,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, altitude_crossing)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Active resolution advisories:
@@ -77,7 +103,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, positive)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Active resolution advisories: positive/vertical speed limit
@@ -92,7 +119,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, no_below)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Resolution advisory complements record: do not pass below
@@ -102,7 +130,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, no_above)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Resolution advisory complements record: do not pass above
@@ -112,7 +141,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, no_left)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Resolution advisory complements record: do not turn left
@@ -122,7 +152,8 @@ pub struct ACASResolutionAdvisory {
         bits = "1",
         map = "|v: bool| -> Result<_, DekuError> {
             if *issued_ra { Ok(Some(v)) } else { Ok(None) }
-        }"
+        }",
+        writer = "write_flag(deku::writer, no_right)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Resolution advisory complements record: do not turn right
@@ -141,10 +172,54 @@ pub struct ACASResolutionAdvisory {
     pub threat_type: ThreatType,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+impl fmt::Display for ResolutionAdvisory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.issued_ra {
+            let sense = match self.downward_sense {
+                Some(true) => "downward",
+                _ => "upward",
+            };
+            let kind = match self.corrective {
+                Some(true) => "corrective",
+                _ => "preventive",
+            };
+            writeln!(f, "  RA:            {kind}, {sense} sense")?;
+        }
+        if self.terminated {
+            writeln!(f, "  RA terminated")?;
+        }
+        match &self.threat_type {
+            ThreatType::ThreatAddress(threat) => {
+                writeln!(f, "  Threat:        {}", threat.threat_identity)?
+            }
+            ThreatType::ThreatOrientation(threat) => {
+                if let Some(range) = threat.range {
+                    writeln!(f, "  Threat range:  {range} NM")?;
+                }
+                if let Some(bearing) = threat.bearing {
+                    writeln!(f, "  Threat bearing: {bearing}°")?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 #[deku(id_type = "u8", bits = "2")]
 #[serde(untagged)]
 pub enum ThreatType {
+    // Variants carrying data come first so that untagged deserialization
+    // does not match the empty variants by default
+    #[deku(id = "1")]
+    ThreatAddress(ThreadAddress),
+
+    #[deku(id = "2")]
+    ThreatOrientation(ThreatOrientation),
+
     #[deku(id = "0")]
     NoIdentity {
         #[deku(bits = "26")]
@@ -152,12 +227,6 @@ pub enum ThreatType {
         unused: u32,
     },
 
-    #[deku(id = "1")]
-    ThreatAddress(ThreadAddress),
-
-    #[deku(id = "2")]
-    ThreatOrientation(ThreatOrientation),
-
     #[deku(id = "3")]
     NotAssigned {
         #[deku(bits = "26")]
@@ -166,7 +235,9 @@ pub enum ThreatType {
     },
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 pub struct ThreadAddress {
     /// Threat identity data (icao24).
     pub threat_identity: ICAO,
@@ -176,7 +247,9 @@ pub struct ThreadAddress {
     pub zeros: u8,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 pub struct ThreatOrientation {
     /// Altitude code on 13 bits
     #[serde(rename = "threat_altitude")]
@@ -186,7 +259,8 @@ pub struct ThreatOrientation {
         bits = "7",
         map = "|n: u8| -> Result<_, DekuError> {
             if n == 0 { Ok(None) } else { Ok(Some((n as f32 - 1.) / 10.)) }
-        }"
+        }",
+        writer = "write_range(deku::writer, range)"
     )]
     /// Most recent threat range from ACAS (max 12.55 nautical miles)
     #[serde(rename = "threat_range")]
@@ -196,7 +270,8 @@ pub struct ThreatOrientation {
         bits = "6",
         map = "|n: u16| -> Result<_, DekuError> {
             if n == 0 { Ok(None) } else { Ok(Some(6 * (n - 1) + 3)) }
-        }"
+        }",
+        writer = "write_bearing(deku::writer, bearing)"
     )]
     /// Most recent estimated bearing of the threat aircraft,
     /// relative to their own heading (3 degree precision)
@@ -208,8 +283,34 @@ fn fail_if_not30(value: u8) -> Result<u8, DekuError> {
     if value == 0x30 {
         Ok(value)
     } else {
-        Err(DekuError::Assertion(
-            "First bits must be 0x30 in BDS 3,0".into(),
-        ))
+        Err(Error::Reserved {
+            field: "bds",
+            value: value as u64,
+        }
+        .raise())
     }
 }
+
+fn write_flag<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    flag: &Option<bool>,
+) -> Result<(), DekuError> {
+    flag.unwrap_or(false)
+        .to_writer(writer, deku::ctx::BitSize(1))
+}
+
+fn write_range<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    range: &Option<f32>,
+) -> Result<(), DekuError> {
+    let n = range.map_or(0, |r| (libm::roundf(r * 10.) as u8).min(126) + 1);
+    n.to_writer(writer, deku::ctx::BitSize(7))
+}
+
+fn write_bearing<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    bearing: &Option<u16>,
+) -> Result<(), DekuError> {
+    let n = bearing.map_or(0, |b| (b.saturating_sub(3) / 6).min(62) as u8 + 1);
+    n.to_writer(writer, deku::ctx::BitSize(6))
+}
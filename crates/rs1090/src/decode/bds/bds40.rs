@@ -1,7 +1,8 @@
 #![allow(clippy::suspicious_else_formatting)]
 
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## Selected vertical intention (BDS 4,0)
@@ -12,14 +13,14 @@ use serde::Serialize;
  * with an altitude command.
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "40")]
 pub struct SelectedVerticalIntention {
-    #[deku(reader = "read_selected(deku::reader)")]
+    #[deku(reader = "read_selected(deku::reader, \"selected_altitude_mcp\")")]
     #[serde(rename = "selected_mcp", skip_serializing_if = "Option::is_none")]
     pub selected_altitude_mcp: Option<u16>, // 1+12
 
-    #[deku(reader = "read_selected(deku::reader)")]
+    #[deku(reader = "read_selected(deku::reader, \"selected_altitude_fms\")")]
     #[serde(rename = "selected_fms", skip_serializing_if = "Option::is_none")]
     pub selected_altitude_fms: Option<u16>, //1+12
 
@@ -30,7 +31,7 @@ pub struct SelectedVerticalIntention {
     #[serde(skip)]
     #[deku(map = "|v: u8| {
         if v == 0 { Ok(v) } else {
-            Err(DekuError::Assertion(\"Reserved bits must be 0\".into()))
+            Err(Error::Reserved { field: \"reserved\", value: v as u64 }.raise())
         }
     }")]
     #[deku(bits = 8)]
@@ -52,7 +53,7 @@ pub struct SelectedVerticalIntention {
 
     #[deku(map = "|v: u8| {
         if v == 0 { Ok(v) } else {
-            Err(DekuError::Assertion(\"Reserved bits must be 0\".into()))
+            Err(Error::Reserved { field: \"reserved1\", value: v as u64 }.raise())
         }
     }")]
     #[deku(bits = 2)]
@@ -65,16 +66,20 @@ pub struct SelectedVerticalIntention {
     pub source_status: bool,
     #[serde(
         rename = "target_source",
+        default,
         skip_serializing_if = "TargetSource::is_unknown"
     )]
     /// Target altitude source
     pub target_altitude_source: TargetSource,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone, Default,
+)]
 #[deku(id_type = "u8", bits = "2")]
 pub enum TargetSource {
     #[deku(id = "0")]
+    #[default]
     Unknown,
     #[deku(id = "1")]
     AircraftAltitude,
@@ -91,6 +96,7 @@ impl TargetSource {
 
 fn read_selected<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     reader: &mut Reader<R>,
+    field: &'static str,
 ) -> Result<Option<u16>, DekuError> {
     let status = bool::from_reader_with_ctx(
         reader,
@@ -103,9 +109,11 @@ fn read_selected<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Invalid selected_fms or mcp value".into(),
-            ));
+            return Err(Error::Status {
+                field,
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -114,11 +122,11 @@ fn read_selected<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     // (encoded as a multiple of 16, but rounded to the closest 100 ft)
     let value = (value + 8) / 100 * 100;
     if value > 45000 {
-        let msg = format!(
-            "Value for selected_fms or selected_mcp: {} ft > 45000 ft",
-            value
-        );
-        return Err(DekuError::Assertion(msg.into()));
+        return Err(Error::Range {
+            field,
+            value: value as f64,
+        }
+        .raise());
     }
 
     Ok(Some(value))
@@ -138,7 +146,11 @@ fn read_qnh<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion("Invalid QNH value".into()));
+            return Err(Error::Status {
+                field: "barometric_setting",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
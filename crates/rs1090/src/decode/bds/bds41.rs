@@ -0,0 +1,116 @@
+#![allow(clippy::suspicious_else_formatting)]
+
+use crate::decode::Error;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
+
+/**
+ * ## Next waypoint identifier (BDS 4,1)
+ *
+ * The identifier of the next waypoint in the flight plan, coded on up to 9
+ * characters of 6 bits (same character set as for the callsign).
+ *
+ * | STATUS | CHARACTERS | RESERVED |
+ * | ------ | ---------- | -------- |
+ * | 1      | 9 × 6      | 1        |
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "41")]
+pub struct NextWaypointIdentifier {
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub status: bool,
+
+    #[deku(reader = "read_waypoint(deku::reader, *status)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waypoint: Option<String>,
+
+    #[serde(skip)]
+    #[deku(map = "|v: u8| {
+        if v == 0 { Ok(v) } else {
+            Err(Error::Reserved { field: \"reserved\", value: v as u64 }.raise())
+        }
+    }")]
+    #[deku(bits = 1)]
+    pub reserved: u8,
+}
+
+const CHAR_LOOKUP: &[u8; 64] =
+    b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+fn read_waypoint<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+) -> Result<Option<String>, DekuError> {
+    let mut chars = vec![];
+    for _ in 0..9 {
+        let c = u8::from_reader_with_ctx(reader, deku::ctx::BitSize(6))?;
+        trace!("Reading letter {}", CHAR_LOOKUP[c as usize] as char);
+        chars.push(c);
+    }
+
+    if !status {
+        if chars.iter().all(|&c| c == 0) {
+            return Ok(None);
+        }
+        let value = chars.iter().fold(0, |acc, &c| acc << 6 | c as u64);
+        return Err(Error::Status {
+            field: "waypoint",
+            value,
+        }
+        .raise());
+    }
+
+    let encoded = chars
+        .into_iter()
+        .map(|b| CHAR_LOOKUP[b as usize] as char)
+        .collect::<String>();
+    debug!("Decoded waypoint: {}", encoded);
+
+    // Only alphanumeric characters, padded with trailing spaces: identifiers
+    // have at least two characters, and at least one letter
+    let waypoint = encoded.trim_end();
+    if waypoint.len() < 2
+        || !waypoint.chars().all(|c| c.is_ascii_alphanumeric())
+        || !waypoint.chars().any(|c| c.is_ascii_alphabetic())
+    {
+        return Err(Error::Text {
+            field: "waypoint",
+            value: encoded,
+        }
+        .raise());
+    }
+    Ok(Some(waypoint.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_valid_bds41() {
+        // "TOU" followed by spaces: T=20, O=15, U=21, space=32
+        let mut payload: u64 = 1 << 55;
+        for (i, c) in [20, 15, 21, 32, 32, 32, 32, 32, 32].iter().enumerate() {
+            payload |= c << (49 - 6 * i);
+        }
+        let bytes = &payload.to_be_bytes()[1..];
+        let bds41 = NextWaypointIdentifier::try_from(bytes).unwrap();
+        assert_eq!(bds41.waypoint, Some("TOU".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_bds41() {
+        let bytes = hex!("a0000638fa81c10000000081a92f");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        if let CommBAltitudeReply { bds, .. } = msg.df {
+            assert_eq!(bds.bds41, None);
+        } else {
+            unreachable!();
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use super::{read_angle, read_longitude};
+use crate::decode::Error;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * ## Next waypoint position (BDS 4,2)
+ *
+ * The position of the next waypoint in the flight plan, with the altitude at
+ * which it should be crossed.
+ *
+ * | STATUS | LAT | LON | STATUS | ALT |
+ * | ------ | --- | --- | ------ | --- |
+ * | 1      | 19  | 20  | 1      | 15  |
+ *
+ * Latitude, longitude and altitude are coded in two's complement. The
+ * resolution is 180/2^18 degrees in latitude, 180/2^19 degrees in longitude
+ * and 8 ft in altitude.
+ *
+ * The register has no reserved bits, so the hypothesis is only accepted when
+ * both the position and the altitude of the waypoint are available, as
+ * trailing zeros in other registers would otherwise decode as a waypoint
+ * without altitude.
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "42")]
+pub struct NextWaypointPosition {
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub position_status: bool,
+
+    #[deku(
+        reader = "read_angle(deku::reader, *position_status, 19, 90., \"latitude\")"
+    )]
+    #[serde(
+        rename = "waypoint_latitude",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub latitude: Option<f64>,
+
+    #[deku(
+        reader = "read_longitude(deku::reader, *position_status, *latitude)"
+    )]
+    #[serde(
+        rename = "waypoint_longitude",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub longitude: Option<f64>,
+
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub altitude_status: bool,
+
+    #[deku(
+        reader = "read_altitude(deku::reader, *position_status, *altitude_status)"
+    )]
+    #[serde(
+        rename = "waypoint_altitude",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub altitude: Option<i32>,
+}
+
+/// The altitude of the waypoint comes with its position, and only with it
+fn read_altitude<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    position_status: bool,
+    status: bool,
+) -> Result<Option<i32>, DekuError> {
+    if status != position_status {
+        return Err(Error::Inconsistent {
+            fields: ("position_status", "altitude_status"),
+            values: (position_status as u8 as f64, status as u8 as f64),
+        }
+        .raise());
+    }
+    super::read_altitude(reader, status, 15, 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use approx::assert_relative_eq;
+    use hexlit::hex;
+
+    #[test]
+    fn test_valid_bds42() {
+        let lat = (43.6 / 180. * (1 << 18) as f64).round() as u64;
+        let lon =
+            ((-1.4 / 180. * (1 << 19) as f64).round() as i64 & 0xfffff) as u64;
+        let alt = 10000 / 8;
+        let payload: u64 =
+            1 << 55 | lat << 36 | lon << 16 | 1 << 15 | alt as u64;
+        let bytes = &payload.to_be_bytes()[1..];
+        let bds42 = NextWaypointPosition::try_from(bytes).unwrap();
+        assert_relative_eq!(bds42.latitude.unwrap(), 43.6, max_relative = 1e-4);
+        assert_relative_eq!(
+            bds42.longitude.unwrap(),
+            -1.4,
+            max_relative = 1e-3
+        );
+        assert_eq!(bds42.altitude, Some(10000));
+    }
+
+    #[test]
+    fn test_invalid_bds42() {
+        // latitude above 90 degrees
+        let payload: u64 = 1 << 55 | 0x30000 << 36 | 1 << 15;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(NextWaypointPosition::try_from(bytes).is_err());
+
+        // altitude without status bit
+        let payload: u64 = 1250;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(NextWaypointPosition::try_from(bytes).is_err());
+
+        // position without altitude
+        let payload: u64 = 1 << 55 | 0x10000 << 36;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(NextWaypointPosition::try_from(bytes).is_err());
+
+        // null coordinates
+        let payload: u64 = 1 << 55 | 1 << 15 | 1250;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(NextWaypointPosition::try_from(bytes).is_err());
+    }
+
+    #[test]
+    fn test_other_bds() {
+        for bytes in [
+            hex!("a0000638fa81c10000000081a92f"), // BDS 1,7
+            hex!("a000029c85e42f313000007047d3"), // BDS 4,0
+            hex!("a00002bf940f19680c0000000000"), // BDS 2,1
+        ] {
+            let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+            if let CommBAltitudeReply { bds, .. } = msg.df {
+                assert_eq!(bds.bds42, None);
+            } else {
+                unreachable!();
+            }
+        }
+    }
+}
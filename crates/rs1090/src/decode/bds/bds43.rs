@@ -0,0 +1,200 @@
+#![allow(clippy::suspicious_else_formatting)]
+
+use crate::decode::Error;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * ## Next waypoint information (BDS 4,3)
+ *
+ * The bearing, time and distance to go to the next waypoint in the flight
+ * plan.
+ *
+ * | STATUS | BEARING | STATUS | TIME | STATUS | DISTANCE | RESERVED |
+ * | ------ | ------- | ------ | ---- | ------ | -------- | -------- |
+ * | 1      | 11      | 1      | 15   | 1      | 16       | 11       |
+ *
+ * The bearing is coded in two's complement with a resolution of 180/1024
+ * degrees; the time to go (in minutes) and the distance to go (in nautical
+ * miles) have a resolution of 1/8.
+ *
+ * When both the time and the distance to go are available, they must match a
+ * plausible groundspeed.
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "43")]
+pub struct NextWaypointInformation {
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub bearing_status: bool,
+
+    #[deku(reader = "read_bearing(deku::reader, *bearing_status)")]
+    #[serde(
+        rename = "waypoint_bearing",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// Bearing to the waypoint (in degrees, relative to the true North)
+    pub bearing: Option<f64>,
+
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub time_status: bool,
+
+    #[deku(reader = "read_eighths(
+            deku::reader, *time_status, 15, MAX_TIME_TO_GO, \"time_to_go\"
+        )")]
+    #[serde(rename = "waypoint_time", skip_serializing_if = "Option::is_none")]
+    /// Time to go to the waypoint (in minutes)
+    pub time_to_go: Option<f64>,
+
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub distance_status: bool,
+
+    #[deku(
+        reader = "read_distance(deku::reader, *distance_status, *time_to_go)"
+    )]
+    #[serde(
+        rename = "waypoint_distance",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// Distance to go to the waypoint (in nautical miles)
+    pub distance_to_go: Option<f64>,
+
+    #[serde(skip)]
+    #[deku(map = "|v: u16| {
+        if v == 0 { Ok(v) } else {
+            Err(Error::Reserved { field: \"reserved\", value: v as u64 }.raise())
+        }
+    }")]
+    #[deku(bits = 11)]
+    pub reserved: u16,
+}
+
+/// Maximum time to go to the next waypoint (in minutes)
+const MAX_TIME_TO_GO: f64 = 600.;
+/// Maximum distance to go to the next waypoint (in nautical miles)
+const MAX_DISTANCE_TO_GO: f64 = 3000.;
+/// Range of groundspeeds (in kts) between the time and distance to go
+const GROUNDSPEED_RANGE: std::ops::RangeInclusive<f64> = 60.0..=800.0;
+
+fn read_bearing<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+) -> Result<Option<f64>, DekuError> {
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(11)),
+    )?;
+    if !status {
+        if value != 0 {
+            return Err(Error::Status {
+                field: "bearing",
+                value: value as u64,
+            }
+            .raise());
+        }
+        return Ok(None);
+    }
+    // two's complement, in degrees between 0 and 360
+    let value = if value & 0x400 != 0 {
+        value as i16 - 0x800
+    } else {
+        value as i16
+    };
+    let bearing = value as f64 * 180. / 1024.;
+    Ok(Some(bearing.rem_euclid(360.)))
+}
+
+fn read_eighths<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    bits: usize,
+    max: f64,
+    field: &'static str,
+) -> Result<Option<f64>, DekuError> {
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(bits)),
+    )?;
+    if !status {
+        if value != 0 {
+            return Err(Error::Status {
+                field,
+                value: value as u64,
+            }
+            .raise());
+        }
+        return Ok(None);
+    }
+    let value = value as f64 / 8.;
+    if value > max {
+        return Err(Error::Range { field, value }.raise());
+    }
+    Ok(Some(value))
+}
+
+fn read_distance<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    time_to_go: Option<f64>,
+) -> Result<Option<f64>, DekuError> {
+    let distance =
+        read_eighths(reader, status, 16, MAX_DISTANCE_TO_GO, "distance_to_go")?;
+    if let (Some(time), Some(distance)) = (time_to_go, distance) {
+        // Close to the waypoint, both values are rounded to small values
+        if distance > 1. {
+            let groundspeed = distance / time * 60.;
+            if !GROUNDSPEED_RANGE.contains(&groundspeed) {
+                return Err(Error::Inconsistent {
+                    fields: ("time_to_go", "distance_to_go"),
+                    values: (time, distance),
+                }
+                .raise());
+            }
+        }
+    }
+    Ok(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_valid_bds43() {
+        // bearing -90 degrees, 12.5 minutes and 96.25 nautical miles to go
+        let bearing = (-512i64 & 0x7ff) as u64;
+        let payload: u64 =
+            1 << 55 | bearing << 44 | 1 << 43 | 100 << 28 | 1 << 27 | 770 << 11;
+        let bytes = &payload.to_be_bytes()[1..];
+        let bds43 = NextWaypointInformation::try_from(bytes).unwrap();
+        assert_eq!(bds43.bearing, Some(270.));
+        assert_eq!(bds43.time_to_go, Some(12.5));
+        assert_eq!(bds43.distance_to_go, Some(96.25));
+    }
+
+    #[test]
+    fn test_invalid_bds43() {
+        for bytes in [
+            hex!("a0000638fa81c10000000081a92f"), // BDS 1,7
+            hex!("a000029c85e42f313000007047d3"), // BDS 4,0
+            hex!("a00002bf940f19680c0000000000"), // BDS 2,1
+        ] {
+            let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+            if let CommBAltitudeReply { bds, .. } = msg.df {
+                assert_eq!(bds.bds43, None);
+            } else {
+                unreachable!();
+            }
+        }
+
+        // 96.25 nautical miles in 100 minutes
+        let payload: u64 = 1 << 43 | 800 << 28 | 1 << 27 | 770 << 11;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(NextWaypointInformation::try_from(bytes).is_err());
+    }
+}
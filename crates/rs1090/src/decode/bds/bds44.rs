@@ -1,11 +1,12 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## Meteorological Routine Air Report (BDS 4,4)
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "44")]
 pub struct MeteorologicalRoutineAirReport {
     /// Figure of merit / source
@@ -37,7 +38,7 @@ pub struct MeteorologicalRoutineAirReport {
     pub humidity: Option<f64>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum Turbulence {
     Nil,
     Light,
@@ -59,16 +60,21 @@ fn read_wind_speed<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Invalid wind speed value".into(),
-            ));
+            return Err(Error::Status {
+                field: "wind_speed",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
     }
     if value > 250 {
-        let msg = format!("Invalid wind speed {} kts > 250 kts", value);
-        return Err(DekuError::Assertion(msg.into()));
+        return Err(Error::Range {
+            field: "wind_speed",
+            value: value as f64,
+        }
+        .raise());
     }
 
     Ok(Some(value))
@@ -85,9 +91,11 @@ fn read_wind_direction<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if speed.is_none() {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Invalid wind direction value".into(),
-            ));
+            return Err(Error::Status {
+                field: "wind_direction",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -115,8 +123,11 @@ fn read_temperature<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     };
 
     if !(-80. ..=60.).contains(&temp) {
-        let msg = "Invalid temperature value {}°C outside [-80, 60]";
-        return Err(DekuError::Assertion(msg.into()));
+        return Err(Error::Range {
+            field: "temperature",
+            value: temp,
+        }
+        .raise());
     }
     Ok(temp)
 }
@@ -135,16 +146,22 @@ fn read_pressure<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion("Invalid pressure value".into()));
+            return Err(Error::Status {
+                field: "pressure",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
     }
 
-    // Never seen any anyway
-    Err(DekuError::Assertion(
-        "Pressure never seen before, message deemed invalid".into(),
-    ))
+    // Never seen any anyway: the message is deemed invalid
+    Err(Error::Reserved {
+        field: "pressure_status",
+        value: 1,
+    }
+    .raise())
 
     // return Ok((rest, Some(value)));
 }
@@ -163,9 +180,11 @@ fn read_turbulence<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Invalid turbulence status".into(),
-            ));
+            return Err(Error::Status {
+                field: "turbulence",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -196,7 +215,11 @@ fn read_humidity<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion("Invalid humidity value".into()));
+            return Err(Error::Status {
+                field: "humidity",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -1,31 +1,32 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::trace;
 
 /**
  * ## Meteorological Hazard Report (BDS 4,5)
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "45")]
 pub struct MeteorologicalHazardReport {
-    #[deku(reader = "read_level(deku::reader)")]
+    #[deku(reader = "read_level(deku::reader, \"turbulence\")")]
     /// Turbulence level
     pub turbulence: Option<Level>,
 
-    #[deku(reader = "read_level(deku::reader)")]
+    #[deku(reader = "read_level(deku::reader, \"wind_shear\")")]
     /// Wind shear
     pub wind_shear: Option<Level>,
 
-    #[deku(reader = "read_level(deku::reader)")]
+    #[deku(reader = "read_level(deku::reader, \"microburst\")")]
     /// Microburst
     pub microburst: Option<Level>,
 
-    #[deku(reader = "read_level(deku::reader)")]
+    #[deku(reader = "read_level(deku::reader, \"icing\")")]
     /// Icing
     pub icing: Option<Level>,
 
-    #[deku(reader = "read_level(deku::reader)")]
+    #[deku(reader = "read_level(deku::reader, \"wake_vortex\")")]
     /// Wake vortex
     pub wake_vortex: Option<Level>,
 
@@ -46,7 +47,7 @@ pub struct MeteorologicalHazardReport {
     pub reserved: u8,
 }
 
-#[derive(Debug, PartialEq, Serialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum Level {
     Nil,
     Light,
@@ -56,6 +57,7 @@ pub enum Level {
 
 fn read_level<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     reader: &mut Reader<R>,
+    field: &'static str,
 ) -> Result<Option<Level>, DekuError> {
     let status = bool::from_reader_with_ctx(
         reader,
@@ -75,7 +77,11 @@ fn read_level<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         (true, 3) => Ok(Some(Level::Severe)),
         (true, _) => unreachable!(),
         (false, 0) => Ok(None),
-        (false, _) => Err(DekuError::Assertion("Invalid level value".into())),
+        (false, value) => Err(Error::Status {
+            field,
+            value: value as u64,
+        }
+        .raise()),
     }
 }
 
@@ -106,18 +112,23 @@ fn read_temperature<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         temperature
     );
 
-    let msg = "Invalid temperature value {}°C outside [-80, 60]";
     match (status, value, temperature) {
         (true, _, temperature) if (-80. ..=60.).contains(&temperature) => {
             Ok(Some(temperature))
         }
-        (true, _, _) => Err(DekuError::Assertion(msg.into())),
+        (true, _, temperature) => Err(Error::Range {
+            field: "static_temperature",
+            value: temperature,
+        }
+        .raise()),
         //(false, _) => Ok(None),
         // In practice, I see quite some pressure fields with invalid status but non zero values
         (false, 0, _) => Ok(None),
-        (false, _, _) => {
-            Err(DekuError::Assertion("Invalid temperature value".into()))
+        (false, value, _) => Err(Error::Status {
+            field: "static_temperature",
+            value: value as u64,
         }
+        .raise()),
     }
 }
 
@@ -140,9 +151,11 @@ fn read_pressure<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         //(false, _) => Ok(None),
         // In practice, I see quite some pressure fields with invalid status but non zero values
         (false, 0) => Ok(None),
-        (false, _) => {
-            Err(DekuError::Assertion("Invalid pressure value".into()))
+        (false, value) => Err(Error::Status {
+            field: "static_pressure",
+            value: value as u64,
         }
+        .raise()),
     }
 }
 
@@ -163,7 +176,11 @@ fn read_height<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     match (status, value) {
         (true, value) => Ok(Some(value * 16)),
         (false, 0) => Ok(None),
-        (false, _) => Err(DekuError::Assertion("Invalid height value".into())),
+        (false, value) => Err(Error::Status {
+            field: "radio_height",
+            value: value as u64,
+        }
+        .raise()),
     }
 }
 
@@ -171,7 +188,11 @@ fn fail_if_not_zero(value: u8) -> Result<u8, DekuError> {
     if value == 0 {
         Ok(value)
     } else {
-        Err(DekuError::Assertion("Reserved bits must be zero".into()))
+        Err(Error::Reserved {
+            field: "reserved",
+            value: value as u64,
+        }
+        .raise())
     }
 }
 
@@ -1,10 +1,11 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
  * ## Track and turn report (BDS 5,0)
  */
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "50")]
 pub struct TrackAndTurnReport {
     #[deku(reader = "read_roll(deku::reader)")] // 11 bits
@@ -47,9 +48,11 @@ fn read_roll<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if (sign != 0) | (value != 0) {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: roll angle".into(),
-            ));
+            return Err(Error::Status {
+                field: "roll_angle",
+                value: (sign as u64) << 9 | value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -61,9 +64,11 @@ fn read_roll<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         value as f64 * 45. / 256.
     };
     if roll.abs() > 50. {
-        return Err(DekuError::Assertion(
-            format!("Roll angle: abs({}) > 50", roll).into(),
-        ));
+        return Err(Error::Range {
+            field: "roll_angle",
+            value: roll,
+        }
+        .raise());
     }
     Ok(Some(roll))
 }
@@ -86,9 +91,11 @@ fn read_track<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if (sign != 0) | (value != 0) {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: track angle".into(),
-            ));
+            return Err(Error::Status {
+                field: "track_angle",
+                value: (sign as u64) << 10 | value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -121,9 +128,11 @@ fn read_groundspeed<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: groundspeed".into(),
-            ));
+            return Err(Error::Status {
+                field: "groundspeed",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -131,9 +140,11 @@ fn read_groundspeed<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     let gs = value * 2;
     if gs > 600 {
-        return Err(DekuError::Assertion(
-            format!("Groundspeed value: {} > 600", gs).into(),
-        ));
+        return Err(Error::Range {
+            field: "groundspeed",
+            value: gs as f64,
+        }
+        .raise());
     }
     Ok(Some(gs))
 }
@@ -157,9 +168,11 @@ fn read_rate<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if (sign != 0) | (value != 0) {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: track rate".into(),
-            ));
+            return Err(Error::Status {
+                field: "track_rate",
+                value: (sign as u64) << 9 | value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -179,13 +192,11 @@ fn read_rate<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     if let Some(roll) = roll {
         if roll * rate < 0. {
             // signs must agree: left wing down = turn left
-            return Err(DekuError::Assertion(
-                format!(
-                    "Roll angle {} and track rate {} signs do not agree.",
-                    roll, rate
-                )
-                .into(),
-            ));
+            return Err(Error::Inconsistent {
+                fields: ("roll_angle", "track_rate"),
+                values: (roll, rate),
+            }
+            .raise());
         }
     }
 
@@ -207,9 +218,11 @@ fn read_tas<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: true air speed".into(),
-            ));
+            return Err(Error::Status {
+                field: "true_airspeed",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -218,11 +231,19 @@ fn read_tas<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     let tas = value * 2;
 
     if let Some(gs) = gs {
-        if !(80..=500).contains(&tas) | ((gs as i16 - tas as i16).abs() > 200) {
-            return Err(DekuError::Assertion(format!(
-                "TAS = {} must be within [80, 500] and abs(GS - TAS) = {} < 200",
-                tas, gs
-            ).into()));
+        if !(80..=500).contains(&tas) {
+            return Err(Error::Range {
+                field: "true_airspeed",
+                value: tas as f64,
+            }
+            .raise());
+        }
+        if (gs as i16 - tas as i16).abs() > 200 {
+            return Err(Error::Inconsistent {
+                fields: ("groundspeed", "true_airspeed"),
+                values: (gs as f64, tas as f64),
+            }
+            .raise());
         }
     }
     Ok(Some(tas))
@@ -0,0 +1,88 @@
+use super::{read_altitude, read_angle, read_longitude};
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * ## Position report coarse (BDS 5,1)
+ *
+ * The position of the aircraft, with its pressure altitude.
+ *
+ * | STATUS | LAT | LON | ALT |
+ * | ------ | --- | --- | --- |
+ * | 1      | 20  | 20  | 15  |
+ *
+ * Latitude, longitude and altitude are coded in two's complement. The
+ * resolution is 360/2^20 degrees in latitude and longitude, and 8 ft in
+ * altitude.
+ *
+ * Only the status bit and the range of the values can be checked in the
+ * register itself: in DF=20 replies, the altitude must also match the
+ * altitude reported in the AC field.
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "51")]
+pub struct PositionReportCoarse {
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub status: bool,
+
+    #[deku(
+        reader = "read_angle(deku::reader, *status, 20, 90., \"latitude\")"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+
+    #[deku(reader = "read_longitude(deku::reader, *status, *latitude)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+
+    #[deku(reader = "read_altitude(deku::reader, *status, 15, 8)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Pressure altitude (in ft)
+    pub altitude: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn encode(lat: f64, lon: f64, alt: i32) -> u64 {
+        let lat = (lat / 360. * (1 << 20) as f64).round() as i64 & 0xfffff;
+        let lon = (lon / 360. * (1 << 20) as f64).round() as i64 & 0xfffff;
+        let alt = (alt / 8) as i64 & 0x7fff;
+        1 << 55 | (lat as u64) << 35 | (lon as u64) << 15 | alt as u64
+    }
+
+    #[test]
+    fn test_valid_bds51() {
+        let payload = encode(43.6, -1.4, 36000);
+        let bds51 = PositionReportCoarse::try_from(&payload.to_be_bytes()[1..])
+            .unwrap();
+        assert_relative_eq!(bds51.latitude.unwrap(), 43.6, max_relative = 1e-4);
+        assert_relative_eq!(
+            bds51.longitude.unwrap(),
+            -1.4,
+            max_relative = 1e-3
+        );
+        assert_eq!(bds51.altitude, Some(36000));
+    }
+
+    #[test]
+    fn test_invalid_bds51() {
+        // latitude above 90 degrees
+        let payload = encode(120., 0., 10000);
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(PositionReportCoarse::try_from(bytes).is_err());
+
+        // position without status bit
+        let payload = encode(43.6, -1.4, 36000) & !(1 << 55);
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(PositionReportCoarse::try_from(bytes).is_err());
+        // null coordinates
+        let payload = encode(0., 0., 10000);
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(PositionReportCoarse::try_from(bytes).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use super::bds05::Source;
+use super::{read_altitude, read_value};
+use crate::decode::Error;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * ## Position report fine (BDS 5,2)
+ *
+ * The position of the aircraft in high resolution, with its pressure
+ * altitude or GNSS height.
+ *
+ * | STATUS | FOM | LAT | LON | SRC | ALT |
+ * | ------ | --- | --- | --- | --- | --- |
+ * | 1      | 3   | 17  | 17  | 1   | 17  |
+ *
+ * The latitude and longitude fields contain the 17 least significant bits of
+ * the angles with a resolution of 360/2^24 degrees, i.e. the position within
+ * a cell of 2.8125 degrees: use [`PositionReportFine::position`] with a
+ * reference position (e.g. from BDS 5,1) to recover the full position.
+ * The altitude is coded in two's complement with a resolution of 1 ft.
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "52")]
+pub struct PositionReportFine {
+    #[deku(bits = "1")]
+    #[serde(skip)]
+    pub status: bool,
+
+    #[deku(
+        reader = "read_value(deku::reader, *status, 3, \"figure_of_merit\")"
+    )]
+    #[serde(rename = "FOM", skip_serializing_if = "Option::is_none")]
+    /// Figure of merit and source of the position
+    pub figure_of_merit: Option<u32>,
+
+    #[deku(reader = "read_fine(deku::reader, *status, \"latitude_fine\")")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Latitude within the cell (in degrees)
+    pub latitude_fine: Option<f64>,
+
+    #[deku(reader = "read_longitude(deku::reader, *status, *latitude_fine)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Longitude within the cell (in degrees)
+    pub longitude_fine: Option<f64>,
+
+    #[deku(
+        bits = "1",
+        map = "|gnss: bool| -> Result<_, DekuError> {
+            Ok(if gnss { Source::Gnss } else { Source::Barometric })
+        }"
+    )]
+    /// Pressure altitude or GNSS height
+    pub source: Source,
+
+    #[deku(reader = "read_altitude(deku::reader, *status, 17, 1)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Altitude (in ft)
+    pub altitude: Option<i32>,
+}
+
+/// The size of the cells (in degrees) covered by the fine position
+const CELL: f64 = 360. / (1 << 7) as f64;
+
+impl PositionReportFine {
+    /// Recover the position of the aircraft, using a reference position
+    /// less than a quarter of a cell (about 0.7 degrees) away.
+    ///
+    /// Positions further away from the reference are not plausible: the
+    /// payload is then more likely another register than BDS 5,2.
+    pub fn position(
+        &self,
+        latitude_ref: f64,
+        longitude_ref: f64,
+    ) -> Option<(f64, f64)> {
+        let latitude = resolve(self.latitude_fine?, latitude_ref);
+        let longitude = resolve(self.longitude_fine?, longitude_ref);
+        if latitude.abs() > 90.
+            || (latitude - latitude_ref).abs() > CELL / 4.
+            || (longitude - longitude_ref).abs() > CELL / 4.
+        {
+            return None;
+        }
+        let longitude = match longitude {
+            lon if lon > 180. => lon - 360.,
+            lon if lon <= -180. => lon + 360.,
+            lon => lon,
+        };
+        Some((latitude, longitude))
+    }
+}
+
+fn resolve(fine: f64, reference: f64) -> f64 {
+    fine + ((reference - fine) / CELL).round() * CELL
+}
+
+fn read_fine<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    field: &'static str,
+) -> Result<Option<f64>, DekuError> {
+    let value = read_value(reader, status, 17, field)?;
+    Ok(value.map(|v| v as f64 * 360. / (1 << 24) as f64))
+}
+
+/// A position at the corner of a cell is more likely trailing zeros in
+/// another register
+fn read_longitude<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    latitude: Option<f64>,
+) -> Result<Option<f64>, DekuError> {
+    let longitude = read_fine(reader, status, "longitude_fine")?;
+    if latitude == Some(0.) && longitude == Some(0.) {
+        return Err(Error::Inconsistent {
+            fields: ("latitude_fine", "longitude_fine"),
+            values: (0., 0.),
+        }
+        .raise());
+    }
+    Ok(longitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn encode(lat: f64, lon: f64, gnss: bool, alt: i32) -> u64 {
+        let lat = (lat / 360. * (1 << 24) as f64).round() as i64 & 0x1ffff;
+        let lon = (lon / 360. * (1 << 24) as f64).round() as i64 & 0x1ffff;
+        let alt = alt as i64 & 0x1ffff;
+        1 << 55
+            | 2 << 52
+            | (lat as u64) << 35
+            | (lon as u64) << 18
+            | (gnss as u64) << 17
+            | alt as u64
+    }
+
+    #[test]
+    fn test_valid_bds52() {
+        let payload = encode(43.60123, -1.40456, true, 36120);
+        let bds52 =
+            PositionReportFine::try_from(&payload.to_be_bytes()[1..]).unwrap();
+        assert_eq!(bds52.figure_of_merit, Some(2));
+        assert_eq!(bds52.source, Source::Gnss);
+        assert_eq!(bds52.altitude, Some(36120));
+
+        let (lat, lon) = bds52.position(43., -2.).unwrap();
+        assert_relative_eq!(lat, 43.60123, max_relative = 1e-6);
+        assert_relative_eq!(lon, -1.40456, max_relative = 1e-5);
+
+        // A reference from the other side of the antimeridian
+        let payload = encode(-17.5, -179.9, false, 2000);
+        let bds52 =
+            PositionReportFine::try_from(&payload.to_be_bytes()[1..]).unwrap();
+        let (lat, lon) = bds52.position(-17., 179.5).unwrap();
+        assert_relative_eq!(lat, -17.5, max_relative = 1e-6);
+        assert_relative_eq!(lon, -179.9, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_bds52() {
+        // altitude out of range
+        let payload = encode(43.6, -1.4, false, 60000);
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(PositionReportFine::try_from(bytes).is_err());
+
+        // position without status bit
+        let payload = encode(43.6, -1.4, false, 36000) & !(1 << 55);
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(PositionReportFine::try_from(bytes).is_err());
+
+        // null position within the cell
+        let payload = encode(0., 0., false, 36000);
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(PositionReportFine::try_from(bytes).is_err());
+
+        // position too far from the reference
+        let payload = encode(43.60123, -1.40456, true, 36120);
+        let bds52 =
+            PositionReportFine::try_from(&payload.to_be_bytes()[1..]).unwrap();
+        assert!(bds52.position(44.6, -1.4).is_none());
+    }
+}
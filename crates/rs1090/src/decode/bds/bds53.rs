@@ -0,0 +1,283 @@
+use crate::decode::Error;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * ## Air-referenced state vector (BDS 5,3)
+ *
+ * | STATUS | HDG | STATUS | IAS | STATUS | MACH | STATUS | TAS | STATUS | VR |
+ * | ------ | --- | ------ | --- | ------ | ---- | ------ | --- | ------ | -- |
+ * | 1      | 11  | 1      | 10  | 1      | 9    | 1      | 12  | 1      | 9  |
+ *
+ * The three airspeeds are linked through the altitude of the aircraft: in
+ * DF=20 replies, they are checked against the altitude in the AC field.
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "53")]
+pub struct AirReferencedStateVector {
+    #[deku(reader = "read_heading(deku::reader)")] // 12 bits
+    /// The magnetic heading is the aircraft's heading with respect to the magnetic North
+    #[serde(rename = "heading", skip_serializing_if = "Option::is_none")]
+    pub magnetic_heading: Option<f64>,
+
+    #[deku(reader = "read_ias(deku::reader)")] // 11 bits
+    #[serde(rename = "IAS", skip_serializing_if = "Option::is_none")]
+    /// Indicated Airspeed (IAS) in kts
+    pub indicated_airspeed: Option<u16>,
+
+    #[deku(reader = "read_mach(deku::reader)")] // 10 bits
+    #[serde(rename = "Mach", skip_serializing_if = "Option::is_none")]
+    /// Mach number
+    pub mach_number: Option<f64>,
+
+    #[deku(reader = "read_tas(deku::reader)")] // 13 bits
+    #[serde(rename = "TAS", skip_serializing_if = "Option::is_none")]
+    /// True Airspeed (TAS) in kts
+    pub true_airspeed: Option<f64>,
+
+    #[deku(reader = "read_vertical(deku::reader)")] // 10 bits
+    #[serde(
+        rename = "vrate_barometric",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// Altitude rate (in ft/mn)
+    pub altitude_rate: Option<i16>,
+
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The true heading (in degrees), if the position of the aircraft is known
+    pub true_heading: Option<f64>,
+}
+
+fn read_heading<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+) -> Result<Option<f64>, DekuError> {
+    let status = bool::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let sign = u8::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(10)),
+    )?;
+
+    if !status {
+        if (sign != 0) | (value != 0) {
+            return Err(Error::Status {
+                field: "magnetic_heading",
+                value: (sign as u64) << 10 | value as u64,
+            }
+            .raise());
+        } else {
+            return Ok(None);
+        }
+    }
+
+    let value = if sign == 1 {
+        value as i16 - 1024
+    } else {
+        value as i16
+    };
+    let mut heading = value as f64 * 90. / 512.;
+    if heading < 0. {
+        heading += 360.
+    }
+
+    Ok(Some(heading))
+}
+
+fn read_ias<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+) -> Result<Option<u16>, DekuError> {
+    let status = bool::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(10)),
+    )?;
+
+    if !status {
+        if value != 0 {
+            return Err(Error::Status {
+                field: "indicated_airspeed",
+                value: value as u64,
+            }
+            .raise());
+        } else {
+            return Ok(None);
+        }
+    }
+
+    if (value == 0) | (value > 500) {
+        return Err(Error::Range {
+            field: "indicated_airspeed",
+            value: value as f64,
+        }
+        .raise());
+    }
+    Ok(Some(value))
+}
+
+fn read_mach<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+) -> Result<Option<f64>, DekuError> {
+    let status = bool::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(9)),
+    )?;
+
+    if !status {
+        if value != 0 {
+            return Err(Error::Status {
+                field: "mach_number",
+                value: value as u64,
+            }
+            .raise());
+        } else {
+            return Ok(None);
+        }
+    }
+
+    let mach = value as f64 * 0.008;
+    if (mach == 0.) | (mach > 1.) {
+        return Err(Error::Range {
+            field: "mach_number",
+            value: mach,
+        }
+        .raise());
+    }
+    Ok(Some(mach))
+}
+
+fn read_tas<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+) -> Result<Option<f64>, DekuError> {
+    let status = bool::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(12)),
+    )?;
+
+    if !status {
+        if value != 0 {
+            return Err(Error::Status {
+                field: "true_airspeed",
+                value: value as u64,
+            }
+            .raise());
+        } else {
+            return Ok(None);
+        }
+    }
+
+    let tas = value as f64 * 0.5;
+    if (tas == 0.) | (tas > 600.) {
+        return Err(Error::Range {
+            field: "true_airspeed",
+            value: tas,
+        }
+        .raise());
+    }
+    Ok(Some(tas))
+}
+
+fn read_vertical<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+) -> Result<Option<i16>, DekuError> {
+    let status = bool::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let sign = u8::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(1)),
+    )?;
+    let value = u16::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(8)),
+    )?;
+
+    if !status {
+        if (sign != 0) | (value != 0) {
+            return Err(Error::Status {
+                field: "altitude_rate",
+                value: (sign as u64) << 8 | value as u64,
+            }
+            .raise());
+        } else {
+            return Ok(None);
+        }
+    }
+
+    let value = if sign == 1 {
+        (value as i16 - 256) * 64
+    } else {
+        value as i16 * 64
+    };
+
+    if value.abs() > 6000 {
+        Err(Error::Range {
+            field: "altitude_rate",
+            value: value as f64,
+        }
+        .raise())
+    } else {
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_valid_bds53() {
+        // heading 270°, IAS 280 kts, Mach 0.78, TAS 460 kts, -1024 ft/mn
+        let hdg = (-90. * 512. / 90.) as i64 as u64 & 0x7ff;
+        let payload: u64 = 1 << 55
+            | hdg << 44
+            | 1 << 43
+            | 280 << 33
+            | 1 << 32
+            | ((0.78f64 / 0.008).round() as u64) << 23
+            | 1 << 22
+            | 920 << 10
+            | 1 << 9
+            | (-16i64 as u64 & 0x1ff);
+        let bds53 =
+            AirReferencedStateVector::try_from(&payload.to_be_bytes()[1..])
+                .unwrap();
+        assert_relative_eq!(bds53.magnetic_heading.unwrap(), 270.);
+        assert_eq!(bds53.indicated_airspeed, Some(280));
+        assert_relative_eq!(bds53.mach_number.unwrap(), 0.784);
+        assert_relative_eq!(bds53.true_airspeed.unwrap(), 460.);
+        assert_eq!(bds53.altitude_rate, Some(-1024));
+    }
+
+    #[test]
+    fn test_invalid_bds53() {
+        // IAS above 500 kts
+        let payload: u64 = 1 << 43 | 600 << 33;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(AirReferencedStateVector::try_from(bytes).is_err());
+
+        // TAS without status bit
+        let payload: u64 = 920 << 10;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(AirReferencedStateVector::try_from(bytes).is_err());
+    }
+}
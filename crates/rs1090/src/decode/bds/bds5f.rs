@@ -0,0 +1,89 @@
+#![allow(clippy::suspicious_else_formatting)]
+
+use crate::decode::Error;
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * ## Quasi-static parameter monitoring (BDS 5,F)
+ *
+ * Each quasi-static parameter is monitored by a 2-bit counter, incremented
+ * (modulo 4) every time the parameter changes, so that the ground station
+ * knows when to extract the corresponding register again.
+ *
+ * | ALT | - | HDG | - | FMS | BARO | -  |
+ * | --- | - | --- | - | --- | ---- | -- |
+ * | 2   | 2 | 2   | 2 | 2   | 2    | 44 |
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
+#[serde(tag = "bds", rename = "5F")]
+pub struct QuasiStaticParameterMonitoring {
+    #[deku(bits = 2)]
+    /// MCP/FCU selected altitude (BDS 4,0)
+    pub selected_altitude: u8,
+
+    #[serde(skip)]
+    #[deku(map = "|v: u8| {
+        if v == 0 { Ok(v) } else {
+            Err(Error::Reserved { field: \"reserved1\", value: v as u64 }.raise())
+        }
+    }")]
+    #[deku(bits = 2)]
+    pub reserved1: u8,
+
+    #[deku(bits = 2)]
+    /// MCP/FCU selected heading
+    pub selected_heading: u8,
+
+    #[serde(skip)]
+    #[deku(map = "|v: u8| {
+        if v == 0 { Ok(v) } else {
+            Err(Error::Reserved { field: \"reserved2\", value: v as u64 }.raise())
+        }
+    }")]
+    #[deku(bits = 2)]
+    pub reserved2: u8,
+
+    #[deku(bits = 2)]
+    /// FMS selected altitude (BDS 4,0)
+    pub fms_selected_altitude: u8,
+
+    #[deku(bits = 2)]
+    /// Barometric pressure setting (BDS 4,0)
+    pub barometric_setting: u8,
+
+    #[serde(skip)]
+    #[deku(map = "|v: u64| {
+        if v == 0 { Ok(v) } else {
+            Err(Error::Reserved { field: \"reserved3\", value: v }.raise())
+        }
+    }")]
+    #[deku(bits = 44)]
+    pub reserved3: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_bds5f() {
+        let payload: u64 = 0b01_00_11_00_00_10 << 44;
+        let bds5f = QuasiStaticParameterMonitoring::try_from(
+            &payload.to_be_bytes()[1..],
+        )
+        .unwrap();
+        assert_eq!(bds5f.selected_altitude, 1);
+        assert_eq!(bds5f.selected_heading, 3);
+        assert_eq!(bds5f.fms_selected_altitude, 0);
+        assert_eq!(bds5f.barometric_setting, 2);
+    }
+
+    #[test]
+    fn test_invalid_bds5f() {
+        let payload: u64 = 0b01_00_11_00_00_10 << 44 | 1;
+        let bytes = &payload.to_be_bytes()[1..];
+        assert!(QuasiStaticParameterMonitoring::try_from(bytes).is_err());
+    }
+}
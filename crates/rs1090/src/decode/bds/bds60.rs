@@ -1,5 +1,6 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /**
 * ## Heading and speed report (BDS 6,0)
@@ -14,7 +15,7 @@ use serde::Serialize;
 *   3. Inertial Reference System/Flight Management System
 *
 */
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, DekuRead, Clone)]
 #[serde(tag = "bds", rename = "60")]
 pub struct HeadingAndSpeedReport {
     #[deku(reader = "read_heading(deku::reader)")] // 12 bits
@@ -32,7 +33,9 @@ pub struct HeadingAndSpeedReport {
     /// Mach number
     pub mach_number: Option<f64>,
 
-    #[deku(reader = "read_vertical(deku::reader)")] // 11 bits
+    #[deku(
+        reader = "read_vertical(deku::reader, \"barometric_altitude_rate\")"
+    )] // 11 bits
     /// Barometric altitude rates (in ft/mn) are only derived from
     /// barometer measurements (noisy).
     #[serde(
@@ -41,7 +44,9 @@ pub struct HeadingAndSpeedReport {
     )]
     pub barometric_altitude_rate: Option<i16>,
 
-    #[deku(reader = "read_vertical(deku::reader)")] // 11 bits
+    #[deku(
+        reader = "read_vertical(deku::reader, \"inertial_vertical_velocity\")"
+    )] // 11 bits
     /// Inertial vertical velocities (in ft/mn) are values provided by
     /// navigational equipment from different sources including the FMS
     #[serde(
@@ -49,6 +54,11 @@ pub struct HeadingAndSpeedReport {
         skip_serializing_if = "Option::is_none"
     )]
     pub inertial_vertical_velocity: Option<i16>,
+
+    #[deku(skip, default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The true heading (in degrees), if the position of the aircraft is known
+    pub true_heading: Option<f64>,
 }
 
 fn read_heading<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
@@ -69,9 +79,11 @@ fn read_heading<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if (sign != 0) | (value != 0) {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: heading".into(),
-            ));
+            return Err(Error::Status {
+                field: "magnetic_heading",
+                value: (sign as u64) << 10 | value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -104,19 +116,22 @@ fn read_ias<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: IAS".into(),
-            ));
+            return Err(Error::Status {
+                field: "indicated_airspeed",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
     }
 
     if (value == 0) | (value > 500) {
-        return Err(DekuError::Assertion(
-            format!("IAS value {} is equal to 0 or greater than 500", value)
-                .into(),
-        ));
+        return Err(Error::Range {
+            field: "indicated_airspeed",
+            value: value as f64,
+        }
+        .raise());
     }
     Ok(Some(value))
 }
@@ -136,9 +151,11 @@ fn read_mach<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if value != 0 {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: Mach".into(),
-            ));
+            return Err(Error::Status {
+                field: "mach_number",
+                value: value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -147,9 +164,11 @@ fn read_mach<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     let mach = value as f64 * 2.048 / 512.;
 
     if (mach == 0.) | (mach > 1.) {
-        return Err(DekuError::Assertion(
-            format!("Mach value {} equal to 0 or greater than 1 ", mach).into(),
-        ));
+        return Err(Error::Range {
+            field: "mach_number",
+            value: mach,
+        }
+        .raise());
     }
     if let Some(ias) = ias {
         /*
@@ -161,23 +180,19 @@ fn read_mach<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
          * forbid IAS > 250 and Mach < 0.5
          */
         if (ias > 250) & (mach < 0.4) {
-            return Err(DekuError::Assertion(
-                format!(
-                    "IAS: {} and Mach: {} (250kts is Mach 0.45 at 10,000 ft)",
-                    ias, mach
-                )
-                .into(),
-            ));
+            return Err(Error::Inconsistent {
+                fields: ("indicated_airspeed", "mach_number"),
+                values: (ias as f64, mach),
+            }
+            .raise());
         }
         // this one is easy IAS = 150 (close to take-off) at FL 400 is Mach 0.5
         if (ias < 150) & (mach > 0.5) {
-            return Err(DekuError::Assertion(
-                format!(
-                    "IAS: {} and Mach: {} (150kts is Mach 0.5 at FL400)",
-                    ias, mach
-                )
-                .into(),
-            ));
+            return Err(Error::Inconsistent {
+                fields: ("indicated_airspeed", "mach_number"),
+                values: (ias as f64, mach),
+            }
+            .raise());
         }
     }
     Ok(Some(mach))
@@ -185,6 +200,7 @@ fn read_mach<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
 fn read_vertical<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     reader: &mut Reader<R>,
+    field: &'static str,
 ) -> Result<Option<i16>, DekuError> {
     let status = bool::from_reader_with_ctx(
         reader,
@@ -201,9 +217,11 @@ fn read_vertical<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
 
     if !status {
         if (sign != 0) | (value != 0) {
-            return Err(DekuError::Assertion(
-                "Non-null value with invalid status: vertical rate".into(),
-            ));
+            return Err(Error::Status {
+                field,
+                value: (sign as u64) << 9 | value as u64,
+            }
+            .raise());
         } else {
             return Ok(None);
         }
@@ -220,10 +238,11 @@ fn read_vertical<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
     };
 
     if value.abs() > 6000 {
-        Err(DekuError::Assertion(
-            format!("Vertical rate absolute value {} > 6000", value.abs())
-                .into(),
-        ))
+        Err(Error::Range {
+            field,
+            value: value as f64,
+        }
+        .raise())
     } else {
         Ok(Some(value))
     }
@@ -247,6 +266,7 @@ mod tests {
                 mach_number,
                 barometric_altitude_rate,
                 inertial_vertical_velocity,
+                ..
             } = bds.bds60.unwrap();
             assert_relative_eq!(
                 magnetic_heading.unwrap(),
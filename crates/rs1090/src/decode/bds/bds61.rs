@@ -1,47 +1,98 @@
+use super::bds30::ResolutionAdvisory;
 use crate::decode::IdentityCode;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /**
  * ## Aircraft Status (BDS 6,1)
+ *
+ * The subtype is coded on the three first bits:
+ *
+ * - subtype 1 broadcasts the emergency/priority status and the squawk;
+ * - subtype 2 broadcasts the ACAS resolution advisory (RA) currently active,
+ *   with the same content as in BDS 3,0 Comm-B replies.
  */
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
-pub struct AircraftStatus {
-    /// The subtype can be "emergency/priority" or "ACAS RA"
-    pub subtype: AircraftStatusType,
-    /// The reason for the emergency
-    pub emergency_state: EmergencyState,
-    /// The 13-bit identity code (squawk)
-    pub squawk: IdentityCode,
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+#[deku(id_type = "u8", bits = "3")]
+#[serde(tag = "subtype")]
+pub enum AircraftStatus {
+    #[deku(id = "0")]
+    #[serde(rename = "no_information")]
+    NoInformation {
+        #[deku(bits = "48")]
+        #[serde(skip)]
+        unused: u64,
+    },
+
+    #[deku(id = "1")]
+    #[serde(rename = "emergency_priority")]
+    EmergencyPriority(EmergencyPriorityStatus),
+
+    #[deku(id = "2")]
+    #[serde(rename = "acas_ra")]
+    ACASRaBroadcast(ResolutionAdvisory),
+
+    #[deku(id_pat = "3..=7")]
+    #[serde(rename = "reserved")]
+    Reserved {
+        #[deku(bits = "3")]
+        #[serde(skip)]
+        id: u8,
+        #[serde(skip)]
+        unused: [u8; 6],
+    },
+}
+
+impl AircraftStatus {
+    /// The identity code (squawk), only broadcast in subtype 1
+    pub fn squawk(&self) -> Option<IdentityCode> {
+        match self {
+            Self::EmergencyPriority(status) => Some(status.squawk),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for AircraftStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "  Aircraft Status (BDS 6,1)")?;
-        writeln!(f, "  Squawk:        {:x?}", &self.squawk)?;
-        writeln!(f, "  Emergency/priority:    {}", &self.emergency_state)?;
+        match self {
+            Self::EmergencyPriority(status) => {
+                writeln!(f, "  Squawk:        {:x?}", &status.squawk)?;
+                writeln!(
+                    f,
+                    "  Emergency/priority:    {}",
+                    &status.emergency_state
+                )?;
+            }
+            Self::ACASRaBroadcast(ra) => {
+                writeln!(f, "  ACAS RA broadcast")?;
+                write!(f, "{ra}")?;
+            }
+            _ => {}
+        }
         Ok(())
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
-#[deku(id_type = "u8", bits = "3")]
-#[serde(rename_all = "snake_case")]
-pub enum AircraftStatusType {
-    #[deku(id = "0")]
-    NoInformation,
-    #[deku(id = "1")]
-    #[serde(rename = "emergency_priority")]
-    EmergencyPriorityStatus,
-    #[deku(id = "2")]
-    #[serde(rename = "acas_ra")]
-    ACASRaBroadcast,
-    #[deku(id_pat = "_")]
-    Reserved,
+/// Emergency/priority status (BDS 6,1, subtype 1)
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
+pub struct EmergencyPriorityStatus {
+    /// The reason for the emergency
+    pub emergency_state: EmergencyState,
+    /// The 13-bit identity code (squawk)
+    #[deku(pad_bits_after = "32")] // reserved
+    pub squawk: IdentityCode,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[serde(rename_all = "snake_case")]
 pub enum EmergencyState {
@@ -72,7 +123,7 @@ impl fmt::Display for EmergencyState {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct OperationCodeSurface {
     #[deku(bits = "1")]
     pub poe: u8,
@@ -84,3 +135,61 @@ pub struct OperationCodeSurface {
     #[deku(pad_bits_before = "6")]
     pub lw: u8,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::bds::bds30::ThreatType;
+    use crate::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_reserved_bits() {
+        // The 32 reserved bits after the squawk are part of the message
+        let bytes = hex!("e1000000000000");
+        let ((rest, offset), me) = ME::from_bytes((&bytes, 0)).unwrap();
+        assert!(matches!(me, ME::BDS61(_)));
+        assert!(rest.is_empty());
+        assert_eq!(offset, 0);
+    }
+
+    #[test]
+    fn test_emergency() {
+        let bytes = hex!("8DA2C1B6E112B600000000760759");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        if let ExtendedSquitterADSB(adsb_msg) = msg.df {
+            if let ME::BDS61(status) = adsb_msg.message {
+                assert_eq!(format!("{}", status.squawk().unwrap()), "6513");
+                return;
+            }
+        }
+        unreachable!();
+    }
+
+    #[test]
+    fn test_acas_ra() {
+        let bytes = hex!("8d3c4b26e2c20004f1b740c7b2ff");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["subtype"], "acas_ra");
+        assert_eq!(json["threat_identity"], "3c6dd0");
+        assert!(json.get("squawk").is_none());
+        if let ExtendedSquitterADSB(adsb_msg) = &msg.df {
+            if let ME::BDS61(status @ AircraftStatus::ACASRaBroadcast(ra)) =
+                &adsb_msg.message
+            {
+                assert!(ra.issued_ra);
+                assert_eq!(ra.corrective, Some(true));
+                assert_eq!(ra.downward_sense, Some(false));
+                assert_eq!(ra.positive, Some(true));
+                assert!(!ra.terminated);
+                assert!(matches!(ra.threat_type, ThreatType::ThreatAddress(_)));
+                assert_eq!(status.squawk(), None);
+                let encoded: Vec<u8> = msg.clone().try_into().unwrap();
+                assert_eq!(encoded, bytes);
+                return;
+            }
+        }
+        unreachable!();
+    }
+}
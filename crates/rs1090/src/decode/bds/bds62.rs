@@ -1,13 +1,15 @@
 #![allow(clippy::suspicious_else_formatting)]
 
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /**
  * ## Target State and Status Information (BDS 6,2)
  */
-#[derive(Copy, Clone, Debug, Serialize, PartialEq, DekuRead)]
+#[derive(
+    Copy, Clone, Debug, Serialize, Deserialize, PartialEq, DekuRead, DekuWrite,
+)]
 pub struct TargetStateAndStatusInformation {
     #[deku(bits = "2")] // bits 5..=6
     #[serde(skip)]
@@ -29,7 +31,10 @@ pub struct TargetStateAndStatusInformation {
                 if altitude > 1 {Some(((altitude - 1) * 32 + 16) / 100 * 100)}
                 else {None}
             )
-        }"
+        }",
+        writer = "selected_altitude
+            .map_or(0, |alt| (alt + 15) / 32 + 1)
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(11)))"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The selected altitude in the FMS or MCP/FCU, in feet
@@ -42,7 +47,10 @@ pub struct TargetStateAndStatusInformation {
         map = "|qnh: u32| -> Result<_, DekuError> {
             if qnh == 0 { Ok(None) }
             else { Ok(Some(800.0 + ((qnh - 1) as f32) * 0.8)) }
-        }"
+        }",
+        writer = "barometric_setting
+            .map_or(0, |qnh| libm::roundf((qnh - 800.) / 0.8) as u16 + 1)
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(9)))"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The barometric pressure setting (in millibars)
@@ -59,7 +67,10 @@ pub struct TargetStateAndStatusInformation {
         map = "|heading: u16| -> Result<_, DekuError> {
             if *heading_status {Ok(Some(heading as f32 * 180.0 / 256.0))} 
             else {Ok(None)}
-        }"
+        }",
+        writer = "selected_heading
+            .map_or(0, |hdg| libm::roundf(hdg * 256. / 180.) as u16 % 512)
+            .to_writer(deku::writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(9)))"
     )]
     /// The selected heading (w.r.t magnetic North).
     /// None if not available.
@@ -91,7 +102,8 @@ pub struct TargetStateAndStatusInformation {
         bits = "1",
         map = "|val: bool| -> Result<_, DekuError> {
             if *mode_status {Ok(Some(val))} else {Ok(None)}
-        }"
+        }",
+        writer = "write_flag(deku::writer, autopilot)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Decode the autopilot engagement.
@@ -102,7 +114,8 @@ pub struct TargetStateAndStatusInformation {
         bits = "1",
         map = "|val: bool| -> Result<_, DekuError> {
             if *mode_status {Ok(Some(val))} else {Ok(None)}
-        }"
+        }",
+        writer = "write_flag(deku::writer, vnav_mode)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Decode the VNAV mode.
@@ -113,7 +126,8 @@ pub struct TargetStateAndStatusInformation {
         bits = "1",
         map = "|val: bool| -> Result<_, DekuError> {
             if *mode_status {Ok(Some(val))} else {Ok(None)}
-        }"
+        }",
+        writer = "write_flag(deku::writer, alt_hold)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Decode the altitude hold mode.
@@ -129,7 +143,8 @@ pub struct TargetStateAndStatusInformation {
         bits = "1",
         map = "|val: bool| -> Result<_, DekuError> {
             if *mode_status {Ok(Some(val))} else {Ok(None)}
-        }"
+        }",
+        writer = "write_flag(deku::writer, approach_mode)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Decode the approach mode.
@@ -144,7 +159,8 @@ pub struct TargetStateAndStatusInformation {
         bits = "1",
         map = "|val: bool| -> Result<_, DekuError> {
             if *mode_status {Ok(Some(val))} else {Ok(None)}
-        }"
+        }",
+        writer = "write_flag(deku::writer, lnav_mode)"
     )]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[deku(pad_bits_after = "2")]
@@ -153,6 +169,15 @@ pub struct TargetStateAndStatusInformation {
     pub lnav_mode: Option<bool>,
 }
 
+/// Mode flags are encoded as false when not available
+fn write_flag<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    flag: &Option<bool>,
+) -> Result<(), DekuError> {
+    flag.unwrap_or(false)
+        .to_writer(writer, deku::ctx::BitSize(1))
+}
+
 impl fmt::Display for TargetStateAndStatusInformation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "  Target state and status (BDS 6,2)")?;
@@ -206,7 +231,9 @@ impl fmt::Display for TargetStateAndStatusInformation {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, PartialEq, DekuRead)]
+#[derive(
+    Copy, Clone, Debug, Serialize, Deserialize, PartialEq, DekuRead, DekuWrite,
+)]
 #[deku(id_type = "u8", bits = "1")]
 /// Encode the source of information for selected altitude
 pub enum AltSource {
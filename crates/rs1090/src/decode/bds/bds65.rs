@@ -1,5 +1,6 @@
+use crate::decode::Error;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /**
@@ -13,7 +14,9 @@ use std::fmt;
  *
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[serde(untagged)]
 pub enum AircraftOperationStatus {
@@ -24,7 +27,7 @@ pub enum AircraftOperationStatus {
     Surface(OperationStatusSurface),
 
     #[deku(id_pat = "2..=7")]
-    Reserved(#[deku(bits = "5")] u8, [u8; 5]),
+    Reserved(#[deku(bits = "3")] u8, [u8; 6]),
 }
 
 impl fmt::Display for AircraftOperationStatus {
@@ -38,7 +41,9 @@ impl fmt::Display for AircraftOperationStatus {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct OperationStatusAirborne {
     /// The capacity class
     #[serde(skip)]
@@ -62,9 +67,22 @@ impl fmt::Display for OperationStatusAirborne {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+    Default,
+)]
 pub struct CapabilityClassAirborne {
-    #[deku(bits = "2", assert_eq = "0")]
+    #[deku(
+        bits = "2",
+        reader = "read_reserved(deku::reader, \"CapabilityClassAirborne.reserved0\")"
+    )]
     #[serde(skip)]
     pub reserved0: u8,
 
@@ -78,7 +96,10 @@ pub struct CapabilityClassAirborne {
     #[serde(rename = "CDTI")]
     pub cdti: bool,
 
-    #[deku(bits = "2", assert_eq = "0")]
+    #[deku(
+        bits = "2",
+        reader = "read_reserved(deku::reader, \"CapabilityClassAirborne.reserved1\")"
+    )]
     #[serde(skip)]
     pub reserved1: u8,
 
@@ -125,7 +146,9 @@ impl fmt::Display for CapabilityClassAirborne {
 }
 
 /// Version 2 support only
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct OperationStatusSurface {
     /// The capacity class
     #[serde(skip)]
@@ -158,9 +181,22 @@ impl fmt::Display for OperationStatusSurface {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+    Default,
+)]
 pub struct CapabilityClassSurface {
-    #[deku(bits = "2", assert_eq = "0")]
+    #[deku(
+        bits = "2",
+        reader = "read_reserved(deku::reader, \"CapabilityClassSurface.reserved0\")"
+    )]
     #[serde(skip)]
     pub reserved0: u8,
 
@@ -203,9 +239,22 @@ impl fmt::Display for CapabilityClassSurface {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+    Default,
+)]
 pub struct OperationalMode {
-    #[deku(bits = "2", assert_eq = "0")]
+    #[deku(
+        bits = "2",
+        reader = "read_reserved(deku::reader, \"OperationalMode.reserved\")"
+    )]
     #[serde(skip)]
     reserved: u8,
 
@@ -254,14 +303,16 @@ impl fmt::Display for OperationalMode {
 /// (specification defined in RTCA document DO-260). Version 1 was introduced
 /// around 2008 (DO-260A), and version 2 around 2012 (DO-260B). Version 3 is
 /// currently being developed.
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[serde(tag = "version")]
 pub enum ADSBVersionAirborne {
     #[deku(id = "0")]
     #[serde(rename = "0")] // useless, never happens
     /// ADS-B version 0 (BDS 6,5 undefined, so these messages should not happen)
-    DOC9871AppendixA(Empty),
+    DOC9871AppendixA(#[deku(pad_bits_after = "13")] Empty),
     #[deku(id = "1")]
     #[serde(rename = "1")]
     /// ADS-B version 1 (2008)
@@ -272,10 +323,15 @@ pub enum ADSBVersionAirborne {
     DOC9871AppendixC(AirborneV2),
     #[deku(id_pat = "3..=7")]
     #[serde(rename = "3to7")]
-    Reserved { id: u8 },
+    Reserved {
+        #[deku(bits = "3", pad_bits_after = "13")]
+        id: u8,
+    },
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct AirborneV1 {
     #[deku(bits = "1")]
     #[serde(rename = "NICs")]
@@ -309,7 +365,9 @@ pub struct AirborneV1 {
     pub horizontal_reference_direction: u8,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct AirborneV2 {
     #[deku(bits = "1")]
     #[serde(rename = "NICa")]
@@ -357,14 +415,16 @@ pub struct AirborneV2 {
 /// (specification defined in RTCA document DO-260). Version 1 was introduced
 /// around 2008 (DO-260A), and version 2 around 2012 (DO-260B). Version 3 is
 /// currently being developed.
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[serde(tag = "version")]
 pub enum ADSBVersionSurface {
     #[deku(id = "0")]
     #[serde(rename = "0")]
     /// ADS-B version 0 (BDS 6,5 undefined, so these messages should not happen)
-    DOC9871AppendixA(Empty),
+    DOC9871AppendixA(#[deku(pad_bits_after = "13")] Empty),
     #[deku(id = "1")]
     #[serde(rename = "1")]
     /// ADS-B version 1 (2008)
@@ -375,10 +435,15 @@ pub enum ADSBVersionSurface {
     DOC9871AppendixC(SurfaceV2),
     #[deku(id_pat = "3..=7")]
     #[serde(rename = "3to7")]
-    Reserved { id: u8 },
+    Reserved {
+        #[deku(bits = "3", pad_bits_after = "13")]
+        id: u8,
+    },
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct SurfaceV1 {
     #[deku(bits = "1")]
     #[serde(rename = "NICs")]
@@ -407,7 +472,9 @@ pub struct SurfaceV1 {
     pub horizontal_reference_direction: u8,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct SurfaceV2 {
     #[deku(bits = "1")]
     #[serde(rename = "NICa")]
@@ -443,11 +510,66 @@ pub struct SurfaceV2 {
     pub sil_supplement: u8,
 }
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct Empty {}
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Copy, Clone,
+)]
 pub struct EmptyU8 {
     pub id: u8,
     pub unused: u8,
 }
+
+/// Reserved bits must be null
+fn read_reserved<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    field: &'static str,
+) -> Result<u8, DekuError> {
+    let value = u8::from_reader_with_ctx(reader, deku::ctx::BitSize(2))?;
+    if value != 0 {
+        return Err(Error::Reserved {
+            field,
+            value: value as u64,
+        }
+        .raise());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_reserved_subtype() {
+        let bytes = hex!("fa000000000000");
+        let ((rest, offset), me) = ME::from_bytes((&bytes, 0)).unwrap();
+        assert!(matches!(
+            me,
+            ME::BDS65(AircraftOperationStatus::Reserved(..))
+        ));
+        assert!(rest.is_empty());
+        assert_eq!(offset, 0);
+    }
+
+    #[test]
+    fn test_version_zero() {
+        let bytes = hex!("f8000000000000");
+        let ((rest, offset), me) = ME::from_bytes((&bytes, 0)).unwrap();
+        if let ME::BDS65(AircraftOperationStatus::Airborne(status)) = me {
+            assert_eq!(
+                status.version,
+                ADSBVersionAirborne::DOC9871AppendixA(Empty {})
+            );
+        } else {
+            unreachable!();
+        }
+        assert!(rest.is_empty());
+        assert_eq!(offset, 0);
+    }
+}
@@ -10,10 +10,130 @@ pub mod bds20;
 pub mod bds21;
 pub mod bds30;
 pub mod bds40;
+pub mod bds41;
+pub mod bds42;
+pub mod bds43;
 pub mod bds44;
 pub mod bds45;
 pub mod bds50;
+pub mod bds51;
+pub mod bds52;
+pub mod bds53;
+pub mod bds5f;
 pub mod bds60;
 pub mod bds61;
 pub mod bds62;
 pub mod bds65;
+
+use crate::decode::Error;
+use deku::prelude::*;
+
+// Readers shared by the position registers (BDS 4,2, 5,1 and 5,2), where each
+// group of fields is preceded by a status bit: the fields must then be all
+// zeros when the status bit is not set.
+
+/// Read a value on `bits` bits, None if the status bit is not set
+fn read_value<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    bits: usize,
+    field: &'static str,
+) -> Result<Option<u32>, DekuError> {
+    let value = u32::from_reader_with_ctx(
+        reader,
+        (deku::ctx::Endian::Big, deku::ctx::BitSize(bits)),
+    )?;
+    if !status {
+        if value != 0 {
+            return Err(Error::Status {
+                field,
+                value: value as u64,
+            }
+            .raise());
+        }
+        return Ok(None);
+    }
+    Ok(Some(value))
+}
+
+/// Read a signed value on `bits` bits, None if the status bit is not set
+fn read_signed<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    bits: usize,
+    field: &'static str,
+) -> Result<Option<i32>, DekuError> {
+    let Some(value) = read_value(reader, status, bits, field)? else {
+        return Ok(None);
+    };
+    // two's complement
+    let value = if value & (1 << (bits - 1)) != 0 {
+        value as i32 - (1 << bits)
+    } else {
+        value as i32
+    };
+    Ok(Some(value))
+}
+
+/// Read an angle on `bits` bits (resolution 180/2^(bits-1) degrees), not
+/// larger than `max` degrees
+fn read_angle<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    bits: usize,
+    max: f64,
+    field: &'static str,
+) -> Result<Option<f64>, DekuError> {
+    let Some(value) = read_signed(reader, status, bits, field)? else {
+        return Ok(None);
+    };
+    let angle = value as f64 * 180. / (1 << (bits - 1)) as f64;
+    if angle.abs() > max {
+        return Err(Error::Range {
+            field,
+            value: angle,
+        }
+        .raise());
+    }
+    Ok(Some(angle))
+}
+
+/// Read a longitude on 20 bits: a position at null coordinates is more likely
+/// trailing zeros in another register
+fn read_longitude<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    latitude: Option<f64>,
+) -> Result<Option<f64>, DekuError> {
+    let longitude = read_angle(reader, status, 20, 180., "longitude")?;
+    if latitude == Some(0.) && longitude == Some(0.) {
+        return Err(Error::Inconsistent {
+            fields: ("latitude", "longitude"),
+            values: (0., 0.),
+        }
+        .raise());
+    }
+    Ok(longitude)
+}
+
+/// Read an altitude on `bits` bits with a resolution of `step` ft, between
+/// -1000 ft and 50000 ft
+fn read_altitude<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+    reader: &mut Reader<R>,
+    status: bool,
+    bits: usize,
+    step: i32,
+) -> Result<Option<i32>, DekuError> {
+    let Some(value) = read_signed(reader, status, bits, "altitude")? else {
+        return Ok(None);
+    };
+    let altitude = value * step;
+    if !(-1000..=50000).contains(&altitude) {
+        return Err(Error::Range {
+            field: "altitude",
+            value: altitude as f64,
+        }
+        .raise());
+    }
+    Ok(Some(altitude))
+}
@@ -1,4 +1,6 @@
+use super::atmosphere::{cas2tas, mach2tas};
 use super::bds::bds05::AirbornePosition;
+use super::bds::bds05::Source;
 use super::bds::bds10::DataLinkCapability;
 use super::bds::bds17::CommonUsageGICBCapabilityReport;
 use super::bds::bds18::GICBCapabilityReportPart1;
@@ -7,14 +9,21 @@ use super::bds::bds20::AircraftIdentification;
 use super::bds::bds21::AircraftAndAirlineRegistrationMarkings;
 use super::bds::bds30::ACASResolutionAdvisory;
 use super::bds::bds40::SelectedVerticalIntention;
+use super::bds::bds41::NextWaypointIdentifier;
+use super::bds::bds42::NextWaypointPosition;
+use super::bds::bds43::NextWaypointInformation;
 use super::bds::bds44::MeteorologicalRoutineAirReport;
 use super::bds::bds45::MeteorologicalHazardReport;
 use super::bds::bds50::TrackAndTurnReport;
+use super::bds::bds51::PositionReportCoarse;
+use super::bds::bds52::PositionReportFine;
+use super::bds::bds53::AirReferencedStateVector;
+use super::bds::bds5f::QuasiStaticParameterMonitoring;
 use super::bds::bds60::HeadingAndSpeedReport;
 use super::bds::bds65::AircraftOperationStatus;
 use super::AC13Field;
 use deku::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use tracing::debug;
 
@@ -23,15 +32,26 @@ use tracing::debug;
  *
  * The first four BDS codes (1,0, 1,7, 2,0, 3,0) belong to the ELS service,
  * the next three ones (4,0, 5,0, 6,0) belong to the EHS services,
+ * the codes 4,1, 4,2 and 4,3 report the next waypoint in the flight plan,
+ * the codes 5,1, 5,2 and 5,3 report the position and airspeeds of the
+ * aircraft, and 5,F signals changes in quasi-static parameters,
  * and the last two codes (4,4, 4,5) report meteorological information.
+ *
+ * The BDS 5,F hypothesis is never tried, as only its reserved bits can be
+ * validated: the `bds5f` field is always None (use
+ * [`QuasiStaticParameterMonitoring`] to decode a known BDS 5,F register).
  */
 
-#[derive(Debug, PartialEq, Serialize, Clone, Default)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
 pub struct DF20DataSelector {
     #[serde(skip)]
     /// Set to true if all zeros, then there is no need to parse
     pub is_empty: bool,
 
+    #[serde(skip)]
+    /// The raw MB field, used to encode BDS without a DekuWrite implementation
+    pub mb: [u8; 7],
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds05: Option<AirbornePosition>,
 
@@ -59,6 +79,15 @@ pub struct DF20DataSelector {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds40: Option<SelectedVerticalIntention>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds41: Option<NextWaypointIdentifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds42: Option<NextWaypointPosition>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds43: Option<NextWaypointInformation>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds44: Option<MeteorologicalRoutineAirReport>,
 
@@ -68,19 +97,47 @@ pub struct DF20DataSelector {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds50: Option<TrackAndTurnReport>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds51: Option<PositionReportCoarse>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds52: Option<PositionReportFine>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds53: Option<AirReferencedStateVector>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds5f: Option<QuasiStaticParameterMonitoring>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds60: Option<HeadingAndSpeedReport>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds65: Option<AircraftOperationStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The confidence in the register selected among BDS 5,0 and 6,0, set
+    /// by the [`CommBInference`](super::inference::CommBInference) engine
+    pub bds_confidence: Option<f64>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Clone, Default)]
+/**
+ * ## Comm-B identity reply (MB field in DF=21)
+ *
+ * Without an altitude to check them against, the BDS 5,1 and 5,2 hypotheses
+ * are never tried in DF=21 replies, nor is BDS 5,F as in DF=20 replies: the
+ * `bds51`, `bds52` and `bds5f` fields are always None.
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
 pub struct DF21DataSelector {
     #[serde(skip)]
     /// Set to true if all zeros, then there is no need to parse
     pub is_empty: bool,
 
+    #[serde(skip)]
+    /// The raw MB field, used to encode BDS without a DekuWrite implementation
+    pub mb: [u8; 7],
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds05: Option<AirbornePosition>,
 
@@ -108,6 +165,15 @@ pub struct DF21DataSelector {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds40: Option<SelectedVerticalIntention>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds41: Option<NextWaypointIdentifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds42: Option<NextWaypointPosition>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds43: Option<NextWaypointInformation>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds44: Option<MeteorologicalRoutineAirReport>,
 
@@ -117,11 +183,44 @@ pub struct DF21DataSelector {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds50: Option<TrackAndTurnReport>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds51: Option<PositionReportCoarse>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds52: Option<PositionReportFine>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds53: Option<AirReferencedStateVector>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds5f: Option<QuasiStaticParameterMonitoring>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds60: Option<HeadingAndSpeedReport>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bds65: Option<AircraftOperationStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The confidence in the register selected among BDS 5,0 and 6,0, set
+    /// by the [`CommBInference`](super::inference::CommBInference) engine
+    pub bds_confidence: Option<f64>,
+}
+
+/**
+ * ## ACAS coordination reply (MV field in DF=16)
+ *
+ * The MV field contains the BDS requested by the air-air interrogation (UF=0),
+ * in practice the resolution advisory report (BDS 3,0) sent by ACAS.
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct DF16DataSelector {
+    #[serde(skip)]
+    /// Set to true if all zeros, then there is no need to parse
+    pub is_empty: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bds30: Option<ACASResolutionAdvisory>,
 }
 
 impl fmt::Display for DF21DataSelector {
@@ -136,6 +235,121 @@ impl fmt::Display for DF20DataSelector {
     }
 }
 
+/// Tolerance (in ft) between the altitude in the AC field and the pressure
+/// altitude reported in BDS 5,1 or 5,2
+const BARO_TOLERANCE: i32 = 100;
+/// Relative tolerance between the true airspeeds derived from the indicated
+/// airspeed, the Mach number and the true airspeed in BDS 5,3
+const TAS_TOLERANCE: f64 = 0.1;
+
+/// Tolerance (in ft) between the altitude in the AC field and the GNSS height
+/// reported in BDS 5,2: the difference grows with the altitude when the
+/// temperature departs from the standard atmosphere (about 3% for 8 K).
+fn gnss_tolerance(altitude: u16) -> i32 {
+    200 + altitude as i32 / 32
+}
+
+/// Check the consistency of the airspeeds in BDS 5,3 at the altitude of the
+/// aircraft (in the standard atmosphere)
+fn airspeeds_match(bds53: &AirReferencedStateVector, altitude: f64) -> bool {
+    let tas = [
+        bds53.true_airspeed,
+        bds53
+            .indicated_airspeed
+            .map(|ias| cas2tas(ias as f64, altitude)),
+        bds53.mach_number.map(|mach| mach2tas(mach, altitude)),
+    ];
+    let tas = tas.into_iter().flatten();
+    let (min, max) = tas.fold((f64::INFINITY, 0f64), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    max - min <= TAS_TOLERANCE * max
+}
+
+/// Without the altitude of the aircraft (DF=21), at least two airspeeds in
+/// BDS 5,3 must be consistent at some altitude
+fn airspeeds_consistent(bds53: &AirReferencedStateVector) -> bool {
+    let airspeeds = [
+        bds53.indicated_airspeed.is_some(),
+        bds53.mach_number.is_some(),
+        bds53.true_airspeed.is_some(),
+    ];
+    airspeeds.iter().filter(|&&v| v).count() >= 2
+        && (0..=50000)
+            .step_by(500)
+            .any(|altitude| airspeeds_match(bds53, altitude as f64))
+}
+
+/// BDS 0,5 and 3,0 are encoded from their decoded content. Other BDS are
+/// encoded from the raw MB field received with the message, and data
+/// selectors without any decoded BDS are encoded as all zeros.
+fn write_mb<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+    writer: &mut Writer<W>,
+    bds05: &Option<AirbornePosition>,
+    bds30: &Option<ACASResolutionAdvisory>,
+    mb: &[u8; 7],
+    is_empty: bool,
+) -> Result<(), DekuError> {
+    match (bds05, bds30) {
+        (Some(bds05), _) => bds05.to_writer(writer, ()),
+        (None, Some(bds30)) => bds30.to_writer(writer, ()),
+        (None, None) if *mb != [0; 7] => writer.write_bytes(mb),
+        (None, None) if is_empty => writer.write_bytes(&[0; 7]),
+        (None, None) => Err(DekuError::InvalidParam(
+            "Only BDS 0,5 and 3,0 can be encoded without the raw MB field"
+                .into(),
+        )),
+    }
+}
+
+impl DekuWriter for DF16DataSelector {
+    fn to_writer<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+        &self,
+        writer: &mut Writer<W>,
+        _: (),
+    ) -> Result<(), DekuError> {
+        match &self.bds30 {
+            Some(bds30) => bds30.to_writer(writer, ()),
+            None if self.is_empty => writer.write_bytes(&[0; 7]),
+            None => Err(DekuError::InvalidParam(
+                "Only BDS 3,0 can be encoded in the MV field".into(),
+            )),
+        }
+    }
+}
+
+impl DekuWriter<AC13Field> for DF20DataSelector {
+    fn to_writer<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+        &self,
+        writer: &mut Writer<W>,
+        _: AC13Field,
+    ) -> Result<(), DekuError> {
+        let is_empty = *self
+            == Self {
+                is_empty: self.is_empty,
+                mb: self.mb,
+                ..Self::default()
+            };
+        write_mb(writer, &self.bds05, &self.bds30, &self.mb, is_empty)
+    }
+}
+
+impl DekuWriter for DF21DataSelector {
+    fn to_writer<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+        &self,
+        writer: &mut Writer<W>,
+        _: (),
+    ) -> Result<(), DekuError> {
+        let is_empty = *self
+            == Self {
+                is_empty: self.is_empty,
+                mb: self.mb,
+                ..Self::default()
+            };
+        write_mb(writer, &self.bds05, &self.bds30, &self.mb, is_empty)
+    }
+}
+
 impl DekuReader<'_, AC13Field> for DF20DataSelector {
     fn from_reader_with_ctx<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         reader: &mut Reader<R>,
@@ -152,6 +366,7 @@ impl DekuReader<'_, AC13Field> for DF20DataSelector {
             "Decoding {:?} according to various hypotheses",
             buf.as_slice()
         );
+        result.mb.copy_from_slice(&buf);
 
         if buf.iter().all(|&x| x == 0) {
             result.is_empty = true;
@@ -206,6 +421,18 @@ impl DekuReader<'_, AC13Field> for DF20DataSelector {
             Ok(bds40) => result.bds40 = Some(bds40),
             Err(e) => debug!("Hypothesis BDS40: {}", e.to_string()),
         }
+        match NextWaypointIdentifier::try_from(buf.as_slice()) {
+            Ok(bds41) => result.bds41 = Some(bds41),
+            Err(e) => debug!("Hypothesis BDS41: {}", e.to_string()),
+        }
+        match NextWaypointPosition::try_from(buf.as_slice()) {
+            Ok(bds42) => result.bds42 = Some(bds42),
+            Err(e) => debug!("Hypothesis BDS42: {}", e.to_string()),
+        }
+        match NextWaypointInformation::try_from(buf.as_slice()) {
+            Ok(bds43) => result.bds43 = Some(bds43),
+            Err(e) => debug!("Hypothesis BDS43: {}", e.to_string()),
+        }
         match MeteorologicalRoutineAirReport::try_from(buf.as_slice()) {
             Ok(bds44) => result.bds44 = Some(bds44),
             Err(e) => debug!("Hypothesis BDS44: {}", e.to_string()),
@@ -218,6 +445,37 @@ impl DekuReader<'_, AC13Field> for DF20DataSelector {
             Ok(bds50) => result.bds50 = Some(bds50),
             Err(e) => debug!("Hypothesis BDS50: {}", e.to_string()),
         }
+        match PositionReportCoarse::try_from(buf.as_slice()) {
+            Ok(bds51) => match bds51.altitude {
+                Some(alt) if (alt - ac.0 as i32).abs() <= BARO_TOLERANCE => {
+                    result.bds51 = Some(bds51)
+                }
+                _ => debug!("Hypothesis BDS51: altitude mismatch"),
+            },
+            Err(e) => debug!("Hypothesis BDS51: {}", e.to_string()),
+        }
+        match PositionReportFine::try_from(buf.as_slice()) {
+            Ok(bds52) => {
+                let tolerance = match bds52.source {
+                    Source::Barometric => BARO_TOLERANCE,
+                    Source::Gnss => gnss_tolerance(ac.0),
+                };
+                match bds52.altitude {
+                    Some(alt) if (alt - ac.0 as i32).abs() <= tolerance => {
+                        result.bds52 = Some(bds52)
+                    }
+                    _ => debug!("Hypothesis BDS52: altitude mismatch"),
+                }
+            }
+            Err(e) => debug!("Hypothesis BDS52: {}", e.to_string()),
+        }
+        match AirReferencedStateVector::try_from(buf.as_slice()) {
+            Ok(bds53) if airspeeds_match(&bds53, ac.0 as f64) => {
+                result.bds53 = Some(bds53)
+            }
+            Ok(_) => debug!("Hypothesis BDS53: airspeed mismatch"),
+            Err(e) => debug!("Hypothesis BDS53: {}", e.to_string()),
+        }
         match HeadingAndSpeedReport::try_from(buf.as_slice()) {
             Ok(bds60) => result.bds60 = Some(bds60),
             Err(e) => debug!("Hypothesis BDS60: {}", e.to_string()),
@@ -243,6 +501,36 @@ impl DekuReader<'_, AC13Field> for DF20DataSelector {
     }
 }
 
+impl DekuReader<'_> for DF16DataSelector {
+    fn from_reader_with_ctx<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+        reader: &mut Reader<R>,
+        _: (),
+    ) -> Result<Self, DekuError>
+    where
+        Self: Sized,
+    {
+        let mut result = Self::default();
+        let res = reader.read_bits(56)?;
+        let buf = res.unwrap().into_vec();
+        debug!(
+            "Decoding {:?} according to various hypotheses",
+            buf.as_slice()
+        );
+
+        if buf.iter().all(|&x| x == 0) {
+            result.is_empty = true;
+            return Ok(result);
+        }
+
+        match ACASResolutionAdvisory::try_from(buf.as_slice()) {
+            Ok(bds30) => result.bds30 = Some(bds30),
+            Err(e) => debug!("Hypothesis BDS30: {}", e.to_string()),
+        }
+
+        Ok(result)
+    }
+}
+
 impl DekuReader<'_> for DF21DataSelector {
     fn from_reader_with_ctx<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
         reader: &mut Reader<R>,
@@ -258,6 +546,7 @@ impl DekuReader<'_> for DF21DataSelector {
             "Decoding {:?} according to various hypotheses",
             buf.as_slice()
         );
+        result.mb.copy_from_slice(&buf);
 
         if buf.iter().all(|&x| x == 0) {
             result.is_empty = true;
@@ -313,6 +602,18 @@ impl DekuReader<'_> for DF21DataSelector {
             Ok(bds40) => result.bds40 = Some(bds40),
             Err(e) => debug!("Hypothesis BDS40: {}", e.to_string()),
         }
+        match NextWaypointIdentifier::try_from(buf.as_slice()) {
+            Ok(bds41) => result.bds41 = Some(bds41),
+            Err(e) => debug!("Hypothesis BDS41: {}", e.to_string()),
+        }
+        match NextWaypointPosition::try_from(buf.as_slice()) {
+            Ok(bds42) => result.bds42 = Some(bds42),
+            Err(e) => debug!("Hypothesis BDS42: {}", e.to_string()),
+        }
+        match NextWaypointInformation::try_from(buf.as_slice()) {
+            Ok(bds43) => result.bds43 = Some(bds43),
+            Err(e) => debug!("Hypothesis BDS43: {}", e.to_string()),
+        }
         match MeteorologicalRoutineAirReport::try_from(buf.as_slice()) {
             Ok(bds44) => result.bds44 = Some(bds44),
             Err(e) => debug!("Hypothesis BDS44: {}", e.to_string()),
@@ -325,6 +626,17 @@ impl DekuReader<'_> for DF21DataSelector {
             Ok(bds50) => result.bds50 = Some(bds50),
             Err(e) => debug!("Hypothesis BDS50: {}", e.to_string()),
         }
+
+        // On purpose: do not try bds51 and bds52 here, for the same reason
+        // as bds05: there is no way to validate the altitude. As in DF20,
+        // neither try bds5f, which only has its reserved bits to be validated.
+        match AirReferencedStateVector::try_from(buf.as_slice()) {
+            Ok(bds53) if airspeeds_consistent(&bds53) => {
+                result.bds53 = Some(bds53)
+            }
+            Ok(_) => debug!("Hypothesis BDS53: airspeed mismatch"),
+            Err(e) => debug!("Hypothesis BDS53: {}", e.to_string()),
+        }
         match HeadingAndSpeedReport::try_from(buf.as_slice()) {
             Ok(bds60) => result.bds60 = Some(bds60),
             Err(e) => debug!("Hypothesis BDS60: {}", e.to_string()),
@@ -369,4 +681,124 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    /// A DF20 reply with the altitude `altitude` and the MB field `mb`
+    fn comm_b_reply(altitude: u16, mb: u64) -> Message {
+        let bytes = hex!("a0001910cc300030aa0000eae004");
+        let (_, mut msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        if let CommBAltitudeReply { ac, bds, .. } = &mut msg.df {
+            *ac = AC13Field(altitude);
+            *bds = DF20DataSelector {
+                is_empty: true,
+                ..Default::default()
+            };
+        }
+        let mut bytes = msg.to_bytes().unwrap();
+        bytes[4..11].copy_from_slice(&mb.to_be_bytes()[1..]);
+        Message::from_bytes((&bytes, 0)).unwrap().1
+    }
+
+    #[test]
+    fn test_bds51_altitude() {
+        // 43.6°N, 1.4°W at 36,000 ft
+        let mb: u64 = 1 << 55 | 0x1f012 << 35 | 0xff012 << 15 | 4500;
+        let msg = comm_b_reply(36000, mb);
+        let CommBAltitudeReply { bds, .. } = msg.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds51.unwrap().altitude, Some(36000));
+
+        let msg = comm_b_reply(30000, mb);
+        let CommBAltitudeReply { bds, .. } = msg.df else {
+            unreachable!()
+        };
+        assert!(bds.bds51.is_none());
+    }
+
+    #[test]
+    fn test_bds53_altitude() {
+        // IAS 260 kts, Mach 0.784, TAS 450 kts
+        let mb: u64 =
+            1 << 43 | 260 << 33 | 1 << 32 | 98 << 23 | 1 << 22 | 900 << 10;
+        let msg = comm_b_reply(36000, mb);
+        let CommBAltitudeReply { bds, .. } = msg.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds53.unwrap().true_airspeed, Some(450.));
+
+        // These airspeeds are not consistent at lower altitudes
+        let msg = comm_b_reply(20000, mb);
+        let CommBAltitudeReply { bds, .. } = msg.df else {
+            unreachable!()
+        };
+        assert!(bds.bds53.is_none());
+    }
+
+    #[test]
+    fn test_bds5f() {
+        // Valid as BDS 5,F, but not validated
+        let mb: u64 = 0b01_00_11_00_00_10 << 44;
+        let bytes = &mb.to_be_bytes()[1..];
+        assert!(QuasiStaticParameterMonitoring::try_from(bytes).is_ok());
+        let CommBAltitudeReply { bds, .. } = comm_b_reply(36000, mb).df else {
+            unreachable!()
+        };
+        assert!(bds.bds5f.is_none());
+    }
+
+    #[test]
+    fn test_bds52_gnss() {
+        // GNSS height 36,600 ft
+        let mb: u64 = 1 << 55 | 2 << 52 | 0x1f012 << 35 | 1 << 17 | 36600;
+        let msg = comm_b_reply(36000, mb);
+        let CommBAltitudeReply { bds, .. } = msg.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds52.unwrap().altitude, Some(36600));
+
+        let msg = comm_b_reply(35000, mb);
+        let CommBAltitudeReply { bds, .. } = msg.df else {
+            unreachable!()
+        };
+        assert!(bds.bds52.is_none());
+    }
+
+    /// A DF21 reply with the MB field `mb`
+    fn identity_reply(mb: u64) -> Message {
+        let mut bytes = hex!("a8001ebcfffb23286004a73f6a5b");
+        bytes[4..11].copy_from_slice(&mb.to_be_bytes()[1..]);
+        Message::from_bytes((&bytes, 0)).unwrap().1
+    }
+
+    #[test]
+    fn test_df21_bds53_bds5f() {
+        // IAS 260 kts, Mach 0.784, TAS 450 kts: consistent at 36,000 ft
+        let mb: u64 =
+            1 << 43 | 260 << 33 | 1 << 32 | 98 << 23 | 1 << 22 | 900 << 10;
+        let CommBIdentityReply { bds, .. } = identity_reply(mb).df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds53.unwrap().true_airspeed, Some(450.));
+
+        // IAS 260 kts and Mach 0.3 are never consistent
+        let mb: u64 = 1 << 43 | 260 << 33 | 1 << 32 | 38 << 23;
+        let CommBIdentityReply { bds, .. } = identity_reply(mb).df else {
+            unreachable!()
+        };
+        assert!(bds.bds53.is_none());
+
+        // A heading and an airspeed only cannot be validated
+        let mb: u64 = 1 << 55 | 1 << 43 | 260 << 33;
+        let CommBIdentityReply { bds, .. } = identity_reply(mb).df else {
+            unreachable!()
+        };
+        assert!(bds.bds53.is_none());
+
+        // Valid as BDS 5,F, but not validated
+        let mb: u64 = 0b01_00_11_00_00_10 << 44;
+        let CommBIdentityReply { bds, .. } = identity_reply(mb).df else {
+            unreachable!()
+        };
+        assert!(bds.bds5f.is_none());
+    }
 }
@@ -16,8 +16,9 @@
 use super::adsb::ME;
 use super::bds::bds05::AirbornePosition;
 use super::bds::bds06::SurfacePosition;
-use super::{TimedMessage, DF, ICAO};
-use crate::data::airports::one_airport;
+use super::integrity::IntegrityState;
+use super::{MilitaryApplication, TimedMessage, DF, ICAO};
+use crate::data::airports::{one_airport, AIRPORTS};
 use deku::prelude::*;
 use libm::fabs;
 use regex::Regex;
@@ -44,7 +45,17 @@ fn dist_haversine(pos1: &Position, pos2: &Position) -> f64 {
 }
 
 /// A flag to qualify a CPR position as odd or even
-#[derive(Debug, PartialEq, Eq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+)]
 #[deku(id_type = "u8", bits = "1")]
 #[serde(rename_all = "snake_case")]
 pub enum CPRFormat {
@@ -110,6 +121,115 @@ pub struct AircraftState {
     odd_msg: Option<AirbornePosition>,
     even_ts: f64,
     even_msg: Option<AirbornePosition>,
+    odd_surface: Option<(f64, SurfacePosition)>,
+    even_surface: Option<(f64, SurfacePosition)>,
+    /// Number of consecutive rejected positions
+    rejections: u32,
+    /// ADS-B version and NIC supplements
+    integrity: IntegrityState,
+}
+
+impl AircraftState {
+    fn new(timestamp: f64) -> Self {
+        AircraftState {
+            timestamp,
+            odd_ts: timestamp,
+            even_ts: timestamp,
+            ..Default::default()
+        }
+    }
+}
+
+/// The reason why a decoded position was rejected
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Rejection {
+    /// The NUCp (or NIC) of the message is below the minimum value
+    Accuracy,
+    /// The position is too far away from the receiver
+    Range,
+    /// The speed implied by the previous position is too high
+    Speed,
+    /// The global and local decodings of the position do not match
+    Divergence,
+}
+
+/**
+ * Reasonableness checks applied to decoded positions.
+ *
+ * Rejected positions are not set in the message, which is flagged with the
+ * reason of the rejection instead. After `max_rejections` consecutive
+ * rejections, the track of the aircraft is reset, and the next position is
+ * decoded globally again from a pair of even and odd messages.
+ */
+#[derive(Debug, Clone)]
+pub struct PositionFilter {
+    /// Maximum speed (in kts) implied by two consecutive positions
+    pub max_speed: f64,
+    /// Maximum distance (in km) to the receiver reference, if any
+    pub max_range: Option<f64>,
+    /// Minimum NUCp (derived from the typecode, as the NIC in ADS-B v2)
+    pub min_nuc_p: u8,
+    /// Number of consecutive rejections before resetting the track
+    pub max_rejections: u32,
+}
+
+impl Default for PositionFilter {
+    fn default() -> Self {
+        Self {
+            max_speed: 1000.,
+            max_range: None,
+            min_nuc_p: 0,
+            max_rejections: 3,
+        }
+    }
+}
+
+/// Tolerance (in km) on the distance between consecutive positions, to
+/// account for the jitter of timestamps between receivers
+const DISTANCE_TOLERANCE: f64 = 1.;
+
+/// Maximum distance (in km) between the global and the local decodings
+const DIVERGENCE_DISTANCE: f64 = 5.;
+
+impl PositionFilter {
+    fn check(
+        &self,
+        pos: &Position,
+        nuc_p: u8,
+        timestamp: f64,
+        latest: &AircraftState,
+        reference: &Option<Position>,
+    ) -> Result<(), Rejection> {
+        if nuc_p < self.min_nuc_p {
+            return Err(Rejection::Accuracy);
+        }
+        if let (Some(max_range), Some(reference)) = (self.max_range, reference)
+        {
+            if dist_haversine(pos, reference) > max_range {
+                return Err(Rejection::Range);
+            }
+        }
+        if let Some(latest_pos) = latest.pos {
+            let elapsed = (timestamp - latest.timestamp).max(0.);
+            let max_distance = self.max_speed * 1.852 * elapsed / 3600.;
+            if dist_haversine(pos, &latest_pos)
+                > max_distance + DISTANCE_TOLERANCE
+            {
+                return Err(Rejection::Speed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Count a rejection, and reset the track after too many of them
+    fn reject(&self, latest: &mut AircraftState) {
+        latest.rejections += 1;
+        if latest.rejections >= self.max_rejections {
+            latest.pos = None;
+            latest.rejections = 0;
+        }
+    }
 }
 
 /// NZ represents the number of latitude zones between the equator and a pole.
@@ -119,6 +239,15 @@ const NZ: f64 = 15.0;
 /// CPR_MAX is 2^17 since CPR lat and lon values are encoded on 17 bits
 const CPR_MAX: f64 = 131_072.0;
 
+/// Maximum duration (in seconds) between the even and odd surface position
+/// messages used for global decoding (surface positions are broadcast less
+/// often than airborne positions when the aircraft is stationary)
+const SURFACE_PAIR_TIMEOUT: f64 = 25.;
+
+/// Maximum distance (in km) to the closest airport for the global decoding
+/// of surface positions without any reference
+const AIRPORT_MAX_DISTANCE: f64 = 10.;
+
 /// Given the latitude, this function yields the number of longitude zones
 /// between 1 and 59.
 /// The nl function uses the precomputed table from 1090-WP-9-14
@@ -404,13 +533,180 @@ pub fn surface_position_with_reference(
     })
 }
 
+/**
+ * Decode the candidate surface positions from a pair of even and odd position
+ * messages.
+ *
+ * Surface positions are encoded over a quarter of the zone size, so the global
+ * decoding yields two possible latitudes (one per hemisphere) and four
+ * possible longitudes (one per quadrant).
+ */
+fn surface_position_candidates(
+    oldest: &SurfacePosition,
+    latest: &SurfacePosition,
+) -> Option<Vec<Position>> {
+    let (even_frame, odd_frame) = match (oldest.parity, latest.parity) {
+        (CPRFormat::Even, CPRFormat::Odd) => (oldest, latest),
+        (CPRFormat::Odd, CPRFormat::Even) => (latest, oldest),
+        _ => return None,
+    };
+
+    let cpr_lat_even = f64::from(even_frame.lat_cpr) / CPR_MAX;
+    let cpr_lon_even = f64::from(even_frame.lon_cpr) / CPR_MAX;
+    let cpr_lat_odd = f64::from(odd_frame.lat_cpr) / CPR_MAX;
+    let cpr_lon_odd = f64::from(odd_frame.lon_cpr) / CPR_MAX;
+
+    let j = libm::floor(59.0 * cpr_lat_even - 60.0 * cpr_lat_odd + 0.5);
+
+    let lat_even = D_LAT_EVEN / 4. * (modulo(j, 60.) + cpr_lat_even);
+    let lat_odd = D_LAT_ODD / 4. * (modulo(j, 59.) + cpr_lat_odd);
+
+    let mut candidates = Vec::new();
+    // Northern and southern hemispheres
+    for offset in [0., -90.] {
+        let (lat_even, lat_odd) = (lat_even + offset, lat_odd + offset);
+        if nl(lat_even) != nl(lat_odd) {
+            continue;
+        }
+        let (lat, p, c) = match latest.parity {
+            CPRFormat::Even => (lat_even, 0, cpr_lon_even),
+            CPRFormat::Odd => (lat_odd, 1, cpr_lon_odd),
+        };
+        let ni = std::cmp::max(nl(lat) - p, 1) as f64;
+        let m = libm::floor(
+            cpr_lon_even * (nl(lat) - 1) as f64 - cpr_lon_odd * nl(lat) as f64
+                + 0.5,
+        );
+        let lon = (90.0 / ni) * (modulo(m, ni) + c);
+        for quadrant in 0..4 {
+            let mut longitude = lon + 90. * quadrant as f64;
+            if longitude >= 180.0 {
+                longitude -= 360.0;
+            }
+            candidates.push(Position {
+                latitude: lat,
+                longitude,
+            });
+        }
+    }
+    Some(candidates)
+}
+
+/**
+ * Decode surface position from a pair of even and odd position messages,
+ * knowing a reference location in the same quadrant, e.g. the last airborne
+ * position of the aircraft. The candidate position closest to the reference
+ * is selected.
+ */
+pub fn surface_position(
+    oldest: &SurfacePosition,
+    latest: &SurfacePosition,
+    latitude_ref: f64,
+    longitude_ref: f64,
+) -> Option<Position> {
+    let reference = Position {
+        latitude: latitude_ref,
+        longitude: longitude_ref,
+    };
+    surface_position_candidates(oldest, latest)?
+        .into_iter()
+        .min_by(|a, b| {
+            dist_haversine(a, &reference)
+                .total_cmp(&dist_haversine(b, &reference))
+        })
+}
+
+/**
+ * Decode surface position from a pair of even and odd position messages,
+ * without any reference location: the candidate position closest to a known
+ * airport is selected, if it is close enough.
+ */
+pub fn surface_position_near_airport(
+    oldest: &SurfacePosition,
+    latest: &SurfacePosition,
+) -> Option<Position> {
+    surface_position_candidates(oldest, latest)?
+        .into_iter()
+        .map(|pos| {
+            let distance = AIRPORTS
+                .iter()
+                .map(|airport| {
+                    haversine(
+                        pos.latitude,
+                        pos.longitude,
+                        airport.lat,
+                        airport.lon,
+                    )
+                })
+                .fold(f64::INFINITY, f64::min);
+            (pos, distance)
+        })
+        .filter(|(_, distance)| *distance < AIRPORT_MAX_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(pos, _)| pos)
+}
+
+/**
+ * Encode a position into the CPR latitude and longitude values of an even or
+ * odd airborne position message (BDS 0,5).
+ */
+pub fn encode_airborne_position(
+    latitude: f64,
+    longitude: f64,
+    parity: CPRFormat,
+) -> (u32, u32) {
+    encode_position(latitude, longitude, parity, 360.)
+}
+
+/**
+ * Encode a position into the CPR latitude and longitude values of an even or
+ * odd surface position message (BDS 0,6).
+ *
+ * Surface positions are encoded on 19 bits, only the 17 lower bits are
+ * transmitted: this is equivalent to a 17 bit encoding over a quarter of
+ * the zone size.
+ */
+pub fn encode_surface_position(
+    latitude: f64,
+    longitude: f64,
+    parity: CPRFormat,
+) -> (u32, u32) {
+    encode_position(latitude, longitude, parity, 90.)
+}
+
+fn encode_position(
+    latitude: f64,
+    longitude: f64,
+    parity: CPRFormat,
+    span: f64,
+) -> (u32, u32) {
+    let i = match parity {
+        CPRFormat::Even => 0,
+        CPRFormat::Odd => 1,
+    };
+    let d_lat = span / (4.0 * NZ - i as f64);
+    let yz = libm::floor(CPR_MAX * modulo(latitude, d_lat) / d_lat + 0.5);
+    let r_lat = d_lat * (yz / CPR_MAX + libm::floor(latitude / d_lat));
+
+    let ni = std::cmp::max(nl(r_lat) - i, 1) as f64;
+    let d_lon = span / ni;
+    let xz = libm::floor(CPR_MAX * modulo(longitude, d_lon) / d_lon + 0.5);
+
+    (modulo(yz, CPR_MAX) as u32, modulo(xz, CPR_MAX) as u32)
+}
+
 pub type UpdateIf = Option<Box<dyn Fn(&AirbornePosition) -> bool>>;
 
 /**
  * Mutates the ME message based on recent past positions (parameter `timestamp`)
  * of the same aircraft (parameter `icao24`). For surface messages, the
  * reference position will be considered; and possibly updated based on low
- * altitude positions detected.
+ * altitude positions detected. Without any reference position, surface
+ * positions are decoded from pairs of even and odd messages.
+ *
+ * Aircraft operation status (BDS 6,5) and airborne velocity (BDS 0,9)
+ * messages update the integrity information of the aircraft (ADS-B version,
+ * NIC supplements, NACv and SIL) attached to the following positions.
  *
  * - `aircraft` is a hashmap of aircraft containing their most recent state;
  * - `reference` is a (possibly None) set of coordinates.
@@ -422,95 +718,23 @@ pub fn decode_position(
     aircraft: &mut BTreeMap<ICAO, AircraftState>,
     reference: &mut Option<Position>,
     update_reference: &UpdateIf,
+    filter: &PositionFilter,
 ) {
-    let latest = aircraft.entry(*icao24).or_insert(AircraftState {
-        timestamp,
-        pos: None,
-        odd_ts: timestamp,
-        odd_msg: None,
-        even_ts: timestamp,
-        even_msg: None,
-    });
     match message {
-        ME::BDS05(airborne) => {
-            let mut pos: Option<Position> = None;
-
-            let latest_timestamp = match airborne.parity {
-                CPRFormat::Even => latest.odd_ts,
-                CPRFormat::Odd => latest.even_ts,
-            };
-            let latest_msg = match airborne.parity {
-                CPRFormat::Even => latest.odd_msg,
-                CPRFormat::Odd => latest.even_msg,
-            };
-
-            // This may happen with several sources of data coming on one mpsc
-            if (timestamp - latest_timestamp) < 0. {
-                return;
-            }
-
-            if (timestamp - latest_timestamp) < 10. {
-                // First decoding based on odd/even (global)
-                // This is the most reasonable way to decode
-                pos = match latest_msg {
-                    Some(oldest) => airborne_position(&oldest, airborne),
-                    None => None,
-                };
-            }
-
-            // If failed try to use previous reference
-            // This is tricky though, use with extra care
-            if pos.is_none() & ((timestamp - latest.timestamp) < 180.) {
-                if let Some(latest_pos) = latest.pos {
-                    pos = airborne_position_with_reference(
-                        airborne,
-                        latest_pos.latitude,
-                        latest_pos.longitude,
-                    )
-                }
-            }
-
-            if let Some(new_pos) = pos {
-                if let Some(latest_pos) = latest.pos {
-                    // Invalidate if new position is not reasonable
-                    if dist_haversine(&new_pos, &latest_pos) > 50. {
-                        pos = None
-                    }
-                }
-            }
-
-            if let Some(pos) = pos {
-                // First update the message
-                airborne.latitude = Some(pos.latitude);
-                airborne.longitude = Some(pos.longitude);
-                // Then update the reference in aircraft
-                latest.pos = Some(pos);
-                latest.timestamp = timestamp;
-                // If necessary (according to the callback) update the reference position
-                if let Some(update_reference) = update_reference {
-                    if update_reference(airborne) {
-                        *reference = Some(Position {
-                            latitude: pos.latitude,
-                            longitude: pos.longitude,
-                        })
-                    }
-                }
-            } else {
-                latest.pos = None;
-            }
-
-            match airborne.parity {
-                CPRFormat::Even => {
-                    latest.even_msg = Some(*airborne);
-                    latest.even_ts = timestamp
-                }
-                CPRFormat::Odd => {
-                    latest.odd_msg = Some(*airborne);
-                    latest.odd_ts = timestamp
-                }
-            }
-        }
+        ME::BDS05(airborne) => decode_airborne_position(
+            airborne,
+            timestamp,
+            icao24,
+            aircraft,
+            reference,
+            update_reference,
+            filter,
+        ),
         ME::BDS06(surface) => {
+            let latest = aircraft
+                .entry(*icao24)
+                .or_insert_with(|| AircraftState::new(timestamp));
+            surface.integrity = Some(latest.integrity.surface(surface));
             let mut pos = None;
             if let Some(latest_pos) = latest.pos {
                 let surface_pos = surface_position_with_reference(
@@ -533,19 +757,190 @@ pub fn decode_position(
                     )
                 }
             }
+            // Without any nearby reference, decode the position from a pair
+            // of even and odd messages: the last airborne position (or the
+            // closest airport) solves the ambiguity on the quadrant.
+            let other = match surface.parity {
+                CPRFormat::Even => latest.odd_surface,
+                CPRFormat::Odd => latest.even_surface,
+            };
+            if let (None, Some((other_ts, other))) = (pos, other) {
+                let delta = timestamp - other_ts;
+                if (0. ..SURFACE_PAIR_TIMEOUT).contains(&delta) {
+                    pos = match latest.pos {
+                        Some(last) => surface_position(
+                            &other,
+                            surface,
+                            last.latitude,
+                            last.longitude,
+                        ),
+                        None => surface_position_near_airport(&other, surface),
+                    }
+                }
+            }
             if let Some(pos) = pos {
-                // First update the message
-                surface.latitude = Some(pos.latitude);
-                surface.longitude = Some(pos.longitude);
-                // Then update the reference in aircraft
-                latest.pos = Some(pos);
-                latest.timestamp = timestamp;
+                let check = filter.check(
+                    &pos,
+                    surface.nuc_p,
+                    timestamp,
+                    latest,
+                    reference,
+                );
+                match check {
+                    Ok(()) => {
+                        // First update the message
+                        surface.latitude = Some(pos.latitude);
+                        surface.longitude = Some(pos.longitude);
+                        // Then update the reference in aircraft
+                        latest.pos = Some(pos);
+                        latest.timestamp = timestamp;
+                        latest.rejections = 0;
+                    }
+                    Err(rejection) => {
+                        surface.rejected = Some(rejection);
+                        filter.reject(latest);
+                    }
+                }
+            }
+
+            match surface.parity {
+                CPRFormat::Even => {
+                    latest.even_surface = Some((timestamp, *surface))
+                }
+                CPRFormat::Odd => {
+                    latest.odd_surface = Some((timestamp, *surface))
+                }
             }
         }
+        ME::BDS09(_) | ME::BDS65(_) => aircraft
+            .entry(*icao24)
+            .or_insert_with(|| AircraftState::new(timestamp))
+            .integrity
+            .update(message),
         _ => (),
     }
 }
 
+/**
+ * Mutates an airborne position (BDS 0,5) based on recent past positions
+ * (parameter `timestamp`) of the same aircraft (parameter `icao24`).
+ *
+ * Airborne positions are found in ADS-B messages, but also in Comm-B replies
+ * (DF=20): all of them are paired together for the same aircraft.
+ */
+pub fn decode_airborne_position(
+    airborne: &mut AirbornePosition,
+    timestamp: f64,
+    icao24: &ICAO,
+    aircraft: &mut BTreeMap<ICAO, AircraftState>,
+    reference: &mut Option<Position>,
+    update_reference: &UpdateIf,
+    filter: &PositionFilter,
+) {
+    let latest = aircraft
+        .entry(*icao24)
+        .or_insert_with(|| AircraftState::new(timestamp));
+    let mut pos: Option<Position> = None;
+
+    let latest_timestamp = match airborne.parity {
+        CPRFormat::Even => latest.odd_ts,
+        CPRFormat::Odd => latest.even_ts,
+    };
+    let latest_msg = match airborne.parity {
+        CPRFormat::Even => latest.odd_msg,
+        CPRFormat::Odd => latest.even_msg,
+    };
+
+    // This may happen with several sources of data coming on one mpsc
+    if (timestamp - latest_timestamp) < 0. {
+        return;
+    }
+
+    airborne.integrity = Some(latest.integrity.airborne(airborne));
+
+    let mut global = None;
+    if (timestamp - latest_timestamp) < 10. {
+        // First decoding based on odd/even (global)
+        // This is the most reasonable way to decode
+        global = match latest_msg {
+            Some(oldest) => airborne_position(&oldest, airborne),
+            None => None,
+        };
+    }
+
+    // Local decoding based on the previous position
+    // This is tricky though, use with extra care
+    let mut local = None;
+    if (timestamp - latest.timestamp) < 180. {
+        if let Some(latest_pos) = latest.pos {
+            local = airborne_position_with_reference(
+                airborne,
+                latest_pos.latitude,
+                latest_pos.longitude,
+            )
+        }
+    }
+
+    let mut rejection = None;
+    match (global, local) {
+        // If both decodings disagree, the track may have been poisoned by a
+        // wrong position: local decoding would keep diverging from it.
+        (Some(global), Some(local))
+            if dist_haversine(&global, &local) > DIVERGENCE_DISTANCE =>
+        {
+            rejection = Some(Rejection::Divergence)
+        }
+        (Some(new_pos), _) | (None, Some(new_pos)) => {
+            match filter.check(
+                &new_pos,
+                airborne.nuc_p,
+                timestamp,
+                latest,
+                reference,
+            ) {
+                Ok(()) => pos = Some(new_pos),
+                Err(reason) => rejection = Some(reason),
+            }
+        }
+        (None, None) => {}
+    }
+
+    if let Some(pos) = pos {
+        // First update the message
+        airborne.latitude = Some(pos.latitude);
+        airborne.longitude = Some(pos.longitude);
+        // Then update the reference in aircraft
+        latest.pos = Some(pos);
+        latest.timestamp = timestamp;
+        latest.rejections = 0;
+        // If necessary (according to the callback) update the reference position
+        if let Some(update_reference) = update_reference {
+            if update_reference(airborne) {
+                *reference = Some(Position {
+                    latitude: pos.latitude,
+                    longitude: pos.longitude,
+                })
+            }
+        }
+    } else if let Some(rejection) = rejection {
+        airborne.rejected = Some(rejection);
+        filter.reject(latest);
+    } else {
+        latest.pos = None;
+    }
+
+    match airborne.parity {
+        CPRFormat::Even => {
+            latest.even_msg = Some(*airborne);
+            latest.even_ts = timestamp
+        }
+        CPRFormat::Odd => {
+            latest.odd_msg = Some(*airborne);
+            latest.odd_ts = timestamp
+        }
+    }
+}
+
 /**
  * This function is only used  for the decoding of offline messages.
  */
@@ -553,6 +948,7 @@ pub fn decode_positions(
     res: &mut [TimedMessage],
     reference: Option<Position>,
     update_reference: &UpdateIf,
+    filter: &PositionFilter,
 ) {
     let mut aircraft: BTreeMap<ICAO, AircraftState> = BTreeMap::new();
     let mut reference = reference;
@@ -569,6 +965,7 @@ pub fn decode_positions(
                         &mut aircraft,
                         &mut reference,
                         update_reference,
+                        filter,
                     ),
                     DF::ExtendedSquitterTisB { cf, .. } => decode_position(
                         &mut cf.me,
@@ -577,7 +974,46 @@ pub fn decode_positions(
                         &mut aircraft,
                         &mut reference,
                         update_reference,
+                        filter,
+                    ),
+                    DF::ExtendedSquitterMilitary {
+                        payload: MilitaryApplication::ExtendedSquitter(es),
+                        ..
+                    } => decode_position(
+                        &mut es.me,
+                        msg.timestamp,
+                        &es.aa,
+                        &mut aircraft,
+                        &mut reference,
+                        update_reference,
+                        filter,
                     ),
+                    DF::CommBAltitudeReply { bds, ap, .. } => {
+                        if let Some(bds05) = &mut bds.bds05 {
+                            decode_airborne_position(
+                                bds05,
+                                msg.timestamp,
+                                &(*ap).into(),
+                                &mut aircraft,
+                                &mut reference,
+                                update_reference,
+                                filter,
+                            )
+                        }
+                    }
+                    DF::CommBIdentityReply { bds, ap, .. } => {
+                        if let Some(bds05) = &mut bds.bds05 {
+                            decode_airborne_position(
+                                bds05,
+                                msg.timestamp,
+                                &(*ap).into(),
+                                &mut aircraft,
+                                &mut reference,
+                                update_reference,
+                                filter,
+                            )
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -588,6 +1024,7 @@ pub fn decode_positions(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::decode::{AC13Field, IcaoParity};
     use crate::prelude::*;
     use approx::assert_relative_eq;
     use hexlit::hex;
@@ -642,6 +1079,87 @@ mod tests {
         assert_relative_eq!(longitude, 0.4347, max_relative = 1e-3);
     }
 
+    #[test]
+    fn decode_commb_position() {
+        let b1 = hex!("8D40058B58C901375147EFD09357");
+        let b2 = hex!("8D40058B58C904A87F402D3B8C59");
+        let (_, msg1) = Message::from_bytes((&b1, 0)).unwrap();
+        let (_, msg2) = Message::from_bytes((&b2, 0)).unwrap();
+        let ExtendedSquitterADSB(adsb) = &msg2.df else {
+            unreachable!()
+        };
+        let ME::BDS05(bds05) = adsb.message else {
+            unreachable!()
+        };
+
+        // The same airborne position, in a Comm-B reply
+        let bytes = hex!("a0001910cc300030aa0000eae004");
+        let (_, mut commb) = Message::from_bytes((&bytes, 0)).unwrap();
+        if let CommBAltitudeReply { ac, bds, ap, .. } = &mut commb.df {
+            *ac = AC13Field(bds05.alt.unwrap());
+            *ap = IcaoParity(adsb.icao24.0);
+            bds.bds05 = Some(bds05);
+        }
+        let bytes = commb.to_bytes().unwrap();
+        let (_, commb) = Message::from_bytes((&bytes, 0)).unwrap();
+
+        // The same airborne position, in an identity reply (BDS 0,5 is not
+        // inferred from DF21 payloads, it can only be set by hand)
+        let ident = hex!("a8001ebcfffb23286004a73f6a5b");
+        let (_, mut identity) = Message::from_bytes((&ident, 0)).unwrap();
+        if let CommBIdentityReply { bds, ap, .. } = &mut identity.df {
+            *ap = IcaoParity(adsb.icao24.0);
+            bds.bds05 = Some(bds05);
+        }
+
+        let mut res: Vec<TimedMessage> = [
+            (0., b1.to_vec(), msg1),
+            (1., bytes, commb),
+            (2., ident.to_vec(), identity),
+        ]
+        .into_iter()
+        .map(|(timestamp, frame, message)| TimedMessage {
+            timestamp,
+            frame,
+            message: Some(message),
+            modeac: None,
+            metadata: vec![],
+            decode_time: None,
+            corrected_bits: None,
+        })
+        .collect();
+        decode_positions(&mut res, None, &None, &PositionFilter::default());
+
+        let Some(CommBAltitudeReply { bds, .. }) =
+            res[1].message.as_ref().map(|msg| &msg.df)
+        else {
+            unreachable!()
+        };
+        let bds05 = bds.bds05.unwrap();
+        assert_relative_eq!(
+            bds05.latitude.unwrap(),
+            49.81755,
+            max_relative = 1e-3
+        );
+        assert_relative_eq!(
+            bds05.longitude.unwrap(),
+            6.08442,
+            max_relative = 1e-3
+        );
+
+        let Some(CommBIdentityReply { bds, .. }) =
+            res[2].message.as_ref().map(|msg| &msg.df)
+        else {
+            unreachable!()
+        };
+        let bds05 = bds.bds05.unwrap();
+        assert_relative_eq!(
+            bds05.latitude.unwrap(),
+            49.81755,
+            max_relative = 1e-3
+        );
+    }
+
     #[test]
     fn decode_airporne_position_with_reference() {
         let bytes = hex!("8D40058B58C901375147EFD09357");
@@ -704,4 +1222,221 @@ mod tests {
         assert_relative_eq!(latitude, 52.32061, max_relative = 1e-3);
         assert_relative_eq!(longitude, 4.73473, max_relative = 1e-3);
     }
+
+    #[test]
+    fn encode_positions() {
+        let bytes = hex!("8D40058B58C901375147EFD09357");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let msg = match msg.df {
+            ExtendedSquitterADSB(msg) => match msg.message {
+                ME::BDS05(me) => me,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let pos = airborne_position_with_reference(&msg, 49.0, 6.0).unwrap();
+        assert_eq!(
+            encode_airborne_position(pos.latitude, pos.longitude, msg.parity),
+            (msg.lat_cpr, msg.lon_cpr)
+        );
+
+        // A pair of even and odd messages decodes back to the position
+        let (latitude, longitude) = (43.6044, 1.4442);
+        let mut even = msg;
+        (even.lat_cpr, even.lon_cpr) =
+            encode_airborne_position(latitude, longitude, CPRFormat::Even);
+        let mut odd = msg;
+        odd.parity = CPRFormat::Odd;
+        (odd.lat_cpr, odd.lon_cpr) =
+            encode_airborne_position(latitude, longitude, CPRFormat::Odd);
+        let pos = airborne_position(&even, &odd).unwrap();
+        assert_relative_eq!(pos.latitude, latitude, max_relative = 1e-4);
+        assert_relative_eq!(pos.longitude, longitude, max_relative = 1e-4);
+
+        let bytes = hex!("8c4841753aab238733c8cd4020b1");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let msg = match msg.df {
+            ExtendedSquitterADSB(msg) => match msg.message {
+                ME::BDS06(me) => me,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let pos = surface_position_with_reference(&msg, 51.99, 4.375).unwrap();
+        assert_eq!(
+            encode_surface_position(pos.latitude, pos.longitude, msg.parity),
+            (msg.lat_cpr, msg.lon_cpr)
+        );
+    }
+
+    fn surface_pair(latitude: f64, longitude: f64) -> [SurfacePosition; 2] {
+        let bytes = hex!("8c4841753aab238733c8cd4020b1");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let ExtendedSquitterADSB(ADSB {
+            message: ME::BDS06(msg),
+            ..
+        }) = msg.df
+        else {
+            unreachable!()
+        };
+        [CPRFormat::Even, CPRFormat::Odd].map(|parity| {
+            let (lat_cpr, lon_cpr) =
+                encode_surface_position(latitude, longitude, parity);
+            SurfacePosition {
+                parity,
+                lat_cpr,
+                lon_cpr,
+                ..msg
+            }
+        })
+    }
+
+    #[test]
+    fn decode_surface_position() {
+        // Toulouse-Blagnac and Sydney airports
+        for (latitude, longitude) in [(43.6293, 1.3638), (-33.9461, 151.1772)] {
+            let [even, odd] = surface_pair(latitude, longitude);
+
+            // A reference in the same quadrant, but more than 45 NM away
+            let pos =
+                surface_position(&even, &odd, latitude + 5., longitude - 10.)
+                    .unwrap();
+            assert_relative_eq!(pos.latitude, latitude, max_relative = 1e-4);
+            assert_relative_eq!(pos.longitude, longitude, max_relative = 1e-4);
+
+            let pos = surface_position_near_airport(&odd, &even).unwrap();
+            assert_relative_eq!(pos.latitude, latitude, max_relative = 1e-4);
+            assert_relative_eq!(pos.longitude, longitude, max_relative = 1e-4);
+        }
+
+        // In the middle of the Atlantic Ocean
+        let [even, odd] = surface_pair(40., -40.);
+        assert_eq!(surface_position_near_airport(&even, &odd), None);
+    }
+
+    #[test]
+    fn decode_surface_positions() {
+        let [even, odd] = surface_pair(43.6293, 1.3638);
+        let mut aircraft = BTreeMap::new();
+        let icao24 = ICAO(0x484175);
+        let mut reference = None;
+
+        let mut msg = ME::BDS06(even);
+        decode_position(
+            &mut msg,
+            0.,
+            &icao24,
+            &mut aircraft,
+            &mut reference,
+            &None,
+            &PositionFilter::default(),
+        );
+        let ME::BDS06(even) = msg else { unreachable!() };
+        assert_eq!(even.latitude, None);
+
+        let mut msg = ME::BDS06(odd);
+        decode_position(
+            &mut msg,
+            5.,
+            &icao24,
+            &mut aircraft,
+            &mut reference,
+            &None,
+            &PositionFilter::default(),
+        );
+        let ME::BDS06(odd) = msg else { unreachable!() };
+        assert_relative_eq!(
+            odd.latitude.unwrap(),
+            43.6293,
+            max_relative = 1e-4
+        );
+        assert_relative_eq!(
+            odd.longitude.unwrap(),
+            1.3638,
+            max_relative = 1e-4
+        );
+    }
+
+    fn airborne(
+        latitude: f64,
+        longitude: f64,
+        parity: CPRFormat,
+    ) -> AirbornePosition {
+        let bytes = hex!("8D40058B58C901375147EFD09357");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let ExtendedSquitterADSB(ADSB {
+            message: ME::BDS05(msg),
+            ..
+        }) = msg.df
+        else {
+            unreachable!()
+        };
+        let (lat_cpr, lon_cpr) =
+            encode_airborne_position(latitude, longitude, parity);
+        AirbornePosition {
+            parity,
+            lat_cpr,
+            lon_cpr,
+            ..msg
+        }
+    }
+
+    #[test]
+    fn filter_positions() {
+        let icao24 = ICAO(0x40058b);
+        let mut aircraft = BTreeMap::new();
+        let mut reference = Some(Position {
+            latitude: 43.6,
+            longitude: 1.4,
+        });
+        let filter = PositionFilter::default();
+        let mut decode = |lat: f64, lon: f64, parity, timestamp, filter| {
+            let mut msg = airborne(lat, lon, parity);
+            decode_airborne_position(
+                &mut msg,
+                timestamp,
+                &icao24,
+                &mut aircraft,
+                &mut reference,
+                &None,
+                filter,
+            );
+            msg
+        };
+
+        let msg = decode(43.6, 1.4, CPRFormat::Even, 0., &filter);
+        assert_eq!(msg.latitude, None);
+        let msg = decode(43.6, 1.4, CPRFormat::Odd, 1., &filter);
+        assert_relative_eq!(msg.latitude.unwrap(), 43.6, max_relative = 1e-4);
+        assert_eq!(msg.rejected, None);
+
+        // About 110 km in 20 seconds, decoded locally: too fast
+        for timestamp in [20., 21., 22.] {
+            let msg = decode(44.6, 1.4, CPRFormat::Odd, timestamp, &filter);
+            assert_eq!(msg.latitude, None);
+            assert_eq!(msg.rejected, Some(Rejection::Speed));
+        }
+
+        // After three rejections, the track is decoded globally again
+        let msg = decode(44.6, 1.4, CPRFormat::Even, 23., &filter);
+        assert_relative_eq!(msg.latitude.unwrap(), 44.6, max_relative = 1e-4);
+        assert_eq!(msg.rejected, None);
+
+        // The message is not accurate enough
+        let strict = PositionFilter {
+            min_nuc_p: msg.nuc_p + 1,
+            ..PositionFilter::default()
+        };
+        let msg = decode(44.6, 1.4, CPRFormat::Odd, 24., &strict);
+        assert_eq!(msg.latitude, None);
+        assert_eq!(msg.rejected, Some(Rejection::Accuracy));
+
+        // The aircraft is too far away from the receiver
+        let close = PositionFilter {
+            max_range: Some(50.),
+            ..PositionFilter::default()
+        };
+        let msg = decode(44.6, 1.4, CPRFormat::Odd, 25., &close);
+        assert_eq!(msg.rejected, Some(Rejection::Range));
+    }
 }
@@ -1,4 +1,6 @@
 use deku::prelude::*;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 
 /// For performance concerns, the CRC_TABLE is statically computed
 #[rustfmt::skip]
@@ -76,6 +78,75 @@ pub fn modes_checksum(message: &[u8], bits: usize) -> Result<u32, DekuError> {
     Ok(rem)
 }
 
+/// The first five bits encode the downlink format: they are never corrected.
+const DF_BITS: usize = 5;
+
+/// Map a syndrome to the bit positions of the error pattern producing it
+type SyndromeTable = HashMap<u32, Vec<usize>>;
+
+/// Build the table of syndromes for all single and double bit errors
+///
+/// The checksum is linear: the checksum of a corrupted message is the
+/// checksum of the original message XORed with the checksum of the error
+/// pattern. A non-zero checksum on a message which should have a null one is
+/// therefore the syndrome of the error pattern.
+fn syndrome_table(bits: usize) -> SyndromeTable {
+    let single: Vec<u32> = (0..bits)
+        .map(|i| {
+            let mut message = vec![0u8; bits / 8];
+            message[i / 8] = 0x80 >> (i % 8);
+            // The length is always valid here
+            modes_checksum(&message, bits).unwrap()
+        })
+        .collect();
+
+    let mut table = SyndromeTable::new();
+    for (i, syndrome) in single.iter().enumerate().skip(DF_BITS) {
+        table.insert(*syndrome, vec![i]);
+    }
+    for i in DF_BITS..bits {
+        for j in (i + 1)..bits {
+            // Prefer the pattern with the fewest errors
+            table.entry(single[i] ^ single[j]).or_insert(vec![i, j]);
+        }
+    }
+    table
+}
+
+static SYNDROMES_SHORT: Lazy<SyndromeTable> = Lazy::new(|| syndrome_table(56));
+static SYNDROMES_LONG: Lazy<SyndromeTable> = Lazy::new(|| syndrome_table(112));
+
+/// Correct up to `max_bits` bit errors (at most 2) in a message
+///
+/// The `syndrome` is the checksum of the corrupted message, which should be 0
+/// for a valid message. The message is corrected in place and the function
+/// returns the number of corrected bits, or None if no error pattern with at
+/// most `max_bits` errors matches the syndrome. The downlink format bits are
+/// never corrected.
+pub fn fix_bit_errors(
+    message: &mut [u8],
+    bits: usize,
+    syndrome: u32,
+    max_bits: u8,
+) -> Option<u8> {
+    if syndrome == 0 {
+        return Some(0);
+    }
+    let table = match bits {
+        56 => &SYNDROMES_SHORT,
+        112 => &SYNDROMES_LONG,
+        _ => return None,
+    };
+    let pattern = table.get(&syndrome)?;
+    if pattern.len() > max_bits as usize || message.len() < bits / 8 {
+        return None;
+    }
+    for &i in pattern {
+        message[i / 8] ^= 0x80 >> (i % 8);
+    }
+    Some(pattern.len() as u8)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +195,33 @@ mod tests {
         let crc = modes_checksum(&bytes, 14 * 8).unwrap();
         assert_eq!(crc, 353333);
     }
+
+    #[test]
+    fn test_syndrome_table() {
+        // Single and double bit error syndromes are all distinct
+        let n = 112 - DF_BITS;
+        assert_eq!(SYNDROMES_LONG.len(), n + n * (n - 1) / 2);
+        let n = 56 - DF_BITS;
+        assert_eq!(SYNDROMES_SHORT.len(), n + n * (n - 1) / 2);
+    }
+
+    #[test]
+    fn test_fix_bit_errors() {
+        let reference = hex!("8D406B902015A678D4D220AA4BDA");
+
+        let mut bytes = reference;
+        bytes[5] ^= 0x10;
+        let crc = modes_checksum(&bytes, 112).unwrap();
+        assert_eq!(fix_bit_errors(&mut bytes, 112, crc, 0), None);
+        assert_eq!(fix_bit_errors(&mut bytes, 112, crc, 1), Some(1));
+        assert_eq!(bytes, reference);
+
+        let mut bytes = reference;
+        bytes[3] ^= 0x01;
+        bytes[12] ^= 0x80;
+        let crc = modes_checksum(&bytes, 112).unwrap();
+        assert_eq!(fix_bit_errors(&mut bytes, 112, crc, 1), None);
+        assert_eq!(fix_bit_errors(&mut bytes, 112, crc, 2), Some(2));
+        assert_eq!(bytes, reference);
+    }
 }
@@ -0,0 +1,605 @@
+/**
+* Aircraft seldom report the wind and temperature they experience
+* (Meteorological Routine Air Report, BDS 4,4), but both can be derived from
+* the difference between their ground and air velocity vectors:
+*
+* - the ground velocity (ground speed and true track) comes from ADS-B
+*   airborne velocities (BDS 0,9) or track and turn reports (BDS 5,0);
+* - the air velocity (true airspeed and magnetic heading) comes from
+*   heading and speed reports (BDS 6,0), air-referenced state vectors
+*   (BDS 5,3) or ADS-B airspeed velocities (BDS 0,9). The magnetic heading is
+*   corrected with the magnetic declination at the position and altitude of
+*   the aircraft, according to the [World Magnetic Model](crate::data::wmm).
+*   Headings in ADS-B airspeed velocities already refer to true north when
+*   the Horizontal Reference Direction (HRD) bit is set accordingly in the
+*   aircraft operation status (BDS 6,5): they are not corrected.
+*
+* Once the position of the aircraft is known, the engine also fills the
+* `true_heading` field of the messages reporting a magnetic heading.
+*
+* The wind vector is the difference between the ground and the air velocity
+* vectors. The static air temperature is derived from the true airspeed and
+* the Mach number, as the speed of sound only depends on the temperature.
+*
+* The [`DerivedQuantities`] engine keeps track of the recent state of each
+* aircraft and updates the estimations with every message. Comm-B replies
+* valid as both BDS 5,0 and BDS 6,0 are ignored: they should first be
+* disambiguated with the [`CommBInference`](super::inference::CommBInference)
+* engine.
+*/
+use super::adsb::ME;
+use super::atmosphere::{cas2tas, mach2tas, A0, T0};
+use super::bds::bds05::AirbornePosition;
+use super::bds::bds09::{AirborneVelocitySubType, AirspeedType};
+use super::bds::bds44::MeteorologicalRoutineAirReport;
+use super::bds::bds50::TrackAndTurnReport;
+use super::bds::bds53::AirReferencedStateVector;
+use super::bds::bds60::HeadingAndSpeedReport;
+use super::bds::bds65::{ADSBVersionAirborne, AircraftOperationStatus};
+use super::{Message, MilitaryApplication, DF, ICAO};
+use crate::data::wmm;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Wind speeds above this value (in kts) are considered as wrong estimations
+const MAX_WIND_SPEED: f64 = 250.;
+/// Range of plausible static air temperatures (in °C)
+const TEMPERATURE_RANGE: std::ops::RangeInclusive<f64> = -80.0..=50.0;
+/// Aircraft not heard of for this duration (in seconds) are forgotten
+const EXPIRY: f64 = 600.;
+/// Minimum duration (in seconds) between two scans for expired aircraft
+const EXPIRY_PERIOD: f64 = 60.;
+
+/// Wind and temperature, estimated or reported by an aircraft
+#[derive(Debug, PartialEq, Serialize, Deserialize, Copy, Clone, Default)]
+pub struct Meteo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Estimated wind speed (in kts)
+    pub wind_speed: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Estimated direction the wind is blowing from (in degrees)
+    pub wind_direction: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Estimated static air temperature (in °C)
+    pub temperature: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Wind speed reported in BDS 4,4 (in kts)
+    pub mrar_wind_speed: Option<u16>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Wind direction reported in BDS 4,4 (in degrees)
+    pub mrar_wind_direction: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Static air temperature reported in BDS 4,4 (in °C)
+    pub mrar_temperature: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct AircraftContext {
+    /// Timestamp of the last message received from the aircraft
+    last_seen: f64,
+    /// Timestamp, ground speed (kts) and true track (degrees)
+    ground: Option<(f64, f64, f64)>,
+    /// Timestamp and true airspeed (kts)
+    tas: Option<(f64, f64)>,
+    /// Timestamp and indicated airspeed (kts)
+    ias: Option<(f64, f64)>,
+    /// Timestamp and Mach number
+    mach: Option<(f64, f64)>,
+    /// Timestamp and heading (degrees)
+    heading: Option<(f64, f64)>,
+    /// True if the last heading refers to true north rather than magnetic
+    heading_true_north: bool,
+    /// True if ADS-B headings refer to true north (HRD bit in BDS 6,5)
+    true_north: bool,
+    /// Timestamp and barometric altitude (ft)
+    altitude: Option<(f64, f64)>,
+    /// Latitude and longitude (degrees), only for the magnetic declination
+    position: Option<(f64, f64)>,
+    meteo: Meteo,
+}
+
+/// Estimate wind and temperature for each aircraft (see module documentation)
+#[derive(Debug)]
+pub struct DerivedQuantities {
+    /// The maximum time difference (in seconds) between combined values
+    pub timeout: f64,
+    aircraft: BTreeMap<ICAO, AircraftContext>,
+    /// Timestamp of the last scan for expired aircraft
+    last_expiry: f64,
+}
+
+impl DerivedQuantities {
+    pub fn new(timeout: f64) -> Self {
+        Self {
+            timeout,
+            aircraft: BTreeMap::new(),
+            last_expiry: 0.,
+        }
+    }
+
+    /**
+     * Process a message received at time `timestamp`.
+     *
+     * Returns the up-to-date wind and temperature of the aircraft sending
+     * an ADS-B message or a Comm-B reply, None for other messages. The true
+     * heading is set in the message if the position of the aircraft is known.
+     * Aircraft not heard of for ten minutes are regularly forgotten.
+     */
+    pub fn push(&mut self, timestamp: f64, msg: &mut Message) -> Option<Meteo> {
+        if timestamp - self.last_expiry >= EXPIRY_PERIOD {
+            self.expire(timestamp);
+        }
+        let icao24 = match &mut msg.df {
+            DF::ExtendedSquitterADSB(adsb) => {
+                let context = self.context(adsb.icao24);
+                context.update_adsb(timestamp, &adsb.message);
+                context.true_heading(timestamp, &mut adsb.message);
+                adsb.icao24
+            }
+            DF::ExtendedSquitterMilitary {
+                payload: MilitaryApplication::ExtendedSquitter(es),
+                ..
+            } => {
+                let context = self.context(es.aa);
+                context.update_adsb(timestamp, &es.me);
+                context.true_heading(timestamp, &mut es.me);
+                es.aa
+            }
+            DF::CommBAltitudeReply { ac, bds, ap, .. } => {
+                let context = self.context(ICAO(ap.0));
+                if ac.0 > 0 {
+                    context.altitude = Some((timestamp, ac.0 as f64));
+                }
+                context.update_commb(
+                    timestamp, &bds.bds05, &bds.bds44, &bds.bds50, &bds.bds53,
+                    &bds.bds60,
+                );
+                context.true_heading_commb(
+                    timestamp,
+                    &mut bds.bds53,
+                    &mut bds.bds60,
+                );
+                ICAO(ap.0)
+            }
+            DF::CommBIdentityReply { bds, ap, .. } => {
+                let context = self.context(ICAO(ap.0));
+                context.update_commb(
+                    timestamp, &bds.bds05, &bds.bds44, &bds.bds50, &bds.bds53,
+                    &bds.bds60,
+                );
+                context.true_heading_commb(
+                    timestamp,
+                    &mut bds.bds53,
+                    &mut bds.bds60,
+                );
+                ICAO(ap.0)
+            }
+            _ => return None,
+        };
+        let timeout = self.timeout;
+        let context = self.aircraft.get_mut(&icao24)?;
+        context.last_seen = timestamp;
+        context.estimate(timestamp, timeout);
+        Some(context.meteo)
+    }
+
+    /// Forget the aircraft not heard of for ten minutes
+    pub fn expire(&mut self, timestamp: f64) {
+        self.aircraft
+            .retain(|_, context| timestamp - context.last_seen <= EXPIRY);
+        self.last_expiry = timestamp;
+    }
+
+    /// The latest wind and temperature of an aircraft
+    pub fn get(&self, icao24: &ICAO) -> Option<&Meteo> {
+        self.aircraft.get(icao24).map(|context| &context.meteo)
+    }
+
+    fn context(&mut self, icao24: ICAO) -> &mut AircraftContext {
+        self.aircraft.entry(icao24).or_default()
+    }
+}
+
+impl AircraftContext {
+    fn update_adsb(&mut self, timestamp: f64, me: &ME) {
+        match me {
+            ME::BDS05(bds05) => {
+                if let Some(alt) = bds05.alt {
+                    self.altitude = Some((timestamp, alt as f64));
+                }
+                self.update_position(bds05);
+            }
+            ME::BDS09(bds09) => match &bds09.velocity {
+                AirborneVelocitySubType::GroundSpeedDecoding(spd) => {
+                    self.ground = Some((timestamp, spd.groundspeed, spd.track))
+                }
+                AirborneVelocitySubType::AirspeedSubsonic(spd) => {
+                    if let Some(heading) = spd.heading {
+                        self.heading = Some((timestamp, heading));
+                        self.heading_true_north = self.true_north;
+                    }
+                    if let Some(airspeed) = spd.airspeed {
+                        let value = Some((timestamp, airspeed as f64));
+                        match spd.airspeed_type {
+                            AirspeedType::IAS => self.ias = value,
+                            AirspeedType::TAS => self.tas = value,
+                        }
+                    }
+                }
+                _ => {}
+            },
+            ME::BDS65(AircraftOperationStatus::Airborne(status)) => {
+                // 0 for true north, 1 for magnetic north
+                match &status.version {
+                    ADSBVersionAirborne::DOC9871AppendixB(v1) => {
+                        self.true_north = v1.horizontal_reference_direction == 0
+                    }
+                    ADSBVersionAirborne::DOC9871AppendixC(v2) => {
+                        self.true_north = v2.horizontal_reference_direction == 0
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update_commb(
+        &mut self,
+        timestamp: f64,
+        bds05: &Option<AirbornePosition>,
+        bds44: &Option<MeteorologicalRoutineAirReport>,
+        bds50: &Option<TrackAndTurnReport>,
+        bds53: &Option<AirReferencedStateVector>,
+        bds60: &Option<HeadingAndSpeedReport>,
+    ) {
+        if let Some(bds05) = bds05 {
+            self.update_position(bds05);
+        }
+        if let Some(bds44) = bds44 {
+            self.meteo.mrar_wind_speed = bds44.wind_speed;
+            self.meteo.mrar_wind_direction = bds44.wind_direction;
+            self.meteo.mrar_temperature = Some(bds44.temperature);
+        }
+        // Ambiguous replies should be disambiguated first
+        match (bds50, bds60) {
+            (Some(bds50), None) => {
+                if let (Some(gs), Some(track)) =
+                    (bds50.groundspeed, bds50.track_angle)
+                {
+                    self.ground = Some((timestamp, gs as f64, track));
+                }
+                if let Some(tas) = bds50.true_airspeed {
+                    self.tas = Some((timestamp, tas as f64));
+                }
+            }
+            (None, Some(bds60)) => {
+                if let Some(heading) = bds60.magnetic_heading {
+                    self.heading = Some((timestamp, heading));
+                    self.heading_true_north = false;
+                }
+                if let Some(ias) = bds60.indicated_airspeed {
+                    self.ias = Some((timestamp, ias as f64));
+                }
+                if let Some(mach) = bds60.mach_number {
+                    self.mach = Some((timestamp, mach));
+                }
+            }
+            _ => {}
+        }
+        if let Some(bds53) = bds53 {
+            if let Some(heading) = bds53.magnetic_heading {
+                self.heading = Some((timestamp, heading));
+                self.heading_true_north = false;
+            }
+            if let Some(ias) = bds53.indicated_airspeed {
+                self.ias = Some((timestamp, ias as f64));
+            }
+            if let Some(mach) = bds53.mach_number {
+                self.mach = Some((timestamp, mach));
+            }
+            if let Some(tas) = bds53.true_airspeed {
+                self.tas = Some((timestamp, tas));
+            }
+        }
+    }
+
+    fn update_position(&mut self, bds05: &AirbornePosition) {
+        if let Some(position) = bds05.latitude.zip(bds05.longitude) {
+            self.position = Some(position);
+        }
+    }
+
+    /// The magnetic declination (in degrees) at the last known position
+    fn declination(&self, timestamp: f64) -> Option<f64> {
+        let (lat, lon) = self.position?;
+        let altitude = self.altitude.map_or(0., |(_, alt)| alt);
+        Some(wmm::declination(
+            lat,
+            lon,
+            altitude,
+            wmm::decimal_year(timestamp),
+        ))
+    }
+
+    fn true_heading(&self, timestamp: f64, me: &mut ME) {
+        // Headings already referenced to true north are not corrected
+        let declination = match self.true_north {
+            true => 0.,
+            false => match self.declination(timestamp) {
+                Some(declination) => declination,
+                None => return,
+            },
+        };
+        if let ME::BDS09(bds09) = me {
+            match &mut bds09.velocity {
+                AirborneVelocitySubType::AirspeedSubsonic(spd) => {
+                    spd.true_heading =
+                        spd.heading.map(|h| (h + declination).rem_euclid(360.));
+                }
+                AirborneVelocitySubType::AirspeedSupersonic(spd) => {
+                    spd.true_heading = spd
+                        .heading
+                        .map(|h| (h as f64 + declination).rem_euclid(360.));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn true_heading_commb(
+        &self,
+        timestamp: f64,
+        bds53: &mut Option<AirReferencedStateVector>,
+        bds60: &mut Option<HeadingAndSpeedReport>,
+    ) {
+        let Some(declination) = self.declination(timestamp) else {
+            return;
+        };
+        let true_heading = |h: f64| (h + declination).rem_euclid(360.);
+        if let Some(bds53) = bds53 {
+            bds53.true_heading = bds53.magnetic_heading.map(true_heading);
+        }
+        if let Some(bds60) = bds60 {
+            bds60.true_heading = bds60.magnetic_heading.map(true_heading);
+        }
+    }
+
+    fn estimate(&mut self, timestamp: f64, timeout: f64) {
+        let fresh = |value: Option<(f64, f64)>| {
+            value
+                .filter(|(ts, _)| timestamp - ts <= timeout)
+                .map(|(_, value)| value)
+        };
+        let altitude = fresh(self.altitude);
+        let mach = fresh(self.mach);
+        let measured_tas = fresh(self.tas);
+
+        let sat = measured_tas.zip(mach).map(|(tas, m)| temperature(tas, m));
+        if let Some(sat) = sat.filter(|t| TEMPERATURE_RANGE.contains(t)) {
+            self.meteo.temperature = Some(sat);
+        }
+
+        // Without any measured true airspeed, assume the standard atmosphere
+        let tas = measured_tas
+            .or_else(|| mach.zip(altitude).map(|(m, alt)| mach2tas(m, alt)))
+            .or_else(|| {
+                fresh(self.ias)
+                    .zip(altitude)
+                    .map(|(ias, alt)| cas2tas(ias, alt))
+            });
+        let ground = self
+            .ground
+            .filter(|(ts, _, _)| timestamp - ts <= timeout)
+            .map(|(_, gs, track)| (gs, track));
+
+        let true_heading = fresh(self.heading).and_then(|heading| {
+            match self.heading_true_north {
+                true => Some(heading),
+                false => self
+                    .declination(timestamp)
+                    .map(|declination| heading + declination),
+            }
+        });
+
+        if let (Some(tas), Some(true_heading), Some((gs, track))) =
+            (tas, true_heading, ground)
+        {
+            let (speed, direction) = wind(tas, true_heading, gs, track);
+            if speed <= MAX_WIND_SPEED {
+                self.meteo.wind_speed = Some(speed);
+                self.meteo.wind_direction = Some(direction);
+            }
+        }
+    }
+}
+
+/**
+ * The wind speed (in kts) and the direction it blows from (in degrees),
+ * from the true airspeed (in kts), the true heading, the ground speed (in
+ * kts) and the true track angle (in degrees).
+ */
+pub fn wind(
+    tas: f64,
+    true_heading: f64,
+    groundspeed: f64,
+    track: f64,
+) -> (f64, f64) {
+    let (air_x, air_y) = (
+        tas * true_heading.to_radians().sin(),
+        tas * true_heading.to_radians().cos(),
+    );
+    let (ground_x, ground_y) = (
+        groundspeed * track.to_radians().sin(),
+        groundspeed * track.to_radians().cos(),
+    );
+    let (wind_x, wind_y) = (ground_x - air_x, ground_y - air_y);
+    let direction = (-wind_x).atan2(-wind_y).to_degrees().rem_euclid(360.);
+    (wind_x.hypot(wind_y), direction)
+}
+
+/// The static air temperature (in °C) from the true airspeed (in kts) and
+/// the Mach number
+pub fn temperature(tas: f64, mach: f64) -> f64 {
+    T0 * (tas / (mach * A0)).powi(2) - 273.15
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use deku::DekuContainerRead;
+    use hexlit::hex;
+
+    #[test]
+    fn test_wind() {
+        // Heading North, with a western wind
+        let track = 50_f64.atan2(450.).to_degrees();
+        let (speed, direction) = wind(450., 0., 450_f64.hypot(50.), track);
+        assert_relative_eq!(speed, 50., max_relative = 1e-6);
+        assert_relative_eq!(direction, 270., max_relative = 1e-6);
+
+        // Heading North, with a tail wind
+        let (speed, direction) = wind(450., 0., 480., 0.);
+        assert_relative_eq!(speed, 30.);
+        assert_relative_eq!(direction, 180.);
+    }
+
+    #[test]
+    fn test_temperature() {
+        // Above the tropopause in the standard atmosphere
+        let tas = mach2tas(0.8, 40000.);
+        assert_relative_eq!(temperature(tas, 0.8), -56.5, epsilon = 0.1);
+        assert_relative_eq!(temperature(250., 250. / A0), 15., epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let (lat, lon) = (43.6, 1.4);
+        let t0 = 1704067200.; // 2024-01-01
+        let year = wmm::decimal_year(t0 + 2.);
+        let declination = wmm::declination(lat, lon, 36000., year);
+        // True heading 90°, with a 20 kts wind from the South
+        let ground_x = 450.;
+        let ground_y = 20.;
+        let mut context = AircraftContext {
+            ground: Some((
+                t0,
+                f64::hypot(ground_x, ground_y),
+                ground_x.atan2(ground_y).to_degrees(),
+            )),
+            tas: Some((t0 + 1., 450.)),
+            mach: Some((t0 + 1., 0.78)),
+            heading: Some((t0 + 1., 90. - declination)),
+            altitude: Some((t0, 36000.)),
+            position: Some((lat, lon)),
+            ..Default::default()
+        };
+        context.estimate(t0 + 2., 10.);
+        assert_relative_eq!(context.meteo.wind_speed.unwrap(), 20.);
+        assert_relative_eq!(context.meteo.wind_direction.unwrap(), 180.);
+        let temperature = context.meteo.temperature.unwrap();
+        assert!((-60. ..-50.).contains(&temperature));
+
+        // Outdated ground velocity
+        let mut context = AircraftContext {
+            ground: Some((t0, 450., 90.)),
+            ..context
+        };
+        context.meteo = Meteo::default();
+        context.estimate(t0 + 20., 10.);
+        assert_eq!(context.meteo.wind_speed, None);
+    }
+
+    #[test]
+    fn test_true_heading() {
+        let bytes = hex!("a80004aaa74a072bfdefc1d5cb4f");
+        let (_, mut msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let DF::CommBIdentityReply { ap, .. } = msg.df else {
+            unreachable!()
+        };
+        let timestamp = 1704067200.;
+        let mut derived = DerivedQuantities::new(10.);
+
+        // Unknown position
+        derived.push(timestamp, &mut msg);
+        let DF::CommBIdentityReply { bds, .. } = &msg.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds60.as_ref().unwrap().true_heading, None);
+
+        derived.context(ICAO(ap.0)).position = Some((40.7, -74.0));
+        derived.push(timestamp, &mut msg);
+        let DF::CommBIdentityReply { bds, .. } = &msg.df else {
+            unreachable!()
+        };
+        let bds60 = bds.bds60.as_ref().unwrap();
+        let declination = wmm::declination(40.7, -74.0, 0., 2024.);
+        assert_relative_eq!(
+            bds60.true_heading.unwrap(),
+            bds60.magnetic_heading.unwrap() + declination
+        );
+    }
+
+    #[test]
+    fn test_true_north() {
+        let bytes = hex!("8DA05F219B06B6AF189400CBC33F");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let timestamp = 1704067200.;
+        let heading = |msg: &Message| {
+            let DF::ExtendedSquitterADSB(adsb) = &msg.df else {
+                unreachable!()
+            };
+            let ME::BDS09(bds09) = &adsb.message else {
+                unreachable!()
+            };
+            let AirborneVelocitySubType::AirspeedSubsonic(spd) =
+                &bds09.velocity
+            else {
+                unreachable!()
+            };
+            (spd.heading.unwrap(), spd.true_heading)
+        };
+
+        // Magnetic heading, without any known position
+        let mut derived = DerivedQuantities::new(10.);
+        let mut magnetic = msg.clone();
+        derived.push(timestamp, &mut magnetic);
+        assert_eq!(heading(&magnetic).1, None);
+
+        // Heading referenced to true north (HRD bit in BDS 6,5)
+        derived.context(ICAO(0xa05f21)).true_north = true;
+        let mut true_north = msg.clone();
+        derived.push(timestamp, &mut true_north);
+        let (heading, true_heading) = heading(&true_north);
+        assert_relative_eq!(true_heading.unwrap(), heading);
+    }
+
+    #[test]
+    fn test_expire() {
+        let bytes = hex!("a80004aaa74a072bfdefc1d5cb4f");
+        let (_, mut msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let DF::CommBIdentityReply { ap, .. } = msg.df else {
+            unreachable!()
+        };
+        let timestamp = 1704067200.;
+        let mut derived = DerivedQuantities::new(10.);
+
+        derived.push(timestamp, &mut msg);
+        assert!(derived.get(&ICAO(ap.0)).is_some());
+        derived.push(timestamp + 300., &mut msg);
+        assert!(derived.get(&ICAO(ap.0)).is_some());
+
+        // Another aircraft, long after the last message
+        let bytes = hex!("8D40058B58C901375147EFD09357");
+        let (_, mut other) = Message::from_bytes((&bytes, 0)).unwrap();
+        derived.push(timestamp + 1000., &mut other);
+        assert!(derived.get(&ICAO(ap.0)).is_none());
+        assert!(derived.get(&ICAO(0x40058b)).is_some());
+    }
+}
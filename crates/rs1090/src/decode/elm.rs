@@ -0,0 +1,225 @@
+/**
+* Downlink Extended Length Messages (ELM) are transmitted as a sequence of up
+* to 16 Comm-D segments (DF=24), each of them carrying 80 bits of payload.
+*
+* The transponder announces a downlink ELM in the downlink request (DR) field
+* of surveillance and Comm-B replies (DF=4, 5, 20 or 21): a DR value between 16
+* and 31 means that `DR - 15` segments are waiting to be transmitted. Segments
+* are then numbered (field ND) from 0 to the number of segments minus one.
+*
+* The [`ElmReassembler`] collects segments per aircraft address and segment
+* number, and emits the full payload once all segments have been received.
+* When the number of segments has not been announced, or when some segments
+* are missing, the payload received so far is emitted after a timeout.
+*/
+use super::{as_hex, DownlinkRequest, Message, DF, ICAO, KE};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The payload of a downlink ELM, concatenated from its Comm-D segments
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub struct ExtendedLengthMessage {
+    /// The timestamp of the last segment received
+    pub timestamp: f64,
+    /// The address of the aircraft
+    pub icao24: ICAO,
+    /// The number of segments received
+    pub segments: u8,
+    /// True if all the announced segments have been received
+    pub complete: bool,
+    /// The payload, 10 bytes per segment, ordered by segment number
+    #[serde(serialize_with = "as_hex")]
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct PendingElm {
+    first_seen: f64,
+    last_seen: f64,
+    expected: Option<u8>,
+    segments: BTreeMap<u8, Vec<u8>>,
+}
+
+impl PendingElm {
+    fn new(timestamp: f64) -> Self {
+        Self {
+            first_seen: timestamp,
+            last_seen: timestamp,
+            expected: None,
+            segments: BTreeMap::new(),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        match self.expected {
+            Some(n) => (0..n).all(|nd| self.segments.contains_key(&nd)),
+            None => false,
+        }
+    }
+
+    fn into_message(self, icao24: ICAO) -> ExtendedLengthMessage {
+        let complete = self.is_complete();
+        ExtendedLengthMessage {
+            timestamp: self.last_seen,
+            icao24,
+            segments: self.segments.len() as u8,
+            complete,
+            payload: self.segments.into_values().flatten().collect(),
+        }
+    }
+}
+
+/// Reassemble downlink ELM from Comm-D segments (DF=24)
+#[derive(Debug)]
+pub struct ElmReassembler {
+    /// The maximum duration (in seconds) to wait for all the segments
+    pub timeout: f64,
+    pending: BTreeMap<ICAO, PendingElm>,
+}
+
+impl ElmReassembler {
+    pub fn new(timeout: f64) -> Self {
+        Self {
+            timeout,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /**
+     * Process a message received at time `timestamp`, and return the
+     * reassembled ELM if the message completes it.
+     *
+     * - DF=24 segments with a downlink ELM transmission control field are
+     *   stored by address and segment number;
+     * - DF=4, 5, 20 and 21 replies announcing a downlink ELM set the number
+     *   of segments expected for the address.
+     *
+     * Incomplete messages older than the timeout are discarded when a new
+     * segment arrives for the same address: call [`ElmReassembler::expire`]
+     * beforehand in order to retrieve them.
+     */
+    pub fn push(
+        &mut self,
+        timestamp: f64,
+        msg: &Message,
+    ) -> Option<ExtendedLengthMessage> {
+        let icao24 = match &msg.df {
+            DF::CommDExtended {
+                ke: KE::DownlinkELMTx,
+                nd,
+                md,
+                ap,
+                ..
+            } => {
+                let icao24 = (*ap).into();
+                let pending = self.entry(icao24, timestamp);
+                pending.last_seen = timestamp;
+                pending.segments.insert(*nd, md.clone());
+                icao24
+            }
+            DF::SurveillanceAltitudeReply { dr, ap, .. }
+            | DF::SurveillanceIdentityReply { dr, ap, .. }
+            | DF::CommBAltitudeReply { dr, ap, .. }
+            | DF::CommBIdentityReply { dr, ap, .. } => {
+                let DownlinkRequest::DownlinkELM(n) = dr else {
+                    return None;
+                };
+                let icao24 = (*ap).into();
+                let pending = self.entry(icao24, timestamp);
+                pending.expected = Some(n - 15);
+                icao24
+            }
+            _ => return None,
+        };
+        if self.pending.get(&icao24)?.is_complete() {
+            let pending = self.pending.remove(&icao24)?;
+            return Some(pending.into_message(icao24));
+        }
+        None
+    }
+
+    /// Return (and forget) all messages with segments older than the timeout
+    pub fn expire(&mut self, timestamp: f64) -> Vec<ExtendedLengthMessage> {
+        let expired: Vec<ICAO> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| timestamp - p.first_seen > self.timeout)
+            .map(|(icao24, _)| *icao24)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|icao24| {
+                let pending = self.pending.remove(&icao24)?;
+                if pending.segments.is_empty() {
+                    return None;
+                }
+                Some(pending.into_message(icao24))
+            })
+            .collect()
+    }
+
+    fn entry(&mut self, icao24: ICAO, timestamp: f64) -> &mut PendingElm {
+        let timeout = self.timeout;
+        let pending = self
+            .pending
+            .entry(icao24)
+            .or_insert_with(|| PendingElm::new(timestamp));
+        if timestamp - pending.first_seen > timeout {
+            *pending = PendingElm::new(timestamp);
+        }
+        pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::IcaoParity;
+    use deku::prelude::*;
+    use hexlit::hex;
+
+    fn segment(icao24: u32, nd: u8) -> Message {
+        Message {
+            crc: icao24,
+            df: DF::CommDExtended {
+                format: 0b11,
+                spare: 0,
+                ke: KE::DownlinkELMTx,
+                nd,
+                md: vec![nd; 10],
+                ap: IcaoParity(icao24),
+            },
+        }
+    }
+
+    #[test]
+    fn test_reassemble() {
+        let mut elm = ElmReassembler::new(10.);
+        // DF4 reply announcing a downlink ELM of two segments
+        let bytes = hex!("208818384e9ea1");
+        let (_, announce) = Message::from_bytes((&bytes, 0)).unwrap();
+        let address = match &announce.df {
+            DF::SurveillanceAltitudeReply {
+                dr: DownlinkRequest::DownlinkELM(17),
+                ap,
+                ..
+            } => ap.0,
+            _ => unreachable!(),
+        };
+        assert_eq!(elm.push(0., &announce), None);
+        // Segments may be received in any order
+        assert_eq!(elm.push(1., &segment(address, 1)), None);
+        assert_eq!(elm.push(1.1, &segment(0x123456, 0)), None);
+        let msg = elm.push(1.2, &segment(address, 0)).unwrap();
+        assert_eq!(msg.icao24, ICAO(address));
+        assert_eq!(msg.segments, 2);
+        assert!(msg.complete);
+        assert_eq!(msg.payload, [[0; 10], [1; 10]].concat());
+
+        assert!(elm.expire(5.).is_empty());
+        let expired = elm.expire(12.);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].icao24, ICAO(0x123456));
+        assert!(!expired[0].complete);
+    }
+}
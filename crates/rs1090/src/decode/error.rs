@@ -0,0 +1,438 @@
+/**
+* The decoders are generated with deku and can only fail with a
+* [`DekuError`], where the reason of the failure is described by a string.
+* Readers which reject a value rather raise an [`Error`], with the offending
+* values attached, so that applications can count or filter rejected
+* messages without matching strings.
+*
+* As deku still expects a [`DekuError`], which cannot carry anything else
+* than a string, [`Error::raise`] returns a [`DekuError::Assertion`] with the
+* description of the error, followed by a dedicated `[rs1090:...]` suffix
+* encoding all its values. The conversion back into an [`Error`] (with
+* [`Error::from`]) parses this suffix, so the typed error survives any copy
+* of the string. Other failures of the decoder are wrapped as is into
+* [`Error::Deku`].
+*/
+use deku::DekuError;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Mutex;
+
+/// The start of the suffix encoding a raised error
+const MARKER: &str = " [rs1090:";
+
+/// The reason why a frame could not be decoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The length of the frame (in bytes) does not match its downlink format
+    Length {
+        df: u8,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The checksum of an extended squitter is not null (only checked in DF17
+    /// messages and in DF19 messages with AF=0, the other DF19 applications
+    /// having no parity)
+    Crc { df: u8, crc: u32 },
+
+    /// The downlink format is not defined
+    UnknownFormat { df: u8 },
+
+    /// The typecode of an ADS-B message, or its subtype, is not defined (or
+    /// not expected)
+    UnknownTypecode { tc: u8, subtype: Option<u8> },
+
+    /// A field (or a group of reserved bits) has a reserved value
+    Reserved { field: &'static str, value: u64 },
+
+    /// A field has a non-null value while its status bit is not set
+    Status { field: &'static str, value: u64 },
+
+    /// A field has a value out of its plausible range
+    Range { field: &'static str, value: f64 },
+
+    /// A text field has invalid characters
+    Text { field: &'static str, value: String },
+
+    /// The values of two fields are not consistent with each other
+    Inconsistent {
+        fields: (&'static str, &'static str),
+        values: (f64, f64),
+    },
+
+    /// The payload is not a valid Comm-B register (or ADS-B message) of the
+    /// expected type
+    Hypothesis {
+        bds: &'static str,
+        reason: Box<Error>,
+    },
+
+    /// Any other error raised by the decoder
+    Deku(DekuError),
+}
+
+impl Error {
+    /// Return this error from a deku reader (see module documentation)
+    pub fn raise(self) -> DekuError {
+        match self {
+            Error::Deku(error) => error,
+            error => DekuError::Assertion(
+                format!("{error}{MARKER}{}]", error.encode()).into(),
+            ),
+        }
+    }
+
+    /// The values of the error as `:` separated tokens, starting with its
+    /// reason: floats are encoded with their bits and texts in hexadecimal,
+    /// so that the tokens never contain any separator.
+    fn encode(&self) -> String {
+        let reason = self.reason();
+        match self {
+            Error::Length {
+                df,
+                expected,
+                actual,
+            } => format!("{reason}:{df}:{expected}:{actual}"),
+            Error::Crc { df, crc } => format!("{reason}:{df}:{crc}"),
+            Error::UnknownFormat { df } => format!("{reason}:{df}"),
+            Error::UnknownTypecode { tc, subtype } => {
+                let subtype = subtype.map_or(String::new(), |s| s.to_string());
+                format!("{reason}:{tc}:{subtype}")
+            }
+            Error::Reserved { field, value }
+            | Error::Status { field, value } => {
+                format!("{reason}:{field}:{value}")
+            }
+            Error::Range { field, value } => {
+                format!("{reason}:{field}:{:x}", value.to_bits())
+            }
+            Error::Text { field, value } => {
+                format!("{reason}:{field}:{}", hex::encode(value))
+            }
+            Error::Inconsistent {
+                fields: (a, b),
+                values: (x, y),
+            } => {
+                format!("{reason}:{a}:{b}:{:x}:{:x}", x.to_bits(), y.to_bits())
+            }
+            Error::Hypothesis { bds, reason: error } => {
+                format!("{reason}:{bds}:{}", error.encode())
+            }
+            Error::Deku(_) => reason.to_string(),
+        }
+    }
+
+    /// Parse the tokens produced by [`Error::encode`]
+    fn decode(encoded: &str) -> Option<Self> {
+        let (reason, rest) = encoded.split_once(':')?;
+        if reason == "hypothesis" {
+            let (bds, error) = rest.split_once(':')?;
+            return Some(Error::Hypothesis {
+                bds: intern(bds),
+                reason: Box::new(Self::decode(error)?),
+            });
+        }
+        let float =
+            |bits: &str| u64::from_str_radix(bits, 16).map(f64::from_bits);
+        let tokens: Vec<&str> = rest.split(':').collect();
+        let error = match (reason, tokens.as_slice()) {
+            ("length", [df, expected, actual]) => Error::Length {
+                df: df.parse().ok()?,
+                expected: expected.parse().ok()?,
+                actual: actual.parse().ok()?,
+            },
+            ("crc", [df, crc]) => Error::Crc {
+                df: df.parse().ok()?,
+                crc: crc.parse().ok()?,
+            },
+            ("unknown_format", [df]) => Error::UnknownFormat {
+                df: df.parse().ok()?,
+            },
+            ("unknown_typecode", [tc, subtype]) => Error::UnknownTypecode {
+                tc: tc.parse().ok()?,
+                subtype: match *subtype {
+                    "" => None,
+                    subtype => Some(subtype.parse().ok()?),
+                },
+            },
+            ("reserved", [field, value]) => Error::Reserved {
+                field: intern(field),
+                value: value.parse().ok()?,
+            },
+            ("status", [field, value]) => Error::Status {
+                field: intern(field),
+                value: value.parse().ok()?,
+            },
+            ("range", [field, value]) => Error::Range {
+                field: intern(field),
+                value: float(value).ok()?,
+            },
+            ("text", [field, value]) => Error::Text {
+                field: intern(field),
+                value: String::from_utf8(hex::decode(value).ok()?).ok()?,
+            },
+            ("inconsistent", [a, b, x, y]) => Error::Inconsistent {
+                fields: (intern(a), intern(b)),
+                values: (float(x).ok()?, float(y).ok()?),
+            },
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// The payload is not valid for the register `bds`
+    pub fn hypothesis(bds: &'static str, error: DekuError) -> Self {
+        Error::Hypothesis {
+            bds,
+            reason: Box::new(error.into()),
+        }
+    }
+
+    /// Check that the length of `frame` matches its downlink format
+    pub fn check_length(frame: &[u8]) -> Result<(), Self> {
+        let df = frame.first().map_or(0, |first| first >> 3);
+        let expected = if df & 0x10 != 0 { 14 } else { 7 };
+        match frame.len() {
+            actual if actual != expected => Err(Error::Length {
+                df,
+                expected,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// A short label for the reason of the error, e.g. to count rejections
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Error::Length { .. } => "length",
+            Error::Crc { .. } => "crc",
+            Error::UnknownFormat { .. } => "unknown_format",
+            Error::UnknownTypecode { .. } => "unknown_typecode",
+            Error::Reserved { .. } => "reserved",
+            Error::Status { .. } => "status",
+            Error::Range { .. } => "range",
+            Error::Text { .. } => "text",
+            Error::Inconsistent { .. } => "inconsistent",
+            Error::Hypothesis { .. } => "hypothesis",
+            Error::Deku(_) => "other",
+        }
+    }
+}
+
+/// The field names (and registers) of the parsed errors, as static strings.
+/// They come from a limited set of names in the decoders, so each of them is
+/// only allocated once.
+fn intern(name: &str) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    match names.get(name) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.into());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+/// Recover the error raised by a reader, if it is the origin of `error`
+impl From<DekuError> for Error {
+    fn from(error: DekuError) -> Self {
+        let raised = match &error {
+            DekuError::Assertion(description) => description
+                .rfind(MARKER)
+                .and_then(|i| description[i + MARKER.len()..].strip_suffix(']'))
+                .and_then(Error::decode),
+            _ => None,
+        };
+        raised.unwrap_or(Error::Deku(error))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Length {
+                df,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Invalid length for DF={df}: {actual} bytes ({expected} expected)"
+            ),
+            Error::Crc { df, crc } => {
+                write!(f, "Invalid CRC in DF={df} message: {crc:06x}")
+            }
+            Error::UnknownFormat { df } => {
+                write!(f, "Unknown downlink format DF={df}")
+            }
+            Error::UnknownTypecode { tc, subtype: None } => {
+                write!(f, "Unknown typecode {tc}")
+            }
+            Error::UnknownTypecode {
+                tc,
+                subtype: Some(subtype),
+            } => write!(f, "Unknown subtype {subtype} for typecode {tc}"),
+            Error::Reserved { field, value } => {
+                write!(f, "Reserved value {value} in {field}")
+            }
+            Error::Status { field, value } => {
+                write!(f, "Non-null value {value} in {field} with invalid status")
+            }
+            Error::Range { field, value } => {
+                write!(f, "Value {value} of {field} out of range")
+            }
+            Error::Text { field, value } => {
+                write!(f, "Invalid characters in {field}: {value:?}")
+            }
+            Error::Inconsistent {
+                fields: (a, b),
+                values: (x, y),
+            } => write!(f, "Inconsistent {a} ({x}) and {b} ({y})"),
+            Error::Hypothesis { bds, reason } => {
+                write!(f, "Invalid BDS {bds}: {reason}")
+            }
+            Error::Deku(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::bds::bds60::HeadingAndSpeedReport;
+    use crate::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_errors() {
+        let bytes = hex!("8D4840D6202CC371C32CE0576098");
+        assert!(Message::decode(&bytes).is_ok());
+
+        // One flipped bit
+        let bytes = hex!("8D4840D6202CC371C32CE0576099");
+        let error = Message::decode(&bytes).unwrap_err();
+        assert!(matches!(error, Error::Crc { df: 17, .. }));
+        assert_eq!(error.reason(), "crc");
+
+        let error = Message::decode(&bytes[..7]).unwrap_err();
+        assert_eq!(
+            error,
+            Error::Length {
+                df: 17,
+                expected: 14,
+                actual: 7
+            }
+        );
+        let bytes = hex!("8D4840D6202CC371C32CE057609800");
+        let error = Message::decode(&bytes).unwrap_err();
+        assert!(matches!(error, Error::Length { actual: 15, .. }));
+
+        let bytes = hex!("08000000000000");
+        let error = Message::decode(&bytes).unwrap_err();
+        assert_eq!(error, Error::UnknownFormat { df: 1 });
+    }
+
+    #[test]
+    fn test_raised_errors() {
+        // Aircraft operation status (BDS 6,5), with reserved bits set in the
+        // capability class
+        let bytes = hex!("8d3c4b26f8c000000000001a2185");
+        let error = Message::decode(&bytes).unwrap_err();
+        assert_eq!(
+            error,
+            Error::Hypothesis {
+                bds: "65",
+                reason: Box::new(Error::Reserved {
+                    field: "CapabilityClassAirborne.reserved0",
+                    value: 3
+                })
+            }
+        );
+        assert_eq!(error.reason(), "hypothesis");
+
+        // IAS 1000 kts in BDS 6,0
+        let payload: u64 = 1 << 43 | 1000 << 33;
+        let error =
+            HeadingAndSpeedReport::try_from(&payload.to_be_bytes()[1..])
+                .unwrap_err();
+        assert_eq!(
+            Error::hypothesis("60", error),
+            Error::Hypothesis {
+                bds: "60",
+                reason: Box::new(Error::Range {
+                    field: "indicated_airspeed",
+                    value: 1000.
+                })
+            }
+        );
+
+        // Other errors are kept as is, even with the same description
+        let error = DekuError::Assertion("Invalid value".into());
+        assert_eq!(Error::from(error.clone()), Error::Deku(error));
+        let raised = Error::Range {
+            field: "indicated_airspeed",
+            value: 1000.,
+        };
+        let error = DekuError::Assertion(raised.to_string().into());
+        assert_eq!(Error::from(error.clone()), Error::Deku(error));
+    }
+
+    #[test]
+    fn test_raise_roundtrip() {
+        let errors = [
+            Error::Length {
+                df: 17,
+                expected: 14,
+                actual: 7,
+            },
+            Error::Crc { df: 17, crc: 1 },
+            Error::UnknownFormat { df: 1 },
+            Error::UnknownTypecode {
+                tc: 31,
+                subtype: Some(3),
+            },
+            Error::UnknownTypecode {
+                tc: 25,
+                subtype: None,
+            },
+            Error::Status {
+                field: "wind_speed",
+                value: 12,
+            },
+            Error::Range {
+                field: "temperature",
+                value: -0.1 + 0.2,
+            },
+            Error::Text {
+                field: "callsign",
+                value: "A:B] [rs1090:".to_string(),
+            },
+            Error::Inconsistent {
+                fields: ("latitude", "longitude"),
+                values: (0.5, -1e-300),
+            },
+            Error::Hypothesis {
+                bds: "65",
+                reason: Box::new(Error::Reserved {
+                    field: "CapabilityClassAirborne.reserved0",
+                    value: 3,
+                }),
+            },
+        ];
+        for error in errors {
+            // The description may be copied by the caller
+            let raised = match error.clone().raise() {
+                DekuError::Assertion(description) => {
+                    DekuError::Assertion(description.to_string().into())
+                }
+                _ => unreachable!(),
+            };
+            assert_eq!(Error::from(raised), error);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::decode::Error;
 use deku::prelude::*;
 use serde::Serialize;
 
@@ -283,7 +284,11 @@ fn magic_value(v: u8) -> Result<bool, DekuError> {
     if v == 0x20 {
         return Ok(false);
     };
-    Err(DekuError::Assertion("Magic must be 0x10 or 0x20".into()))
+    Err(Error::Reserved {
+        field: "magic",
+        value: v as u64,
+    }
+    .raise())
 }
 
 #[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
@@ -0,0 +1,452 @@
+/**
+* Comm-B replies (DF=20 and 21) do not identify the register (BDS) they carry,
+* so the [`DF20DataSelector`](super::commb::DF20DataSelector) and
+* [`DF21DataSelector`](super::commb::DF21DataSelector) keep every hypothesis
+* consistent with the content of the message. Track and turn reports
+* (BDS 5,0) and heading and speed reports (BDS 6,0) are often both valid
+* for the same payload.
+*
+* The [`CommBInference`] engine keeps track of the recent state of each
+* aircraft, as reported by ADS-B (ground speed, track, altitude, vertical
+* rate) and by its capability report (BDS 1,7), and scores the BDS 5,0 and
+* BDS 6,0 hypotheses against it:
+*
+* - the velocity vector of each hypothesis (ground speed and track for
+*   BDS 5,0, true airspeed and magnetic heading for BDS 6,0) is compared to
+*   the ADS-B velocity, with a larger tolerance for BDS 6,0 to account for the
+*   wind and the magnetic declination;
+* - the vertical rates of BDS 6,0 are compared to the ADS-B vertical rate;
+* - registers not supported according to BDS 1,7 are penalised.
+*
+* The confidence is the score of the best hypothesis, normalised by the
+* scores of all hypotheses and of the hypothesis that none is correct. It is
+* reported in the `bds_confidence` field of the Comm-B reply. Without any
+* reference (no recent velocity, vertical rate nor capability report), the
+* hypotheses cannot be told apart and the reply is left untouched.
+*/
+use super::adsb::ME;
+use super::atmosphere::{cas2tas, mach2tas};
+use super::bds::bds09::AirborneVelocitySubType;
+use super::bds::bds17::CommonUsageGICBCapabilityReport;
+use super::bds::bds50::TrackAndTurnReport;
+use super::bds::bds60::HeadingAndSpeedReport;
+use super::{Message, MilitaryApplication, DF, ICAO};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Expected difference between BDS 5,0 and ADS-B velocities (in kts)
+const SIGMA_BDS50: f64 = 10.;
+/// Expected difference between BDS 6,0 and ADS-B velocities (in kts)
+const SIGMA_BDS60: f64 = 50.;
+/// Expected difference between BDS 6,0 and ADS-B vertical rates (in ft/mn)
+const SIGMA_VRATE: f64 = 500.;
+/// Penalty for registers not supported according to BDS 1,7
+const UNSUPPORTED: f64 = 0.05;
+/// Minimum confidence to select a register when several are valid
+const MIN_CONFIDENCE: f64 = 0.5;
+/// Minimum duration (in seconds) between two scans for expired aircraft
+const EXPIRY_PERIOD: f64 = 60.;
+
+/// The register selected for a Comm-B reply
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Register {
+    BDS50,
+    BDS60,
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BDS50 => write!(f, "BDS 5,0"),
+            Self::BDS60 => write!(f, "BDS 6,0"),
+        }
+    }
+}
+
+/// The most likely register for a Comm-B reply
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Inference {
+    pub bds: Register,
+    /// A score between 0 and 1
+    pub confidence: f64,
+}
+
+#[derive(Debug, Default)]
+struct AircraftContext {
+    /// Timestamp, ground speed (kts) and track angle (degrees)
+    velocity: Option<(f64, f64, f64)>,
+    /// Timestamp and barometric altitude (ft)
+    altitude: Option<(f64, f64)>,
+    /// Timestamp and vertical rate (ft/mn)
+    vertical_rate: Option<(f64, f64)>,
+    capability: Option<CommonUsageGICBCapabilityReport>,
+    /// Timestamp of the last message received from the aircraft
+    last_seen: f64,
+}
+
+/// Select the most likely register of Comm-B replies (see module documentation)
+#[derive(Debug)]
+pub struct CommBInference {
+    /// The maximum age (in seconds) of ADS-B data used as a reference
+    pub timeout: f64,
+    aircraft: BTreeMap<ICAO, AircraftContext>,
+    last_expiry: f64,
+}
+
+impl CommBInference {
+    pub fn new(timeout: f64) -> Self {
+        Self {
+            timeout,
+            aircraft: BTreeMap::new(),
+            last_expiry: 0.,
+        }
+    }
+
+    /**
+     * Process a message received at time `timestamp`.
+     *
+     * ADS-B messages and capability reports update the state of the
+     * aircraft. When a Comm-B reply is valid as both BDS 5,0 and BDS 6,0, the
+     * least likely register is removed from the message; both are removed if
+     * no register reaches a sufficient confidence. The confidence in the
+     * selected register is set in the `bds_confidence` field of the reply.
+     *
+     * Returns the register selected for the Comm-B reply, if any.
+     */
+    pub fn push(
+        &mut self,
+        timestamp: f64,
+        msg: &mut Message,
+    ) -> Option<Inference> {
+        if timestamp - self.last_expiry >= EXPIRY_PERIOD {
+            self.expire(timestamp);
+        }
+        match &mut msg.df {
+            DF::ExtendedSquitterADSB(adsb) => {
+                self.update(timestamp, adsb.icao24, &adsb.message);
+                None
+            }
+            DF::ExtendedSquitterMilitary {
+                payload: MilitaryApplication::ExtendedSquitter(es),
+                ..
+            } => {
+                self.update(timestamp, es.aa, &es.me);
+                None
+            }
+            DF::CommBAltitudeReply { ac, bds, ap, .. } => {
+                let context = self.context(timestamp, ICAO(ap.0));
+                if ac.0 > 0 {
+                    context.altitude = Some((timestamp, ac.0 as f64));
+                }
+                if let (Some(bds17), None, None) =
+                    (&bds.bds17, &bds.bds50, &bds.bds60)
+                {
+                    context.capability = Some(*bds17);
+                }
+                let inference = self.resolve(
+                    timestamp,
+                    &ICAO(ap.0),
+                    &mut bds.bds50,
+                    &mut bds.bds60,
+                );
+                bds.bds_confidence = inference.map(|i| i.confidence);
+                inference
+            }
+            DF::CommBIdentityReply { bds, ap, .. } => {
+                let context = self.context(timestamp, ICAO(ap.0));
+                if let (Some(bds17), None, None) =
+                    (&bds.bds17, &bds.bds50, &bds.bds60)
+                {
+                    context.capability = Some(*bds17);
+                }
+                let inference = self.resolve(
+                    timestamp,
+                    &ICAO(ap.0),
+                    &mut bds.bds50,
+                    &mut bds.bds60,
+                );
+                bds.bds_confidence = inference.map(|i| i.confidence);
+                inference
+            }
+            _ => None,
+        }
+    }
+
+    /// Forget the aircraft not heard of for longer than the timeout, as
+    /// their state is too old to be used as a reference
+    pub fn expire(&mut self, timestamp: f64) {
+        self.aircraft
+            .retain(|_, context| timestamp - context.last_seen <= self.timeout);
+        self.last_expiry = timestamp;
+    }
+
+    fn context(
+        &mut self,
+        timestamp: f64,
+        icao24: ICAO,
+    ) -> &mut AircraftContext {
+        let context = self.aircraft.entry(icao24).or_default();
+        context.last_seen = timestamp;
+        context
+    }
+
+    fn update(&mut self, timestamp: f64, icao24: ICAO, me: &ME) {
+        let context = self.context(timestamp, icao24);
+        match me {
+            ME::BDS05(bds05) => {
+                if let Some(alt) = bds05.alt {
+                    context.altitude = Some((timestamp, alt as f64));
+                }
+            }
+            ME::BDS09(bds09) => {
+                if let AirborneVelocitySubType::GroundSpeedDecoding(spd) =
+                    &bds09.velocity
+                {
+                    context.velocity =
+                        Some((timestamp, spd.groundspeed, spd.track));
+                }
+                if let Some(vrate) = bds09.vertical_rate {
+                    context.vertical_rate = Some((timestamp, vrate as f64));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve(
+        &self,
+        timestamp: f64,
+        icao24: &ICAO,
+        bds50: &mut Option<TrackAndTurnReport>,
+        bds60: &mut Option<HeadingAndSpeedReport>,
+    ) -> Option<Inference> {
+        if bds50.is_none() && bds60.is_none() {
+            return None;
+        }
+        let context = self.aircraft.get(icao24)?;
+        let fresh = |value: Option<(f64, f64)>| {
+            value.filter(|(ts, _)| timestamp - ts <= self.timeout)
+        };
+        let velocity = context
+            .velocity
+            .filter(|(ts, _, _)| timestamp - ts <= self.timeout)
+            .map(|(_, gs, trk)| (gs, trk));
+        let altitude = fresh(context.altitude).map(|(_, alt)| alt);
+        let vertical_rate = fresh(context.vertical_rate).map(|(_, vr)| vr);
+        if velocity.is_none()
+            && vertical_rate.is_none()
+            && context.capability.is_none()
+        {
+            // Nothing to compare the hypotheses with
+            return None;
+        }
+
+        let score50 = bds50.as_ref().map_or(0., |bds50| {
+            let supported = context.capability.is_none_or(|c| c.bds50);
+            let v50 =
+                bds50.groundspeed.map(|gs| gs as f64).zip(bds50.track_angle);
+            prior(supported) * likelihood(v50, velocity, SIGMA_BDS50)
+        });
+        let score60 = bds60.as_ref().map_or(0., |bds60| {
+            let supported = context.capability.is_none_or(|c| c.bds60);
+            let tas = match (bds60.mach_number, bds60.indicated_airspeed) {
+                (Some(mach), _) => altitude.map(|alt| mach2tas(mach, alt)),
+                (None, Some(ias)) => {
+                    altitude.map(|alt| cas2tas(ias as f64, alt))
+                }
+                _ => None,
+            };
+            let v60 = tas.zip(bds60.magnetic_heading);
+            let vrate = bds60
+                .inertial_vertical_velocity
+                .or(bds60.barometric_altitude_rate)
+                .map(|vr| vr as f64);
+            let vrate_likelihood = match (vrate, vertical_rate) {
+                (Some(vr), Some(reference)) => {
+                    gaussian((vr - reference) / SIGMA_VRATE)
+                }
+                _ => 1.,
+            };
+            prior(supported)
+                * likelihood(v60, velocity, SIGMA_BDS60)
+                * vrate_likelihood
+        });
+        // The score of an outlier at three standard deviations
+        let none = gaussian(3.);
+        let total = score50 + score60 + none;
+
+        let inference = if score50 >= score60 {
+            Inference {
+                bds: Register::BDS50,
+                confidence: score50 / total,
+            }
+        } else {
+            Inference {
+                bds: Register::BDS60,
+                confidence: score60 / total,
+            }
+        };
+        let ambiguous = bds50.is_some() && bds60.is_some();
+        if inference.confidence < MIN_CONFIDENCE {
+            if ambiguous {
+                *bds50 = None;
+                *bds60 = None;
+            }
+            return None;
+        }
+        match inference.bds {
+            Register::BDS50 => *bds60 = None,
+            Register::BDS60 => *bds50 = None,
+        }
+        Some(inference)
+    }
+}
+
+fn prior(supported: bool) -> f64 {
+    if supported {
+        1.
+    } else {
+        UNSUPPORTED
+    }
+}
+
+fn gaussian(x: f64) -> f64 {
+    (-0.5 * x * x).exp()
+}
+
+/// Compare the velocity vectors (speed in kts, angle in degrees) of a
+/// hypothesis and of the reference. A missing value in the hypothesis
+/// counts as a one standard deviation difference.
+fn likelihood(
+    velocity: Option<(f64, f64)>,
+    reference: Option<(f64, f64)>,
+    sigma: f64,
+) -> f64 {
+    match (velocity, reference) {
+        (_, None) => 1.,
+        (None, Some(_)) => gaussian(1.),
+        (Some((spd, angle)), Some((ref_spd, ref_angle))) => {
+            let (x, y) = (
+                spd * angle.to_radians().sin(),
+                spd * angle.to_radians().cos(),
+            );
+            let (ref_x, ref_y) = (
+                ref_spd * ref_angle.to_radians().sin(),
+                ref_spd * ref_angle.to_radians().cos(),
+            );
+            gaussian((x - ref_x).hypot(y - ref_y) / sigma)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deku::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_bds50_or_60() {
+        // Valid as both BDS 5,0 and BDS 6,0
+        let bytes = hex!("A8001EBCFFFB23286004A73F6A5B");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let icao24 = ICAO(msg.crc);
+        let DF::CommBIdentityReply { bds, .. } = &msg.df else {
+            unreachable!()
+        };
+        let bds50 = bds.bds50.clone().unwrap();
+        let bds60 = bds.bds60.clone().unwrap();
+
+        // Without any reference, both registers are kept
+        let mut inference = CommBInference::new(10.);
+        let mut unresolved = msg.clone();
+        inference.aircraft.insert(
+            icao24,
+            AircraftContext {
+                altitude: Some((0., 30000.)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(inference.push(0., &mut unresolved), None);
+        let DF::CommBIdentityReply { bds, .. } = &unresolved.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds50.as_ref(), Some(&bds50));
+        assert_eq!(bds.bds60.as_ref(), Some(&bds60));
+        assert_eq!(bds.bds_confidence, None);
+
+        // With an ADS-B velocity matching the BDS 5,0 interpretation
+        let gs = bds50.groundspeed.unwrap() as f64;
+        let trk = bds50.track_angle.unwrap();
+        inference.aircraft.insert(
+            icao24,
+            AircraftContext {
+                velocity: Some((0., gs + 3., trk - 1.)),
+                altitude: Some((0., 30000.)),
+                ..Default::default()
+            },
+        );
+        let mut resolved = msg.clone();
+        let result = inference.push(1., &mut resolved).unwrap();
+        assert_eq!(result.bds, Register::BDS50);
+        assert!(result.confidence > 0.9);
+        let DF::CommBIdentityReply { bds, .. } = &resolved.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds50, Some(bds50));
+        assert!(bds.bds60.is_none());
+        assert_eq!(bds.bds_confidence, Some(result.confidence));
+
+        // The reference is too old: no longer a reference
+        let mut expired = msg.clone();
+        assert_eq!(inference.push(20., &mut expired), None);
+        let DF::CommBIdentityReply { bds, .. } = &expired.df else {
+            unreachable!()
+        };
+        assert!(bds.bds50.is_some() && bds.bds60.is_some());
+
+        // With a capability report excluding BDS 5,0, and no velocity
+        inference.aircraft.insert(
+            icao24,
+            AircraftContext {
+                capability: Some(CommonUsageGICBCapabilityReport {
+                    bds50: false,
+                    bds60: true,
+                    ..capability()
+                }),
+                ..Default::default()
+            },
+        );
+        let mut resolved = msg.clone();
+        let result = inference.push(1., &mut resolved).unwrap();
+        assert_eq!(result.bds, Register::BDS60);
+        let DF::CommBIdentityReply { bds, .. } = &resolved.df else {
+            unreachable!()
+        };
+        assert_eq!(bds.bds60, Some(bds60));
+        assert!(bds.bds50.is_none());
+    }
+
+    #[test]
+    fn test_expire() {
+        let bytes = hex!("A8001EBCFFFB23286004A73F6A5B");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let icao24 = ICAO(msg.crc);
+        let mut inference = CommBInference::new(10.);
+
+        inference.push(0., &mut msg.clone());
+        inference.expire(5.);
+        assert!(inference.aircraft.contains_key(&icao24));
+        inference.expire(15.);
+        assert!(!inference.aircraft.contains_key(&icao24));
+    }
+
+    fn capability() -> CommonUsageGICBCapabilityReport {
+        let bytes = hex!("a0000638fa81c10000000081a92f");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        match msg.df {
+            DF::CommBAltitudeReply { bds, .. } => bds.bds17.unwrap(),
+            _ => unreachable!(),
+        }
+    }
+}
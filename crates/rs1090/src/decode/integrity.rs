@@ -0,0 +1,307 @@
+/**
+* The integrity of ADS-B positions is not fully described by a single
+* message. The typecode of airborne (BDS 0,5) and surface (BDS 0,6) position
+* messages sets a range of possible Navigation Integrity Categories (NIC),
+* which is refined by supplement bits spread over several messages:
+*
+* - NIC-A (or NICs in version 1) in the aircraft operation status (BDS 6,5);
+* - NIC-B in airborne position messages (BDS 0,5), only in version 2, as the
+*   same bit is the Single Antenna Flag (SAF) in previous versions;
+* - NIC-C in the surface aircraft operation status (BDS 6,5), only in
+*   version 2.
+*
+* The interpretation of these bits depends on the ADS-B version of the
+* transponder, also reported in the aircraft operation status. The
+* [`IntegrityState`] keeps track of this information for each aircraft, and
+* computes the [`Integrity`] of each position message: the NIC and the
+* associated radius of containment (Rc), the Navigation Accuracy Category
+* for velocity (NACv) and the Surveillance Integrity Level (SIL).
+*
+* Before any aircraft operation status is received, the version is assumed
+* to be 0: the radius of containment is then the Horizontal Protection Limit
+* (HPL) associated to the NUCp.
+*/
+use super::adsb::ME;
+use super::bds::bds05::AirbornePosition;
+use super::bds::bds06::SurfacePosition;
+use super::bds::bds65::{
+    ADSBVersionAirborne, ADSBVersionSurface, AircraftOperationStatus,
+};
+use serde::{Deserialize, Serialize};
+
+/// The integrity information attached to a position message
+#[derive(Debug, PartialEq, Serialize, Deserialize, Copy, Clone)]
+pub struct Integrity {
+    /// The ADS-B version of the transponder (0 if unknown)
+    pub version: u8,
+
+    #[serde(rename = "NIC", skip_serializing_if = "Option::is_none")]
+    /// The Navigation Integrity Category (NIC), only from version 1
+    pub nic: Option<u8>,
+
+    #[serde(rename = "Rc", skip_serializing_if = "Option::is_none")]
+    /// The radius of containment (in m), None if unknown
+    pub rc: Option<f64>,
+
+    #[serde(rename = "NACv", skip_serializing_if = "Option::is_none")]
+    /// The Navigation Accuracy Category for velocity (NACv)
+    pub nac_v: Option<u8>,
+
+    #[serde(rename = "SIL", skip_serializing_if = "Option::is_none")]
+    /// The Surveillance Integrity Level (SIL)
+    pub sil: Option<u8>,
+}
+
+/// The integrity information reported by an aircraft over several messages
+#[derive(Debug, Default, Copy, Clone)]
+pub struct IntegrityState {
+    /// The ADS-B version, as reported in the aircraft operation status
+    pub version: u8,
+    /// The NIC supplement A (NICs in version 1)
+    pub nic_a: u8,
+    /// The NIC supplement C, for surface positions in version 2
+    pub nic_c: u8,
+    /// The NACv, from airborne velocities or surface operation status
+    pub nac_v: Option<u8>,
+    /// The SIL, from the aircraft operation status
+    pub sil: Option<u8>,
+}
+
+impl IntegrityState {
+    /// Update the state with an aircraft operation status (BDS 6,5) or an
+    /// airborne velocity (BDS 0,9) message; other messages are ignored.
+    pub fn update(&mut self, msg: &ME) {
+        match msg {
+            ME::BDS65(AircraftOperationStatus::Airborne(status)) => {
+                match status.version {
+                    ADSBVersionAirborne::DOC9871AppendixA(_) => {
+                        self.version = 0
+                    }
+                    ADSBVersionAirborne::DOC9871AppendixB(v1) => {
+                        self.version = 1;
+                        self.nic_a = v1.nic_s;
+                        self.sil = Some(v1.sil);
+                    }
+                    ADSBVersionAirborne::DOC9871AppendixC(v2) => {
+                        self.version = 2;
+                        self.nic_a = v2.nic_a;
+                        self.sil = Some(v2.sil);
+                    }
+                    ADSBVersionAirborne::Reserved { .. } => {}
+                }
+            }
+            ME::BDS65(AircraftOperationStatus::Surface(status)) => {
+                match status.version {
+                    ADSBVersionSurface::DOC9871AppendixA(_) => self.version = 0,
+                    ADSBVersionSurface::DOC9871AppendixB(v1) => {
+                        self.version = 1;
+                        self.nic_a = v1.nic_s;
+                        self.sil = Some(v1.sil);
+                    }
+                    ADSBVersionSurface::DOC9871AppendixC(v2) => {
+                        self.version = 2;
+                        self.nic_a = v2.nic_a;
+                        self.nic_c = status.capability_class.nic_c;
+                        self.sil = Some(v2.sil);
+                    }
+                    ADSBVersionSurface::Reserved { .. } => {}
+                }
+                if self.version > 0 {
+                    self.nac_v = Some(status.capability_class.nac_v);
+                }
+            }
+            ME::BDS09(velocity) => {
+                // This field is a NUCv in version 0
+                self.nac_v = Some(velocity.nac_v);
+            }
+            _ => {}
+        }
+    }
+
+    /// The integrity of an airborne position, given the current state
+    pub fn airborne(&self, msg: &AirbornePosition) -> Integrity {
+        let (nic, rc) = match self.version {
+            0 => (None, hpl(msg.tc)),
+            // Only one supplement bit in version 1
+            1 => airborne_nic(msg.tc, self.nic_a, self.nic_a),
+            _ => airborne_nic(msg.tc, self.nic_a, msg.saf_or_nicb.unwrap_or(0)),
+        };
+        self.integrity(nic, rc)
+    }
+
+    /// The integrity of a surface position, given the current state
+    pub fn surface(&self, msg: &SurfacePosition) -> Integrity {
+        let (nic, rc) = match self.version {
+            0 => (None, hpl(msg.tc)),
+            1 => match msg.tc {
+                // NIC-C is not defined in version 1
+                8 => (Some(0), None),
+                tc => surface_nic(tc, self.nic_a, 0),
+            },
+            _ => surface_nic(msg.tc, self.nic_a, self.nic_c),
+        };
+        self.integrity(nic, rc)
+    }
+
+    fn integrity(&self, nic: Option<u8>, rc: Option<f64>) -> Integrity {
+        Integrity {
+            version: self.version,
+            nic,
+            rc,
+            nac_v: self.nac_v.filter(|_| self.version > 0),
+            sil: self.sil,
+        }
+    }
+}
+
+/**
+ * The NIC and the radius of containment (in m) of an airborne position,
+ * based on the typecode and the NIC supplements A and B.
+ *
+ * Reference: DO-260B, Table 2-14
+ */
+pub fn airborne_nic(tc: u8, nic_a: u8, nic_b: u8) -> (Option<u8>, Option<f64>) {
+    match (tc, nic_a, nic_b) {
+        (9 | 20, _, _) => (Some(11), Some(7.5)),
+        (10 | 21, _, _) => (Some(10), Some(25.)),
+        (11, 1, 1) => (Some(9), Some(75.)),
+        (11, 0, 0) => (Some(8), Some(185.2)),
+        (12, _, _) => (Some(7), Some(370.4)),
+        (13, 0, 1) => (Some(6), Some(555.6)),
+        (13, 0, 0) => (Some(6), Some(926.)),
+        (13, 1, 1) => (Some(6), Some(1111.2)),
+        (14, _, _) => (Some(5), Some(1852.)),
+        (15, _, _) => (Some(4), Some(3704.)),
+        (16, 1, 1) => (Some(3), Some(7408.)),
+        (16, 0, 0) => (Some(2), Some(14816.)),
+        (17, _, _) => (Some(1), Some(37040.)),
+        (18 | 22, _, _) => (Some(0), None),
+        _ => (None, None),
+    }
+}
+
+/**
+ * The NIC and the radius of containment (in m) of a surface position,
+ * based on the typecode and the NIC supplements A and C.
+ *
+ * Reference: DO-260B, Table 2-15
+ */
+pub fn surface_nic(tc: u8, nic_a: u8, nic_c: u8) -> (Option<u8>, Option<f64>) {
+    match (tc, nic_a, nic_c) {
+        (5, _, _) => (Some(11), Some(7.5)),
+        (6, _, _) => (Some(10), Some(25.)),
+        (7, 1, _) => (Some(9), Some(75.)),
+        (7, 0, _) => (Some(8), Some(185.2)),
+        (8, 1, 1) => (Some(7), Some(370.4)),
+        (8, 1, 0) => (Some(6), Some(555.6)),
+        (8, 0, 1) => (Some(6), Some(1111.2)),
+        (8, 0, 0) => (Some(0), None),
+        _ => (None, None),
+    }
+}
+
+/// The Horizontal Protection Limit (in m) associated to the NUCp in version 0
+fn hpl(tc: u8) -> Option<f64> {
+    match tc {
+        5 | 9 | 20 => Some(7.5),
+        6 | 10 | 21 => Some(25.),
+        7 | 11 => Some(185.2),
+        8 | 12 => Some(370.4),
+        13 => Some(926.),
+        14 => Some(1852.),
+        15 => Some(3704.),
+        16 => Some(18520.),
+        17 => Some(37040.),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::bds::bds65::{AirborneV2, OperationStatusAirborne};
+    use crate::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_airborne_integrity() {
+        let bytes = hex!("8D40058B58C901375147EFD09357");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let ExtendedSquitterADSB(ADSB {
+            message: ME::BDS05(mut bds05),
+            ..
+        }) = msg.df
+        else {
+            unreachable!()
+        };
+        assert_eq!(bds05.tc, 11);
+
+        // Version 0, only based on the typecode
+        let mut state = IntegrityState::default();
+        let integrity = state.airborne(&bds05);
+        assert_eq!(integrity.version, 0);
+        assert_eq!(integrity.nic, None);
+        assert_eq!(integrity.rc, Some(185.2));
+
+        // Version 2, with NIC-A and NIC-B
+        let status = OperationStatusAirborne {
+            capability_class: Default::default(),
+            operational_mode: Default::default(),
+            version: ADSBVersionAirborne::DOC9871AppendixC(AirborneV2 {
+                nic_a: 1,
+                nac_p: 10,
+                geometry_vertical_accuracy: 2,
+                sil: 3,
+                barometric_altitude_integrity: 1,
+                horizontal_reference_direction: 0,
+                sil_s: 0,
+            }),
+        };
+        state.update(&ME::BDS65(AircraftOperationStatus::Airborne(status)));
+        bds05.saf_or_nicb = Some(1);
+        let integrity = state.airborne(&bds05);
+        assert_eq!(integrity.version, 2);
+        assert_eq!(integrity.nic, Some(9));
+        assert_eq!(integrity.rc, Some(75.));
+        assert_eq!(integrity.sil, Some(3));
+
+        // Inconsistent supplements
+        bds05.saf_or_nicb = Some(0);
+        let integrity = state.airborne(&bds05);
+        assert_eq!(integrity.nic, None);
+        assert_eq!(integrity.rc, None);
+    }
+
+    #[test]
+    fn test_surface_integrity_v0() {
+        let bytes = hex!("8c4841753a9a153237aef0f275be");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let ExtendedSquitterADSB(ADSB {
+            message: ME::BDS06(mut bds06),
+            ..
+        }) = msg.df
+        else {
+            unreachable!()
+        };
+        assert_eq!(bds06.tc, 7);
+
+        // Version 0, only based on the typecode (NUCp 7, then 6)
+        let state = IntegrityState::default();
+        let integrity = state.surface(&bds06);
+        assert_eq!(integrity.version, 0);
+        assert_eq!(integrity.nic, None);
+        assert_eq!(integrity.rc, Some(185.2));
+
+        bds06.tc = 8;
+        let integrity = state.surface(&bds06);
+        assert_eq!(integrity.rc, Some(370.4));
+    }
+
+    #[test]
+    fn test_surface_integrity() {
+        assert_eq!(surface_nic(8, 1, 1), (Some(7), Some(370.4)));
+        assert_eq!(surface_nic(8, 0, 1), (Some(6), Some(1111.2)));
+        assert_eq!(surface_nic(8, 0, 0), (Some(0), None));
+        assert_eq!(airborne_nic(13, 0, 1), (Some(6), Some(555.6)));
+    }
+}
@@ -0,0 +1,155 @@
+/**
+* Mode S interrogators (secondary surveillance radars) are identified by an
+* interrogator identifier (II code, from 0 to 15) or by a surveillance
+* identifier (SI code, from 1 to 63). Replies carry this code in two ways:
+*
+* - in all-call replies (DF=11), the parity is overlaid with the interrogator
+*   code, so the checksum of the message is the 7-bit field made of the code
+*   label (CL, 3 bits) and of the interrogator code (IC, 4 bits);
+* - in surveillance and Comm-B replies (DF=4, 5, 20 and 21), the utility
+*   message (UM) reports the II code of the interrogator holding a multisite
+*   reservation (IIS), when the reservation type (IDS) is not null.
+*
+* Both checks require a known aircraft address: the address of a corrupted
+* surveillance reply would not match, and an all-call reply with bit errors
+* would most likely have a checksum outside of the range of valid codes.
+*
+* Note that acquisition squitters (DF=11 messages transmitted spontaneously)
+* are undistinguishable from replies to interrogators with an II code of 0.
+*/
+use super::{Message, UtilityMessageType, DF, ICAO};
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// The identifier of a Mode S interrogator
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub enum InterrogatorCode {
+    /// Interrogator identifier (0 to 15)
+    II(u8),
+    /// Surveillance identifier (1 to 63)
+    SI(u8),
+}
+
+impl InterrogatorCode {
+    /// Decode the interrogator code from the code label (CL) and interrogator
+    /// code (IC) fields, as found in the parity of all-call replies.
+    pub fn new(cl: u8, ic: u8) -> Option<Self> {
+        match (cl, ic) {
+            (_, 16..) => None,
+            (0, ic) => Some(Self::II(ic)),
+            (1, 0) => None,
+            (1..=4, ic) => Some(Self::SI(16 * (cl - 1) + ic)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InterrogatorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::II(code) => write!(f, "II{code}"),
+            Self::SI(code) => write!(f, "SI{code}"),
+        }
+    }
+}
+
+impl Serialize for InterrogatorCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/**
+ * Recover the code of the interrogator which triggered a reply from the
+ * aircraft with the known address `icao24`.
+ *
+ * Returns None if the message is not an all-call, surveillance or Comm-B
+ * reply, if it was not sent by `icao24`, or if it does not report any
+ * interrogator.
+ */
+pub fn interrogator(msg: &Message, icao24: &ICAO) -> Option<InterrogatorCode> {
+    match &msg.df {
+        DF::AllCallReply { icao, .. } if icao == icao24 => {
+            let cl = (msg.crc >> 4) as u8;
+            let ic = (msg.crc & 0xf) as u8;
+            match msg.crc {
+                0..=0x4f => InterrogatorCode::new(cl, ic),
+                _ => None,
+            }
+        }
+        DF::SurveillanceAltitudeReply { um, ap, .. }
+        | DF::SurveillanceIdentityReply { um, ap, .. }
+        | DF::CommBAltitudeReply { um, ap, .. }
+        | DF::CommBIdentityReply { um, ap, .. }
+            if ap.0 == icao24.0 =>
+        {
+            match um.ids {
+                UtilityMessageType::NoInformation => None,
+                _ => Some(InterrogatorCode::II(um.iis)),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deku::prelude::*;
+    use hexlit::hex;
+
+    #[test]
+    fn test_interrogator_code() {
+        assert_eq!(InterrogatorCode::new(0, 0), Some(InterrogatorCode::II(0)));
+        assert_eq!(InterrogatorCode::new(0, 9), Some(InterrogatorCode::II(9)));
+        assert_eq!(InterrogatorCode::new(1, 0), None);
+        assert_eq!(InterrogatorCode::new(1, 5), Some(InterrogatorCode::SI(5)));
+        assert_eq!(
+            InterrogatorCode::new(4, 15),
+            Some(InterrogatorCode::SI(63))
+        );
+        assert_eq!(InterrogatorCode::new(5, 1), None);
+        assert_eq!(format!("{}", InterrogatorCode::SI(42)), "SI42");
+    }
+
+    #[test]
+    fn test_all_call() {
+        let bytes = hex!("5d4ca8d9a8a7d3");
+        let (_, mut msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let icao24 = ICAO(0x4ca8d9);
+        // The checksum is not a valid interrogator code
+        assert_eq!(interrogator(&msg, &icao24), None);
+
+        for (crc, code) in [
+            (0, InterrogatorCode::II(0)),
+            (0b000_1011, InterrogatorCode::II(11)),
+            (0b010_0101, InterrogatorCode::SI(21)),
+        ] {
+            msg.crc = crc;
+            let bytes = msg.to_bytes().unwrap();
+            let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+            assert_eq!(interrogator(&msg, &icao24), Some(code));
+            assert_eq!(interrogator(&msg, &ICAO(0x123456)), None);
+        }
+    }
+
+    #[test]
+    fn test_surveillance() {
+        let bytes = hex!("20001838ca3804");
+        let (_, mut msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let icao24 = ICAO(msg.crc);
+        assert_eq!(interrogator(&msg, &icao24), None);
+
+        // Comm-B reservation by the interrogator II=5
+        if let DF::SurveillanceAltitudeReply { um, .. } = &mut msg.df {
+            um.iis = 5;
+            um.ids = UtilityMessageType::CommB;
+        }
+        let bytes = msg.to_bytes().unwrap();
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(interrogator(&msg, &icao24), Some(InterrogatorCode::II(5)));
+    }
+}
@@ -1,15 +1,25 @@
 pub mod adsb;
+pub mod atmosphere;
 pub mod bds;
 pub mod commb;
 pub mod cpr;
 pub mod crc;
+pub mod derived;
+pub mod elm;
+pub mod error;
 pub mod flarm;
+pub mod inference;
+pub mod integrity;
+pub mod interrogator;
+pub mod modeac;
 pub mod time;
 
 use adsb::{ADSB, ME};
-use commb::{DF20DataSelector, DF21DataSelector};
-use crc::modes_checksum;
+use commb::{DF16DataSelector, DF20DataSelector, DF21DataSelector};
+use crc::{fix_bit_errors, modes_checksum};
 use deku::prelude::*;
+pub use error::Error;
+use modeac::ModeAC;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
@@ -36,7 +46,9 @@ use tracing::debug;
  * | 24       | [`DF::CommDExtended`]               | 3.1.2.7.3   |
  */
 
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 #[deku(id_type = "u8", bits = "5", ctx = "crc: u32")]
 #[serde(tag = "df")]
 pub enum DF {
@@ -65,9 +77,9 @@ pub enum DF {
         #[serde(skip)]
         unused1: u8,
         /// Reply information
-        #[deku(bits = "4")]
         #[serde(skip)]
-        ri: u8,
+        #[deku(reader = "ReplyInformation::read(deku::reader)")]
+        ri: ReplyInformation,
         /// unused
         #[deku(bits = "2")]
         #[serde(skip)]
@@ -155,14 +167,9 @@ pub enum DF {
         #[deku(bits = "2")]
         #[serde(skip)]
         reserved2: u8,
-        #[deku(bits = "4")]
-        /// Reply information
-        ///
-        /// - 0000: No operating ACAS
-        /// - 0010: ACAS with resolution capability inhibited
-        /// - 0011: ACAS with vertical-only resolution capability
-        /// - 0111: ACAS with vertical and horizontal resolution capability
-        ri: u8,
+        /// Reply information: ACAS capability or maximum airspeed
+        #[deku(reader = "ReplyInformation::read(deku::reader)")]
+        ri: ReplyInformation,
         #[deku(bits = "2")]
         #[serde(skip)]
         reserved3: u8,
@@ -170,9 +177,8 @@ pub enum DF {
         #[serde(rename = "altitude")]
         ac: AC13Field,
         /// Message, ACAS (56 bits, a BDS of a type requested in UF=0)
-        #[deku(count = "7")]
-        #[serde(skip)]
-        mv: Vec<u8>,
+        #[serde(flatten)]
+        mv: DF16DataSelector,
         /// Address/Parity
         #[serde(rename = "icao24")]
         #[deku(ctx = "crc")]
@@ -201,10 +207,15 @@ pub enum DF {
 
     /// DF=19: Extended Squitter Military Application, Downlink Format 19 (3.1.2.8.8)
     #[deku(id = "19")]
+    #[serde(rename = "19")]
     ExtendedSquitterMilitary {
-        /// Reserved
+        /// Application Field
         #[deku(bits = "3")]
         af: u8,
+        /// Message content, depending on the application field
+        #[serde(flatten)]
+        #[deku(ctx = "*af")]
+        payload: MilitaryApplication,
     },
 
     /// Comm-B Altitude Reply, Downlink Format 20 (3.1.2.6.6)
@@ -260,9 +271,15 @@ pub enum DF {
 
     /// 24: Comm-D Extended, Downlink Format 24 (3.1.2.7.3)
     #[deku(id_pat = "24..=31")]
+    #[serde(rename = "24")]
     CommDExtended {
+        /// Format number: only the first two bits (0b11) identify DF24
+        #[deku(bits = "2")]
+        #[serde(skip)]
+        format: u8,
         /// Reserved
         #[deku(bits = "1")]
+        #[serde(skip)]
         spare: u8,
         /// Control, ELM
         #[serde(skip)]
@@ -272,16 +289,19 @@ pub enum DF {
         nd: u8,
         /// Message, Comm-D, 80 bits
         #[deku(count = "10")]
+        #[serde(serialize_with = "as_hex", deserialize_with = "from_hex")]
         md: Vec<u8>,
         /// Address/Parity
-        parity: ICAO,
+        #[serde(rename = "icao24")]
+        #[deku(ctx = "crc")]
+        ap: IcaoParity,
     },
 }
 
 /// The entry point to Mode S and ADS-B decoding
 ///
 /// Use as `Message::try_from()` in mostly all applications
-#[derive(Debug, PartialEq, Serialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Message {
     /// Calculated from all bits, should be 0 for ADS-B (raises a DekuError),
     /// icao24 otherwise
@@ -323,7 +343,7 @@ impl DekuContainerRead<'_> for Message {
         }
 
         let value = Self::from_reader_with_ctx(reader, ())?;
-        let read_whole_byte = (reader.bits_read % 8) == 0;
+        let read_whole_byte = reader.bits_read.is_multiple_of(8);
         let idx = if read_whole_byte {
             reader.bits_read / 8
         } else {
@@ -352,6 +372,9 @@ impl DekuReader<'_> for Message {
 
         // Decode the DF quickly to determine the length of the message
         let df = remaining_bytes[0] >> 3;
+        if !matches!(df, 0 | 4 | 5 | 11 | 16..=21 | 24..=31) {
+            return Err(Error::UnknownFormat { df }.raise());
+        }
 
         let bit_len = if df & 0x10 != 0 {
             MODES_LONG_MSG_BYTES * 8
@@ -365,18 +388,36 @@ impl DekuReader<'_> for Message {
         remaining_bytes.extend_from_slice(&res);
 
         let crc = modes_checksum(&remaining_bytes, bit_len)?;
-        // Also the CRC must be 0 for ADS-B (DF=17) messages
+        // Also the CRC must be 0 for ADS-B (DF=17) messages, and for the
+        // military extended squitters following the same format (DF=19, AF=0)
+        let af = remaining_bytes[0] & 0x07;
         match (df, crc) {
-            (17, c) if c > 0 => Err(DekuError::Assertion(
-                format!("Invalid CRC in ADS-B message: {c}").into(),
-            )),
+            (17, c) if c > 0 => Err(Error::Crc { df, crc }.raise()),
+            (19, c) if c > 0 && af == 0 => Err(Error::Crc { df, crc }.raise()),
             _ => {
                 // Restart reading by creating a new cursor/reader (with context)
                 let mut input = deku::no_std_io::Cursor::new(&remaining_bytes);
                 let mut reader = Reader::new(&mut input);
                 match DF::from_reader_with_ctx(&mut reader, crc) {
                     Ok(df) => Ok(Self { crc, df }),
-                    Err(e) => Err(e),
+                    Err(e) => {
+                        // Invalid content for the register designated by the
+                        // typecode of an extended squitter
+                        let tc = remaining_bytes[4] >> 3;
+                        let es = df == 17 || df == 18 || (df == 19 && af == 0);
+                        match (
+                            es.then(|| adsb::register(tc)).flatten(),
+                            e.into(),
+                        ) {
+                            (_, Error::Deku(e)) => Err(e),
+                            (Some(bds), reason) => Err(Error::Hypothesis {
+                                bds,
+                                reason: Box::new(reason),
+                            }
+                            .raise()),
+                            (None, reason) => Err(reason.raise()),
+                        }
+                    }
                 }
             }
         }
@@ -399,6 +440,132 @@ impl core::convert::TryFrom<&[u8]> for Message {
     }
 }
 
+/// Messages are encoded with a valid parity field, computed from the
+/// content of the message and from the address (or the interrogator
+/// identifier for DF11), so the parity bits stored in the structure are not
+/// relevant. Military messages (DF19) with a reserved application field
+/// carry no parity and are encoded as is.
+impl DekuWriter for Message {
+    fn to_writer<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+        &self,
+        writer: &mut Writer<W>,
+        _: (),
+    ) -> Result<(), DekuError> {
+        let mut frame = Vec::new();
+        let mut cursor = deku::no_std_io::Cursor::new(&mut frame);
+        let mut df_writer = Writer::new(&mut cursor);
+        self.df.to_writer(&mut df_writer, self.crc)?;
+        df_writer.finalize()?;
+
+        let bits = if frame[0] & 0x80 != 0 { 112 } else { 56 };
+        if frame.len() > bits / 8 {
+            return Err(DekuError::InvalidParam(
+                format!("Encoded message is longer than {bits} bits").into(),
+            ));
+        }
+        // Downlink formats with unknown content are padded with zeros
+        frame.resize(bits / 8, 0);
+
+        if let Some(address_parity) = self.address_parity() {
+            let n = frame.len();
+            frame[n - 3..].fill(0);
+            let parity = modes_checksum(&frame, bits)? ^ address_parity;
+            frame[n - 3..].copy_from_slice(&parity.to_be_bytes()[1..]);
+        }
+
+        writer.write_bytes(&frame)
+    }
+}
+
+impl DekuContainerWrite for Message {}
+
+impl core::convert::TryFrom<Message> for Vec<u8> {
+    type Error = DekuError;
+
+    #[inline]
+    fn try_from(input: Message) -> core::result::Result<Self, Self::Error> {
+        input.to_bytes()
+    }
+}
+
+impl Message {
+    /// The value the checksum of the encoded message must be equal to: the
+    /// address for address/parity fields, the interrogator identifier for
+    /// DF11 and 0 for extended squitters (None if there is no parity field).
+    fn address_parity(&self) -> Option<u32> {
+        match &self.df {
+            DF::ShortAirAirSurveillance { ap, .. }
+            | DF::SurveillanceAltitudeReply { ap, .. }
+            | DF::SurveillanceIdentityReply { ap, .. }
+            | DF::LongAirAirSurveillance { ap, .. }
+            | DF::CommBAltitudeReply { ap, .. }
+            | DF::CommBIdentityReply { ap, .. }
+            | DF::CommDExtended { ap, .. } => Some(ap.0),
+            DF::AllCallReply { .. } => Some(self.crc),
+            DF::ExtendedSquitterADSB(_)
+            | DF::ExtendedSquitterTisB { .. }
+            | DF::ExtendedSquitterMilitary {
+                payload: MilitaryApplication::ExtendedSquitter(_),
+                ..
+            } => Some(0),
+            DF::ExtendedSquitterMilitary { .. } => None,
+        }
+    }
+
+    /// Correct up to `max_bits` bit errors (at most 2) in a frame, based on
+    /// the syndrome table in [`crc`].
+    ///
+    /// Only DF11, DF17 and DF18 messages are corrected, as their checksum is
+    /// expected to be null. (DF11 messages may carry a non-null interrogator
+    /// identifier code, so they are only corrected when the checksum cannot
+    /// be such a code, and like in dump1090 at most one bit is corrected, as
+    /// the short frames are too prone to wrong two-bit corrections.)
+    ///
+    /// The frame is corrected in place and the number of corrected bits is
+    /// returned, 0 if the frame could not be corrected.
+    pub fn fix_bit_errors(frame: &mut [u8], max_bits: u8) -> Result<u8, Error> {
+        Error::check_length(frame)?;
+        let df = frame[0] >> 3;
+        let bits = if df & 0x10 != 0 { 112 } else { 56 };
+        let crc = modes_checksum(frame, bits)?;
+        let (fixable, max_bits) = match df {
+            11 => (crc > 0x7f, max_bits.min(1)),
+            17 | 18 => (crc > 0, max_bits),
+            _ => (false, 0),
+        };
+        if fixable {
+            if let Some(n) = fix_bit_errors(frame, bits, crc, max_bits) {
+                debug!("Corrected {} bit(s) in DF={}", n, df);
+                return Ok(n);
+            }
+        }
+        Ok(0)
+    }
+
+    /// Decode a message after correcting up to `max_bits` bit errors, see
+    /// [`Message::fix_bit_errors`].
+    ///
+    /// The frame is corrected in place; the number of corrected bits is
+    /// returned together with the message.
+    pub fn from_bytes_with_fix(
+        frame: &mut [u8],
+        max_bits: u8,
+    ) -> Result<(Self, u8), Error> {
+        let corrected = Self::fix_bit_errors(frame, max_bits)?;
+        let (_, msg) = Self::from_bytes((frame, 0))?;
+        Ok((msg, corrected))
+    }
+
+    /// Decode a message, with the reason of the failure as an [`Error`].
+    ///
+    /// Unlike [`Message::from_bytes`], the frame must have the exact length
+    /// of its downlink format.
+    pub fn decode(frame: &[u8]) -> Result<Self, Error> {
+        Error::check_length(frame)?;
+        Ok(Self::try_from(frame)?)
+    }
+}
+
 impl fmt::Display for Message {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let crc = self.crc;
@@ -436,7 +603,7 @@ impl fmt::Display for Message {
                 writeln!(f, "  ICAO Address:  {icao} (Mode S / ADS-B)")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
             }
-            DF::LongAirAirSurveillance { ac, .. } => {
+            DF::LongAirAirSurveillance { ac, ri, mv, .. } => {
                 writeln!(f, " DF16. Long Air-Air ACAS")?;
                 writeln!(f, "  ICAO Address:  {crc:06x} (Mode S / ADS-B)")?;
                 if ac.0 > 0 {
@@ -446,6 +613,11 @@ impl fmt::Display for Message {
                 } else {
                     writeln!(f, "  Air/Ground:    ground")?;
                 }
+                writeln!(f, "  ACAS:          {ri}")?;
+                if let Some(bds30) = &mv.bds30 {
+                    writeln!(f, "  ACAS RA (BDS 3,0)")?;
+                    write!(f, "{}", bds30.ra)?;
+                }
             }
             DF::ExtendedSquitterADSB(msg) => {
                 write!(f, "{msg}")?;
@@ -454,7 +626,11 @@ impl fmt::Display for Message {
                 // DF18
                 write!(f, "{cf}")?;
             }
-            DF::ExtendedSquitterMilitary { .. } => {} // DF19
+            DF::ExtendedSquitterMilitary { af, payload } => {
+                writeln!(f, " DF19. Extended Squitter Military Application")?;
+                writeln!(f, "  Application:   {af}")?;
+                write!(f, "{payload}")?;
+            }
             DF::CommBAltitudeReply { ac, bds, .. } => {
                 writeln!(f, " DF20. Comm-B, Altitude Reply")?;
                 writeln!(f, "  ICAO Address:  {crc:x?}")?;
@@ -468,9 +644,12 @@ impl fmt::Display for Message {
                 writeln!(f, "  Squawk:        {id:x?}")?;
                 write!(f, "    {bds}")?;
             }
-            DF::CommDExtended { .. } => {
-                writeln!(f, " DF24..=31 Comm-D Extended Length Message")?;
-                writeln!(f, "  ICAO Address:     {crc:x?}")?;
+            DF::CommDExtended { ke, nd, md, ap, .. } => {
+                writeln!(f, " DF24. Comm-D Extended Length Message")?;
+                writeln!(f, "  ICAO Address:  {ap} (Mode S / ADS-B)")?;
+                writeln!(f, "  Control:       {ke}")?;
+                writeln!(f, "  Segment:       {nd}")?;
+                writeln!(f, "  Payload:       {}", hex::encode(md))?;
             }
         }
         Ok(())
@@ -516,7 +695,7 @@ pub fn serialize_config(decode_time: bool) {
         .expect("configuration can only happen once");
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct TimedMessage {
     /// The timestamp (in s) of the first time the message was received
     pub timestamp: f64,
@@ -526,8 +705,15 @@ pub struct TimedMessage {
     /// The decoded message
     #[serde(flatten)]
     pub message: Option<Message>,
+    /// The decoded Mode A/C reply (for 2-byte frames)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modeac: Option<ModeAC>,
     /// Information about when and where the message was received
+    #[serde(default)]
     pub metadata: Vec<SensorMetadata>,
+    /// Number of bits corrected in the frame based on the CRC (if any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corrected_bits: Option<u8>,
     /// Debugging information about decoding time (not serialized)
     #[serde(skip_serializing_if = "skip_serialize_decode_time")]
     pub decode_time: Option<f64>,
@@ -555,6 +741,9 @@ impl fmt::Display for TimedMessage {
         if let Some(msg) = &self.message {
             writeln!(f, "{}", msg)?;
         }
+        if let Some(modeac) = &self.modeac {
+            writeln!(f, "{}", modeac)?;
+        }
         write!(f, "")
     }
 }
@@ -564,18 +753,27 @@ impl fmt::Debug for TimedMessage {
         if let Some(msg) = &self.message {
             writeln!(f, "{:#}", msg)?;
         }
+        if let Some(modeac) = &self.modeac {
+            writeln!(f, "{:#}", modeac)?;
+        }
         write!(f, "")
     }
 }
 
 /// ICAO 24-bit address, commonly use to reference airframes, i.e. tail numbers
 /// of aircraft
-#[derive(PartialEq, Eq, PartialOrd, DekuRead, Hash, Copy, Clone, Ord)]
+#[derive(
+    PartialEq, Eq, PartialOrd, DekuRead, DekuWrite, Hash, Copy, Clone, Ord,
+)]
 #[deku(ctx = "crc: u32")]
 pub struct IcaoParity(
     // Ok it looks convoluted, actually the final bits are already read when
     // we compute the crc so we don't need to read this again
-    #[deku(bits = 24, map = "|_v: u32| -> Result<_, DekuError> { Ok(crc) }")]
+    #[deku(
+        bits = 24,
+        endian = "big",
+        map = "|_v: u32| -> Result<_, DekuError> { Ok(crc) }"
+    )]
     pub u32,
 );
 
@@ -603,6 +801,17 @@ impl Serialize for IcaoParity {
     }
 }
 
+impl<'de> Deserialize<'de> for IcaoParity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        <IcaoParity as std::str::FromStr>::from_str(&s)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl core::str::FromStr for IcaoParity {
     type Err = core::num::ParseIntError;
 
@@ -614,7 +823,18 @@ impl core::str::FromStr for IcaoParity {
 
 /// ICAO 24-bit address, commonly use to reference airframes, i.e. tail numbers
 /// of aircraft
-#[derive(PartialEq, Eq, PartialOrd, DekuRead, Hash, Copy, Clone, Ord)]
+#[derive(
+    PartialEq,
+    Eq,
+    PartialOrd,
+    DekuRead,
+    DekuWrite,
+    Hash,
+    Copy,
+    Clone,
+    Ord,
+    Default,
+)]
 pub struct ICAO(#[deku(bits = 24, endian = "big")] pub u32);
 
 impl fmt::Debug for ICAO {
@@ -668,8 +888,14 @@ impl From<IcaoParity> for ICAO {
 }
 
 /// 13 bit identity code (squawk code), a 4-octal digit identifier
-#[derive(PartialEq, DekuRead, Copy, Clone)]
-pub struct IdentityCode(#[deku(reader = "Self::read(deku::reader)")] pub u16);
+#[derive(PartialEq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct IdentityCode(
+    #[deku(
+        reader = "Self::read(deku::reader)",
+        writer = "Self::write(deku::writer, field_0)"
+    )]
+    pub u16,
+);
 
 impl IdentityCode {
     fn read<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
@@ -681,6 +907,14 @@ impl IdentityCode {
         )?;
         Ok(decode_id13(num))
     }
+
+    fn write<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+        writer: &mut Writer<W>,
+        squawk: &u16,
+    ) -> Result<(), DekuError> {
+        encode_id13(*squawk)
+            .to_writer(writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(13)))
+    }
 }
 
 impl fmt::Debug for IdentityCode {
@@ -707,9 +941,38 @@ impl Serialize for IdentityCode {
     }
 }
 
+impl<'de> Deserialize<'de> for IdentityCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        // The squawk code is serialized with its octal digits as hexadecimal
+        let s = String::deserialize(deserializer)?;
+        let num =
+            u16::from_str_radix(&s, 16).map_err(serde::de::Error::custom)?;
+        Ok(Self(num))
+    }
+}
+
 /// 13 bit encoded altitude
-#[derive(Debug, PartialEq, Eq, Serialize, DekuRead, Copy, Clone)]
-pub struct AC13Field(#[deku(reader = "Self::read(deku::reader)")] pub u16);
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+)]
+pub struct AC13Field(
+    #[deku(
+        reader = "Self::read(deku::reader)",
+        writer = "Self::write(deku::writer, field_0)"
+    )]
+    pub u16,
+);
 
 impl AC13Field {
     fn read<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
@@ -741,23 +1004,63 @@ impl AC13Field {
         } else {
             // 11 bit Gillham coded altitude
             if let Ok(n) = gray2alt(decode_id13(ac13field)) {
-                Ok((100 * n) as u16)
+                // Negative altitudes are not represented
+                Ok(u16::try_from(100 * n).unwrap_or(0))
             } else {
                 Ok(0)
             }
         }
     }
+
+    /// Altitudes are encoded with a 25 ft resolution (Q bit set) when
+    /// possible, and Gillham coded (100 ft resolution) above 50,175 ft.
+    ///
+    /// An altitude of 0 stands for an unavailable altitude, encoded as all
+    /// zeros: altitudes rounded to 0 ft are encoded the same way rather than
+    /// as a Q-coded 0 ft, which is decoded as unavailable as well.
+    fn write<W: deku::no_std_io::Write + deku::no_std_io::Seek>(
+        writer: &mut Writer<W>,
+        altitude: &u16,
+    ) -> Result<(), DekuError> {
+        let n = (*altitude as u32 + 1000 + 12) / 25;
+        let ac13field = if n <= 40 {
+            0
+        } else if n < 2048 {
+            let n = n as u16;
+            ((n & 0x07e0) << 2) | ((n & 0x0010) << 1) | 0x0010 | (n & 0x000f)
+        } else {
+            let gray = alt2gray((*altitude as i32 + 50) / 100)
+                .map_err(|e| DekuError::InvalidParam(e.into()))?;
+            encode_id13(gray)
+        };
+        ac13field
+            .to_writer(writer, (deku::ctx::Endian::Big, deku::ctx::BitSize(13)))
+    }
 }
 
 /// Transponder level and additional information (3.1.2.5.2.2.1)
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+    Default,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[allow(non_camel_case_types)]
 pub enum Capability {
     /// Level 1 transponder (surveillance only), and either airborne or on the ground
     #[serde(rename = "level1")]
+    #[default]
     AG_LEVEL1 = 0x00,
-    #[deku(id_pat = "0x01..=0x03")]
+    #[deku(
+        id_pat = "0x01..=0x03",
+        writer = "1u8.to_writer(deku::writer, deku::ctx::BitSize(3))"
+    )]
     AG_RESERVED,
     /// Level 2 or above transponder, on ground
     #[serde(rename = "ground")]
@@ -792,10 +1095,21 @@ impl fmt::Display for Capability {
 }
 
 /// Airborne or Ground and SPI (used in DF=4, 5, 20 or 21)
-#[derive(Debug, PartialEq, Serialize, DekuRead, Copy, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+    Default,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[serde(rename_all = "snake_case")]
 pub enum FlightStatus {
+    #[default]
     NoAlertNoSpiAirborne = 0b000,
     NoAlertNoSpiOnGround = 0b001,
     AlertNoSpiAirborne = 0b010,
@@ -825,19 +1139,146 @@ impl fmt::Display for FlightStatus {
 }
 
 /// The downlink request (used in DF=4, 5, 20 or 21)
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone, Default)]
 #[deku(id_type = "u8", bits = "5")]
 pub enum DownlinkRequest {
-    None = 0b00000,
-    RequestSendCommB = 0b00001,
-    CommBBroadcastMsg1 = 0b00100,
-    CommBBroadcastMsg2 = 0b00101,
+    #[default]
+    #[deku(id = "0b00000")]
+    None,
+    #[deku(id = "0b00001")]
+    RequestSendCommB,
+    #[deku(id = "0b00100")]
+    CommBBroadcastMsg1,
+    #[deku(id = "0b00101")]
+    CommBBroadcastMsg2,
+    /// Request to send a downlink ELM of `n - 15` segments (n in 16..=31)
+    #[deku(id_pat = "16..=31")]
+    DownlinkELM(#[deku(bits = "5")] u8),
+    /// A reserved value, kept as is
     #[deku(id_pat = "_")]
-    Unknown,
+    Unknown(#[deku(bits = "5")] u8),
+}
+
+/**
+ * The reply information (used in DF=0 and 16)
+ *
+ * It reports the ACAS capability of the aircraft when replying to an
+ * acquisition interrogation, or its maximum cruising true airspeed otherwise.
+ */
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    DekuRead,
+    DekuWrite,
+    Copy,
+    Clone,
+    Default,
+)]
+#[deku(id_type = "u8", bits = "4")]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyInformation {
+    /// No operating ACAS
+    #[default]
+    #[deku(id = "0")]
+    NoACAS,
+    /// ACAS with resolution capability inhibited
+    #[deku(id = "2")]
+    ResolutionInhibited,
+    /// ACAS with vertical-only resolution capability
+    #[deku(id = "3")]
+    VerticalOnly,
+    /// ACAS with vertical and horizontal resolution capability
+    #[deku(id = "4")]
+    VerticalAndHorizontal,
+    /// No maximum airspeed data available
+    #[deku(id = "8")]
+    #[serde(rename = "airspeed_unavailable")]
+    AirspeedUnavailable,
+    /// Maximum airspeed up to 75 kts
+    #[deku(id = "9")]
+    #[serde(rename = "airspeed_75")]
+    Airspeed75,
+    /// Maximum airspeed between 75 and 150 kts
+    #[deku(id = "10")]
+    #[serde(rename = "airspeed_150")]
+    Airspeed150,
+    /// Maximum airspeed between 150 and 300 kts
+    #[deku(id = "11")]
+    #[serde(rename = "airspeed_300")]
+    Airspeed300,
+    /// Maximum airspeed between 300 and 600 kts
+    #[deku(id = "12")]
+    #[serde(rename = "airspeed_600")]
+    Airspeed600,
+    /// Maximum airspeed between 600 and 1200 kts
+    #[deku(id = "13")]
+    #[serde(rename = "airspeed_1200")]
+    Airspeed1200,
+    /// Maximum airspeed above 1200 kts
+    #[deku(id = "14")]
+    #[serde(rename = "airspeed_above_1200")]
+    AirspeedAbove1200,
+    /// Not assigned (1, 5 to 7, and 15)
+    #[deku(id_pat = "_")]
+    Reserved(#[deku(bits = "4")] u8),
+}
+
+impl ReplyInformation {
+    /// Read the field, which is not aligned on a byte: the derived reader
+    /// cannot read the identifier of reserved values again in this case.
+    fn read<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+        reader: &mut Reader<R>,
+    ) -> Result<Self, DekuError> {
+        let ri = u8::from_reader_with_ctx(reader, deku::ctx::BitSize(4))?;
+        match ri {
+            1 | 5..=7 | 15 => Ok(Self::Reserved(ri)),
+            ri => Self::from_bytes((&[ri << 4], 0)).map(|(_, ri)| ri),
+        }
+    }
+
+    /// The upper bound of the maximum airspeed range, in kts
+    pub fn max_airspeed(&self) -> Option<u16> {
+        match self {
+            Self::Airspeed75 => Some(75),
+            Self::Airspeed150 => Some(150),
+            Self::Airspeed300 => Some(300),
+            Self::Airspeed600 => Some(600),
+            Self::Airspeed1200 => Some(1200),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ReplyInformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoACAS => write!(f, "no operating ACAS"),
+            Self::ResolutionInhibited => {
+                write!(f, "resolution capability inhibited")
+            }
+            Self::VerticalOnly => write!(f, "vertical-only resolution"),
+            Self::VerticalAndHorizontal => {
+                write!(f, "vertical and horizontal resolution")
+            }
+            Self::AirspeedUnavailable => write!(f, "no maximum airspeed"),
+            Self::AirspeedAbove1200 => {
+                write!(f, "maximum airspeed above 1200 kts")
+            }
+            Self::Reserved(ri) => write!(f, "reserved ({ri})"),
+            other => write!(
+                f,
+                "maximum airspeed up to {} kts",
+                other.max_airspeed().unwrap_or_default()
+            ),
+        }
+    }
 }
 
 /// The utility message (used in DF=4, 5, 20 or 21)
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone, Default)]
 pub struct UtilityMessage {
     #[deku(bits = "4")]
     pub iis: u8,
@@ -845,9 +1286,10 @@ pub struct UtilityMessage {
 }
 
 /// The utility message type (used in DF=4, 5, 20 or 21)
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone, Default)]
 #[deku(id_type = "u8", bits = "2")]
 pub enum UtilityMessageType {
+    #[default]
     NoInformation = 0b00,
     CommB = 0b01,
     CommC = 0b10,
@@ -855,7 +1297,9 @@ pub enum UtilityMessageType {
 }
 
 /// The control field in TIS-B messages (DF=18)
-#[derive(Debug, PartialEq, Serialize, DekuRead, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
 pub struct ControlField {
     #[serde(rename = "tisb")]
     pub field_type: ControlFieldType,
@@ -873,8 +1317,67 @@ impl fmt::Display for ControlField {
     }
 }
 
+/// The content of military extended squitters (DF=19), based on the
+/// application field (AF)
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+#[deku(ctx = "af: u8", id = "af")]
+#[serde(untagged)]
+pub enum MilitaryApplication {
+    /// AF=0: Extended squitter, in the same format as DF=17
+    #[deku(id = "0")]
+    ExtendedSquitter(MilitaryExtendedSquitter),
+
+    /// AF=1..=7: Reserved for military applications (104 bits)
+    #[deku(id_pat = "_")]
+    Reserved {
+        #[deku(count = "13")]
+        #[serde(serialize_with = "as_hex", deserialize_with = "from_hex")]
+        payload: Vec<u8>,
+    },
+}
+
+/// The extended squitter message in military messages (DF=19, AF=0)
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, DekuRead, DekuWrite, Clone,
+)]
+pub struct MilitaryExtendedSquitter {
+    /// AA: Address, Announced
+    #[serde(rename = "icao24")]
+    pub aa: ICAO,
+    /// ME: message, extended squitter
+    #[serde(flatten)]
+    pub me: ME,
+    /// Parity/interrogator identifier
+    #[serde(skip)]
+    pub pi: ICAO,
+}
+
+impl fmt::Display for MilitaryApplication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExtendedSquitter(es) => {
+                writeln!(f, "  Address:       {}", &es.aa)?;
+                write!(f, "{}", &es.me)
+            }
+            Self::Reserved { payload } => {
+                writeln!(f, "  Payload:       {}", hex::encode(payload))
+            }
+        }
+    }
+}
+
 /// The control field type in TIS-B messages (DF=18)
-#[derive(Debug, PartialEq, serde::Serialize, DekuRead, Clone)]
+#[derive(
+    Debug,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+    DekuRead,
+    DekuWrite,
+    Clone,
+)]
 #[deku(id_type = "u8", bits = "3")]
 #[allow(non_camel_case_types)]
 pub enum ControlFieldType {
@@ -928,13 +1431,27 @@ impl fmt::Display for ControlFieldType {
 }
 
 /// Uplink / Downlink (DF=24)
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone, Default)]
 #[deku(id_type = "u8", bits = "1")]
 pub enum KE {
+    #[default]
     DownlinkELMTx = 0,
     UplinkELMAck = 1,
 }
 
+impl fmt::Display for KE {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::DownlinkELMTx => "downlink ELM transmission",
+                Self::UplinkELMAck => "uplink ELM acknowledgement",
+            }
+        )
+    }
+}
+
 /// Decode a [Gillham code](https://en.wikipedia.org/wiki/Gillham_code)
 /// 
 /// In the squawk (identity) field bits are interleaved as follows in
@@ -968,8 +1485,31 @@ pub fn decode_id13(id13_field: u16) -> u16 {
     hex_gillham
 }
 
+/// Encode a squawk (identity) code into the 13 bits of the identity field.
+///
+/// This is the inverse function of [`decode_id13`].
+#[rustfmt::skip]
+pub fn encode_id13(hex_gillham: u16) -> u16 {
+    let mut id13_field: u16 = 0;
+
+    if hex_gillham & 0x0010 != 0 { id13_field |= 0x1000; } // C1
+    if hex_gillham & 0x1000 != 0 { id13_field |= 0x0800; } // A1
+    if hex_gillham & 0x0020 != 0 { id13_field |= 0x0400; } // C2
+    if hex_gillham & 0x2000 != 0 { id13_field |= 0x0200; } // A2
+    if hex_gillham & 0x0040 != 0 { id13_field |= 0x0100; } // C4
+    if hex_gillham & 0x4000 != 0 { id13_field |= 0x0080; } // A4
+    if hex_gillham & 0x0100 != 0 { id13_field |= 0x0020; } // B1
+    if hex_gillham & 0x0001 != 0 { id13_field |= 0x0010; } // D1
+    if hex_gillham & 0x0200 != 0 { id13_field |= 0x0008; } // B2
+    if hex_gillham & 0x0002 != 0 { id13_field |= 0x0004; } // D2
+    if hex_gillham & 0x0400 != 0 { id13_field |= 0x0002; } // B4
+    if hex_gillham & 0x0004 != 0 { id13_field |= 0x0001; } // D4
+
+    id13_field
+}
+
 /// Convert a [Gillham code](https://en.wikipedia.org/wiki/Gillham_code) to
-/// an altitude in feet.
+/// an altitude in hundreds of feet, from -1200 ft.
 #[rustfmt::skip]
 pub fn gray2alt(gray: u16) -> Result<i32, &'static str> {
     let mut five_hundreds: u32 = 0;
@@ -1005,18 +1545,60 @@ pub fn gray2alt(gray: u16) -> Result<i32, &'static str> {
         one_hundreds = 6 - one_hundreds;
     }
 
+    // Altitudes start at -1200 ft
     let n = (five_hundreds * 5) + one_hundreds;
-    if n >= 13 {
+    if n >= 1 {
         Ok(n as i32 - 13)
     } else {
         Err("Invalid altitude")
     }
 }
 
+/// Convert an altitude (in hundreds of feet) to a
+/// [Gillham code](https://en.wikipedia.org/wiki/Gillham_code).
+///
+/// This is the inverse function of [`gray2alt`].
+#[rustfmt::skip]
+pub fn alt2gray(alt: i32) -> Result<u16, &'static str> {
+    let n = alt + 13;
+    if !(1..=(255 * 5 + 5)).contains(&n) {
+        return Err("Invalid altitude");
+    }
+
+    let five_hundreds = ((n - 1) / 5) as u16;
+    let mut one_hundreds = (n - 5 * ((n - 1) / 5)) as u16;
+    if five_hundreds & 1 != 0 {
+        one_hundreds = 6 - one_hundreds;
+    }
+    // Restore 7s in OneHundreds (Make 5->7)
+    if one_hundreds == 5 { one_hundreds = 7; }
+
+    let five_hundreds = five_hundreds ^ (five_hundreds >> 1);
+    let one_hundreds = one_hundreds ^ (one_hundreds >> 1);
+
+    let mut gray: u16 = 0;
+
+    if one_hundreds & 0x04 != 0 { gray |= 0x0010; } // C1
+    if one_hundreds & 0x02 != 0 { gray |= 0x0020; } // C2
+    if one_hundreds & 0x01 != 0 { gray |= 0x0040; } // C4
+
+    if five_hundreds & 0x80 != 0 { gray |= 0x0002; } // D2
+    if five_hundreds & 0x40 != 0 { gray |= 0x0004; } // D4
+    if five_hundreds & 0x20 != 0 { gray |= 0x1000; } // A1
+    if five_hundreds & 0x10 != 0 { gray |= 0x2000; } // A2
+    if five_hundreds & 0x08 != 0 { gray |= 0x4000; } // A4
+    if five_hundreds & 0x04 != 0 { gray |= 0x0100; } // B1
+    if five_hundreds & 0x02 != 0 { gray |= 0x0200; } // B2
+    if five_hundreds & 0x01 != 0 { gray |= 0x0400; } // B4
+
+    Ok(gray)
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use bds::bds08::{self, AircraftIdentification};
     use hexlit::hex;
 
     #[test]
@@ -1044,4 +1626,269 @@ mod tests {
             unreachable!()
         }
     }
+
+    #[test]
+    fn test_fix_crc() {
+        let mut bytes = hex!("8D406B902015A678D4D220AA4BDA");
+        bytes[6] ^= 0x04;
+        assert!(Message::from_bytes((&bytes, 0)).is_err());
+        assert!(Message::from_bytes_with_fix(&mut bytes.clone(), 0).is_err());
+
+        let (msg, corrected) =
+            Message::from_bytes_with_fix(&mut bytes, 1).unwrap();
+        assert_eq!(corrected, 1);
+        assert_eq!(bytes, hex!("8D406B902015A678D4D220AA4BDA"));
+        match msg.df {
+            DF::ExtendedSquitterADSB(adsb) => {
+                assert_eq!(format!("{}", adsb.icao24), "406b90");
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_fix_crc_df11() {
+        // An all-call reply with a null interrogator code
+        let (_, mut msg) =
+            Message::from_bytes((&hex!("5d4ca8d9a8a7d3"), 0)).unwrap();
+        msg.crc = 0;
+        let frame = msg.to_bytes().unwrap();
+
+        let mut bytes = frame.clone();
+        bytes[2] ^= 0x10;
+        let (_, corrected) =
+            Message::from_bytes_with_fix(&mut bytes, 2).unwrap();
+        assert_eq!(corrected, 1);
+        assert_eq!(bytes, frame);
+
+        // Two-bit errors are never corrected in DF11 messages
+        let mut bytes = frame.clone();
+        bytes[2] ^= 0x10;
+        bytes[3] ^= 0x01;
+        let (_, corrected) =
+            Message::from_bytes_with_fix(&mut bytes, 2).unwrap();
+        assert_eq!(corrected, 0);
+        assert_ne!(bytes, frame);
+    }
+
+    #[test]
+    fn test_deserialize_roundtrip() {
+        let frames = [
+            hex!("8d406b902015a678d4d220aa4bda").to_vec(), // BDS 0,8
+            hex!("8d40058b58c901375147efd09357").to_vec(), // BDS 0,5
+            hex!("8c4841753a9a153237aef0f275be").to_vec(), // BDS 0,6
+            hex!("8d485020994409940838175b284f").to_vec(), // BDS 0,9
+            hex!("8da05f219b06b6af189400cbc33f").to_vec(), // BDS 0,9 (airspeed)
+            hex!("a0001910cc300030aa0000eae004").to_vec(), // DF20 BDS 4,0
+            hex!("a8001ebcfffb23286004a73f6a5b").to_vec(), // DF21 BDS 5,0
+            hex!("02e197b00179c3").to_vec(),               // DF0
+            hex!("20001838ca3804").to_vec(),               // DF4
+            hex!("28001a1a0de0a1").to_vec(),               // DF5
+            hex!("5d4ca8d9a8a7d3").to_vec(),               // DF11
+            hex!("8081983830e00004f1b74084b2b8").to_vec(), // DF16
+            hex!("29400000000000").to_vec(),               // DF5, reserved DR
+            hex!("8d4bb463003d10a1b2c3d44b0269").to_vec(), // TC 0
+            hex!("8d4bb463c81234567890ab57882b").to_vec(), // TC 25
+        ];
+        for frame in frames {
+            let (_, msg) = Message::from_bytes((&frame, 0)).unwrap();
+            let json = serde_json::to_string(&msg).unwrap();
+            let back: Message = serde_json::from_str(&json).unwrap();
+            assert_eq!(json, serde_json::to_string(&back).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        let frames = [
+            hex!("8d406b902015a678d4d220aa4bda").to_vec(), // BDS 0,8
+            hex!("8d40058b58c901375147efd09357").to_vec(), // BDS 0,5
+            hex!("8c4841753a9a153237aef0f275be").to_vec(), // BDS 0,6
+            hex!("8d485020994409940838175b284f").to_vec(), // BDS 0,9
+            hex!("8da05f219b06b6af189400cbc33f").to_vec(), // BDS 0,9 (airspeed)
+            hex!("8da05629ea21485cbf3f8cadaeeb").to_vec(), // BDS 6,2
+            hex!("a0001910cc300030aa0000eae004").to_vec(), // DF20 BDS 4,0
+            hex!("a8001ebcfffb23286004a73f6a5b").to_vec(), // DF21 BDS 5,0
+            hex!("02e197b00179c3").to_vec(),               // DF0
+            hex!("20001838ca3804").to_vec(),               // DF4
+            hex!("28001a1a0de0a1").to_vec(),               // DF5
+            hex!("5d4ca8d9a8a7d3").to_vec(),               // DF11
+            hex!("8081983830e00004f1b74084b2b8").to_vec(), // DF16
+            hex!("29400000000000").to_vec(),               // DF5, reserved DR
+            hex!("8d4bb463003d10a1b2c3d44b0269").to_vec(), // TC 0
+            hex!("8d4bb463c81234567890ab57882b").to_vec(), // TC 25
+        ];
+        for frame in frames {
+            let (_, msg) = Message::from_bytes((&frame, 0)).unwrap();
+            assert_eq!(msg.to_bytes().unwrap(), frame);
+        }
+    }
+
+    #[test]
+    fn test_encode_callsign() {
+        let msg = Message {
+            crc: 0,
+            df: DF::ExtendedSquitterADSB(ADSB {
+                capability: Capability::AG_AIRBORNE,
+                icao24: ICAO(0x406b90),
+                message: ME::BDS08(AircraftIdentification {
+                    id: 4,
+                    tc: bds08::Typecode::A,
+                    ca: 0,
+                    wake_vortex: bds08::WakeVortex::NoInformation,
+                    callsign: "EZY85MH".to_string(),
+                }),
+                parity: ICAO(0),
+            }),
+        };
+        let bytes: Vec<u8> = msg.try_into().unwrap();
+        assert_eq!(bytes, hex!("8d406b902015a678d4d220aa4bda"));
+    }
+
+    #[test]
+    fn test_long_air_air() {
+        let bytes = hex!("8081983830e00004f1b74084b2b8");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        match &msg.df {
+            DF::LongAirAirSurveillance { ri, ac, mv, ap, .. } => {
+                assert_eq!(*ri, ReplyInformation::VerticalOnly);
+                assert_eq!(ac.0, 38000);
+                assert_eq!(format!("{ap}"), "3c4b26");
+                let bds30 = mv.bds30.as_ref().unwrap();
+                assert!(bds30.ra.issued_ra);
+                assert_eq!(bds30.ra.downward_sense, Some(true));
+            }
+            _ => unreachable!(),
+        }
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["ri"], "vertical_only");
+        assert_eq!(json["bds30"]["threat_identity"], "3c6dd0");
+    }
+
+    #[test]
+    fn test_reserved_reply_information() {
+        // DF0 with RI=5 (not assigned)
+        let bytes = hex!("0002d7186813a6");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        let DF::ShortAirAirSurveillance { ri, ac, .. } = &msg.df else {
+            unreachable!()
+        };
+        assert_eq!(*ri, ReplyInformation::Reserved(5));
+        assert_eq!(ac.0, 36000);
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_alt2gray() {
+        for alt in -12..=1267 {
+            let gray = alt2gray(alt).unwrap();
+            assert_eq!(gray2alt(gray), Ok(alt));
+        }
+        assert!(alt2gray(-13).is_err());
+        assert!(alt2gray(1268).is_err());
+    }
+
+    #[test]
+    fn test_ac13_encode() {
+        let (_, mut msg) =
+            Message::from_bytes((&hex!("20001838ca3804"), 0)).unwrap();
+        for (altitude, expected) in
+            [(0, 0), (10, 0), (25, 25), (38000, 38000), (50200, 50200)]
+        {
+            let DF::SurveillanceAltitudeReply { ac, .. } = &mut msg.df else {
+                unreachable!()
+            };
+            *ac = AC13Field(altitude);
+            let bytes = msg.to_bytes().unwrap();
+            if altitude < 25 {
+                // All zeros in the AC field: altitude unavailable
+                assert_eq!(bytes[2] & 0x1f, 0);
+                assert_eq!(bytes[3], 0);
+            }
+            let (_, back) = Message::from_bytes((&bytes, 0)).unwrap();
+            let DF::SurveillanceAltitudeReply { ac, .. } = back.df else {
+                unreachable!()
+            };
+            assert_eq!(ac.0, expected);
+        }
+    }
+
+    #[test]
+    fn test_military() {
+        let bytes = hex!("983c4b2658c901375147ef514490");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        match &msg.df {
+            DF::ExtendedSquitterMilitary {
+                af: 0,
+                payload: MilitaryApplication::ExtendedSquitter(es),
+            } => {
+                assert_eq!(format!("{}", es.aa), "3c4b26");
+                assert!(matches!(es.me, ME::BDS05(_)));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+
+        let bytes = hex!("9d0102030405060708090a0b0c0d");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(
+            format!("{msg}"),
+            r#" DF19. Extended Squitter Military Application
+  Application:   5
+  Payload:       0102030405060708090a0b0c0d
+"#
+        );
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"df":"19","af":5,"payload":"0102030405060708090a0b0c0d"}"#
+        );
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_comm_d() {
+        let bytes = hex!("c30102030405060708090a752f3c");
+        let (_, msg) = Message::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(
+            format!("{msg}"),
+            r#" DF24. Comm-D Extended Length Message
+  ICAO Address:  3c4b26 (Mode S / ADS-B)
+  Control:       downlink ELM transmission
+  Segment:       3
+  Payload:       0102030405060708090a
+"#
+        );
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"df":"24","nd":3,"md":"0102030405060708090a","icao24":"3c4b26"}"#
+        );
+        assert_eq!(msg.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_deserialize_timed_message() {
+        let json = r#"{"timestamp":1735216024.5,"frame":"8d406b902015a678d4d220aa4bda","df":"17","icao24":"406b90","bds":"08","id":4,"wake_vortex":"n/a","callsign":"EZY85MH","metadata":[]}"#;
+        let tmsg: TimedMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(tmsg.frame, hex!("8d406b902015a678d4d220aa4bda"));
+        let msg = tmsg.message.unwrap();
+        match &msg.df {
+            DF::ExtendedSquitterADSB(adsb) => {
+                assert_eq!(format!("{}", adsb.icao24), "406b90");
+                match &adsb.message {
+                    ME::BDS08(bds08) => {
+                        assert_eq!(bds08.callsign, "EZY85MH");
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+        let (_, decoded) =
+            Message::from_bytes((&hex!("8d406b902015a678d4d220aa4bda"), 0))
+                .unwrap();
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            serde_json::to_string(&decoded).unwrap()
+        );
+    }
 }
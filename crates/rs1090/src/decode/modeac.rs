@@ -0,0 +1,210 @@
+/**
+* Mode A/C replies are 2-byte frames (e.g. Beast type "1" frames), carrying
+* either the identity code (Mode A, squawk) or the barometric altitude in
+* Gillham code (Mode C).
+*
+* The interrogation mode is not known from the reply only, so both
+* interpretations are provided: the squawk is always decoded, the altitude
+* only when the code is a valid Gillham code.
+*
+* The two bytes follow the layout of the identity code after
+* [`decode_id13`](super::decode_id13), i.e. each hexadecimal digit represents
+* one octal digit of the squawk, with the SPI bit at position 0x0080.
+*/
+use super::adsb::ME;
+use super::{gray2alt, IdentityCode, TimedMessage, DF, ICAO};
+use deku::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A Mode A/C reply
+#[derive(Debug, PartialEq, Serialize, Deserialize, Copy, Clone)]
+pub struct ModeAC {
+    /// The identity code (squawk), if interpreted as a Mode A reply
+    pub squawk: IdentityCode,
+    /// The barometric altitude in ft, if interpreted as a Mode C reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<i32>,
+    /// Special position identification (IDENT) pulse
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub spi: bool,
+    /// The address of the only Mode S aircraft recently seen with the same
+    /// squawk (see [`ModeACCorrelator`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icao24: Option<ICAO>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl ModeAC {
+    pub fn new(code: u16) -> Self {
+        let spi = code & 0x0080 != 0;
+        // The SPI bit is not part of the Gillham code
+        let altitude = gray2alt(code & !0x0080).ok().map(|alt| 100 * alt);
+        Self {
+            squawk: IdentityCode(code & 0x7777),
+            altitude,
+            spi,
+            icao24: None,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for ModeAC {
+    type Error = DekuError;
+
+    fn try_from(input: &[u8]) -> Result<Self, Self::Error> {
+        match input {
+            [msb, lsb] => Ok(Self::new(u16::from_be_bytes([*msb, *lsb]))),
+            _ => Err(DekuError::Parse(
+                format!("Invalid Mode A/C frame length: {}", input.len())
+                    .into(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ModeAC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, " Mode A/C reply")?;
+        writeln!(f, "  Squawk:        {}", &self.squawk)?;
+        if let Some(altitude) = &self.altitude {
+            writeln!(f, "  Altitude:      {altitude} ft barometric")?;
+        }
+        if self.spi {
+            writeln!(f, "  IDENT")?;
+        }
+        if let Some(icao24) = &self.icao24 {
+            writeln!(f, "  ICAO Address:  {icao24} (Mode S / ADS-B)")?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Link Mode A/C replies to the Mode S aircraft broadcasting the same squawk.
+ *
+ * Squawks are collected from DF=5 and DF=21 replies, and from BDS 6,1 ADS-B
+ * messages. A Mode A/C reply is linked to an aircraft only if no other
+ * aircraft has been seen with the same squawk within the timeout, so
+ * conspicuity codes (e.g. 7000 or 2000) are rarely linked.
+ */
+#[derive(Debug)]
+pub struct ModeACCorrelator {
+    /// The maximum duration (in seconds) since the squawk was last seen
+    pub timeout: f64,
+    squawks: BTreeMap<u16, BTreeMap<ICAO, f64>>,
+}
+
+impl ModeACCorrelator {
+    pub fn new(timeout: f64) -> Self {
+        Self {
+            timeout,
+            squawks: BTreeMap::new(),
+        }
+    }
+
+    /// Record the squawk of Mode S messages, and fill the `icao24` field of
+    /// Mode A/C replies when the squawk identifies a single aircraft.
+    pub fn push(&mut self, msg: &mut TimedMessage) {
+        let timestamp = msg.timestamp;
+        if let Some(modeac) = &mut msg.modeac {
+            modeac.icao24 = self.lookup(modeac.squawk, timestamp);
+        }
+        if let Some(message) = &msg.message {
+            let squawk = match &message.df {
+                DF::SurveillanceIdentityReply { id, ap, .. }
+                | DF::CommBIdentityReply { id, ap, .. } => {
+                    Some((*id, (*ap).into()))
+                }
+                DF::ExtendedSquitterADSB(adsb) => match &adsb.message {
+                    ME::BDS61(bds61) => {
+                        bds61.squawk().map(|sq| (sq, adsb.icao24))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            if let Some((squawk, icao24)) = squawk {
+                self.squawks
+                    .entry(squawk.0)
+                    .or_default()
+                    .insert(icao24, timestamp);
+            }
+        }
+    }
+
+    fn lookup(&mut self, squawk: IdentityCode, timestamp: f64) -> Option<ICAO> {
+        let aircraft = self.squawks.get_mut(&squawk.0)?;
+        aircraft.retain(|_, last_seen| timestamp - *last_seen <= self.timeout);
+        match aircraft.len() {
+            1 => aircraft.keys().next().copied(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{alt2gray, Message};
+    use hexlit::hex;
+
+    #[test]
+    fn test_modeac() {
+        let modeac = ModeAC::try_from(hex!("7700").as_slice()).unwrap();
+        assert_eq!(format!("{}", modeac.squawk), "7700");
+        assert_eq!(modeac.altitude, None);
+
+        let code = alt2gray(43).unwrap();
+        let modeac = ModeAC::try_from(code.to_be_bytes().as_slice()).unwrap();
+        assert_eq!(modeac.altitude, Some(4300));
+
+        let modeac = ModeAC::new(code | 0x0080);
+        assert!(modeac.spi);
+        assert_eq!(modeac.altitude, Some(4300));
+
+        assert!(ModeAC::try_from(hex!("131000").as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_correlate() {
+        let frame = hex!("28001a1a0de0a1").to_vec();
+        let message = Message::try_from(frame.as_slice()).ok();
+        let squawk = match &message.as_ref().unwrap().df {
+            DF::SurveillanceIdentityReply { id, .. } => *id,
+            _ => unreachable!(),
+        };
+        let mut tmsg = TimedMessage {
+            timestamp: 0.,
+            frame,
+            message,
+            modeac: None,
+            metadata: vec![],
+            decode_time: None,
+            corrected_bits: None,
+        };
+        let mut correlator = ModeACCorrelator::new(10.);
+        correlator.push(&mut tmsg);
+
+        let mut modeac = TimedMessage {
+            timestamp: 5.,
+            frame: squawk.0.to_be_bytes().to_vec(),
+            message: None,
+            modeac: Some(ModeAC::new(squawk.0)),
+            metadata: vec![],
+            decode_time: None,
+            corrected_bits: None,
+        };
+        correlator.push(&mut modeac);
+        let icao24 = modeac.modeac.and_then(|m| m.icao24);
+        assert_eq!(icao24, Some(ICAO(tmsg.message.unwrap().crc)));
+
+        modeac.timestamp = 20.;
+        correlator.push(&mut modeac);
+        assert_eq!(modeac.modeac.and_then(|m| m.icao24), None);
+    }
+}
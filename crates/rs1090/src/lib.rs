@@ -31,6 +31,10 @@ pub mod prelude {
     #[cfg(not(target_arch = "wasm32"))]
     pub use crate::source::beast;
 
+    /// Parsing of AVR (text) feeds
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use crate::source::avr;
+
     #[cfg(feature = "rtlsdr")]
     pub use crate::source::rtlsdr;
 
@@ -0,0 +1,201 @@
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+
+use std::io;
+
+use super::beast::{keep_connected, sensor_metadata, Session};
+use super::beast::{Backoff, ConnectionState};
+use crate::decode::modeac::ModeAC;
+use crate::prelude::*;
+
+/**
+ * Parse a line of an AVR feed, as produced by rtl_adsb, dump1090
+ * (`--net-ro-port`, usually on port 30002) or modesdeco.
+ *
+ *  - `*<frame>;` : a frame without metadata
+ *  - `@<timestamp><frame>;` : a 12 hex digit (48-bit) MLAT timestamp before
+ *    the frame
+ *  - `%<timestamp><signal><frame>;` : a 12 hex digit MLAT timestamp and a
+ *    2 hex digit signal level before the frame (the `<` prefix is also
+ *    accepted for the same layout)
+ *
+ * Frames are 2 bytes long (Mode A/C), 7 bytes or 14 bytes (Mode S). The
+ * MLAT timestamp is interpreted as in Beast feeds.
+ *
+ * Lines which do not follow any of these formats are ignored (None).
+ */
+pub fn parse_line(
+    line: &str,
+    serial: u64,
+    name: Option<String>,
+) -> Option<TimedMessage> {
+    let line = line.trim();
+    let prefix = line.chars().next()?;
+    let content = line.get(1..)?;
+    // The trailing semicolon is optional in some implementations
+    let content = content.strip_suffix(';').unwrap_or(content);
+    let (timestamp, signal, frame) = match prefix {
+        '*' => (None, None, content),
+        '@' => {
+            let (timestamp, frame) = split_hex(content, 12)?;
+            (Some(timestamp), None, frame)
+        }
+        '%' | '<' => {
+            let (timestamp, rest) = split_hex(content, 12)?;
+            let (signal, frame) = split_hex(rest, 2)?;
+            (Some(timestamp), Some(signal as u8), frame)
+        }
+        _ => return None,
+    };
+    let frame = hex::decode(frame).ok()?;
+    if ![2, 7, 14].contains(&frame.len()) {
+        return None;
+    }
+
+    let metadata = sensor_metadata(timestamp, signal, serial, name);
+    let modeac = match frame.len() {
+        2 => ModeAC::try_from(frame.as_slice()).ok(),
+        _ => None,
+    };
+
+    Some(TimedMessage {
+        timestamp: metadata.system_timestamp,
+        frame,
+        message: None,
+        modeac,
+        metadata: vec![metadata],
+        decode_time: None,
+        corrected_bits: None,
+    })
+}
+
+/// Split the first `digits` hexadecimal digits from a string, and parse them
+fn split_hex(content: &str, digits: usize) -> Option<(u64, &str)> {
+    let value = content.get(..digits)?;
+    let value = u64::from_str_radix(value, 16).ok()?;
+    Some((value, &content[digits..]))
+}
+
+/**
+ * Receive messages from an AVR source over TCP and send them to `tx`.
+ *
+ * As for Beast sources, the receiver reconnects when the connection is lost,
+ * following the `backoff` policy, and reports the changes of the connection
+ * state to `state`.
+ *
+ * The function returns when `tx` is closed, or with an error after the last
+ * failed attempt.
+ */
+pub async fn receiver(
+    address: String,
+    tx: mpsc::Sender<TimedMessage>,
+    serial: u64,
+    name: Option<String>,
+    backoff: Backoff,
+    state: Option<mpsc::Sender<(u64, ConnectionState)>>,
+) -> io::Result<()> {
+    let connect = || {
+        let address = &address;
+        async move {
+            let stream = TcpStream::connect(address).await?;
+            info!("Connected to AVR stream: {}", address);
+            Ok(stream)
+        }
+    };
+    let session = |stream| {
+        let (tx, name) = (&tx, &name);
+        async move {
+            let mut active = false;
+            let mut lines = BufReader::new(stream).lines();
+            loop {
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break, // Connection closed by peer
+                    Err(e) => {
+                        error!("Error reading from socket: {}", e);
+                        break;
+                    }
+                };
+                active = true;
+                let Some(tmsg) = parse_line(&line, serial, name.clone()) else {
+                    debug!("Invalid AVR line: {:?}", line);
+                    continue;
+                };
+                info!("Received {}", tmsg);
+                if tx.send(tmsg).await.is_err() {
+                    return Session::Done;
+                }
+            }
+            Session::Lost { active }
+        }
+    };
+    let display = format!("avr://{address}");
+    keep_connected(display, backoff, serial, state, connect, session).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_line() {
+        let msg =
+            parse_line("*8D4840D6202CC371C32CE0576098;", 0, None).unwrap();
+        assert_eq!(
+            msg.frame,
+            hex::decode("8D4840D6202CC371C32CE0576098").unwrap()
+        );
+        assert_eq!(msg.metadata[0].nanoseconds, None);
+        assert_eq!(msg.metadata[0].rssi, None);
+
+        let line = "@00000F4240008D4840D6202CC371C32CE0576098;\r";
+        let msg = parse_line(line, 0, None).unwrap();
+        assert_eq!(msg.frame.len(), 14);
+        assert_eq!(msg.metadata[0].nanoseconds, Some(0xF424000));
+
+        let line = "%0000000F4240808D4840D6202CC371C32CE0576098;";
+        let msg = parse_line(line, 0, None).unwrap();
+        assert_eq!(msg.metadata[0].nanoseconds, Some(0xF4240));
+        let rssi = msg.metadata[0].rssi.unwrap();
+        assert!((rssi - -5.99).abs() < 0.01);
+
+        // Mode A/C
+        let msg = parse_line("*0A40;", 0, None).unwrap();
+        assert!(msg.modeac.is_some());
+
+        // Invalid lines
+        assert!(parse_line("", 0, None).is_none());
+        assert!(parse_line("*8D4840D6202CC371C32CE05760;", 0, None).is_none());
+        assert!(parse_line("*XYZ;", 0, None).is_none());
+        assert!(parse_line("#8D4840D6202CC371C32CE0576098;", 0, None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (tx, mut rx) = mpsc::channel(10);
+        let backoff = Backoff {
+            initial: 0.01,
+            max: 0.01,
+            max_attempts: Some(1),
+        };
+        let handle =
+            tokio::spawn(receiver(address, tx, 0, None, backoff, None));
+
+        let (mut socket, _) = listener.accept().await.unwrap();
+        socket
+            .write_all(b"garbage\n*8D4840D6202CC371C32CE0576098;\n")
+            .await
+            .unwrap();
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.frame.len(), 14);
+        drop(socket);
+        drop(listener);
+        assert!(handle.await.unwrap().is_err());
+    }
+}
@@ -2,19 +2,24 @@ use async_stream::stream;
 use futures::stream::SplitStream;
 use futures_util::pin_mut;
 use futures_util::stream::{Stream, StreamExt};
-use tokio::io::AsyncReadExt;
-use tokio::net::{TcpStream, UdpSocket};
-use tokio::sync::mpsc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{sleep, Duration, Instant};
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::{
     tungstenite::protocol::Message, MaybeTlsStream, WebSocketStream,
 };
 use tracing::info;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::io;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{fmt, io};
 
+use crate::decode::modeac::ModeAC;
 use crate::decode::time::{now_in_ns, since_today_to_nanos};
 use crate::prelude::*;
 
@@ -82,8 +87,14 @@ pub async fn next_msg(mut stream: DataSource) -> impl Stream<Item = Vec<u8>> {
                         buffer[..len].copy_from_slice(&data[..len]);
                         len
                     }
-                    _ => {
-                        error!("Error reading from websocket");
+                    Some(Ok(Message::Close(_))) | None => {
+                        info!("Websocket closed by peer");
+                        break;
+                    }
+                    // Ping, pong and text messages
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        error!("Error reading from websocket: {}", e);
                         break;
                     }
                 }
@@ -134,9 +145,7 @@ pub async fn next_msg(mut stream: DataSource) -> impl Stream<Item = Vec<u8>> {
                     }
 
                     let msg = data.drain(..msg_size).collect::<Vec<u8>>();
-                    if msg_type != 0x34 {
-                        yield msg
-                    }
+                    yield msg
                 } else {
                     // Probably corrupted message
                     data = data.split_off(1);
@@ -149,53 +158,476 @@ pub async fn next_msg(mut stream: DataSource) -> impl Stream<Item = Vec<u8>> {
     }
 }
 
-pub async fn receiver(
-    address: BeastSource,
-    tx: mpsc::Sender<TimedMessage>,
-    serial: u64,
-    name: Option<String>,
-) -> io::Result<()> {
-    let msg_stream = match address {
-        BeastSource::Tcp(address) => match TcpStream::connect(&address).await {
+/// The state of the connection to a Beast source, as reported by [`receiver`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// Connected (or bound, for UDP sources)
+    Connected,
+    /// Disconnected, next attempt after `delay` seconds
+    Retrying { attempt: u32, delay: f64 },
+    /// Disconnected, no more attempts
+    Failed,
+}
+
+/**
+ * The status of a Beast receiver, sent in 0x34 frames (about once per
+ * second on Radarcape devices):
+ *
+ *  - the configuration settings (DIP switches on the Mode-S Beast, software
+ *    settings on Radarcape devices), named after their letter;
+ *  - the GPS status byte (Radarcape only), reported as is, with the UTC
+ *    and lock flags decoded;
+ *  - the MLAT timestamp of the frame, decoded as a GPS timestamp when GPS
+ *    timestamps are enabled.
+ *
+ * After the timestamp and the (unused) signal level, the data of the frame
+ * starts with the settings byte, followed by the timestamp offset, then the
+ * GPS status byte, as documented in the Beast binary format of the
+ * Radarcape manual and as decoded by mlat-client (`_modes.c`, data offset
+ * 2, bit 7 flagging timestamps aligned on UTC rather than GPS time). In the
+ * GPS status byte, bit 5 is set while the timestamp counter is synchronised
+ * on the pulse per second of the GPS receiver, and bit 4 while the GPS
+ * receiver has a valid UTC time.
+ *
+ * When a receiver loses its GPS lock, its timestamps drift away from the
+ * UTC time: MLAT timestamps are then no longer reliable, even though the
+ * free-running clock may remain close to the system clock for a while.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReceiverStatus {
+    /// The MLAT timestamp of the status frame
+    pub nanoseconds: u64,
+    /// The GPS timestamp of the status frame, if consistent with the system
+    /// clock (within one hour)
+    pub gnss_timestamp: Option<f64>,
+    /// (c) Beast binary format (AVR format otherwise)
+    pub binary_format: bool,
+    /// (d) Only DF11 and DF17 messages are sent
+    pub df11_df17_only: bool,
+    /// (e) MLAT timestamps are sent
+    pub mlat_timestamps: bool,
+    /// (f) CRC check disabled
+    pub crc_disabled: bool,
+    /// (g) GPS timestamps (12 MHz counter otherwise)
+    pub gps_timestamps: bool,
+    /// (h) RTS handshake
+    pub rts_handshake: bool,
+    /// (i) DF0, DF4 and DF5 messages are filtered out
+    pub df0_df4_df5_filter: bool,
+    /// (j) Mode A/C messages are sent
+    pub mode_ac: bool,
+    /// The GPS status byte (Radarcape only)
+    pub gps_status: u8,
+    /// The GPS timestamps are aligned on UTC rather than GPS time, as
+    /// flagged in the GPS status byte
+    pub utc_timestamps: bool,
+    /// GPS timestamps are enabled, and the GPS status byte reports both a
+    /// synchronised timestamp counter and a valid GPS time
+    pub gps_lock: bool,
+    /// GPS timestamps are enabled and consistent with the system clock
+    /// (within one hour), regardless of the GPS status byte
+    pub plausible_timestamp: bool,
+}
+
+/// The flag of the GPS status byte set when timestamps are aligned on UTC
+const UTC_TIMESTAMPS: u8 = 0x80;
+/// The flag of the GPS status byte set when the timestamp counter is
+/// synchronised on the pulse per second of the GPS receiver
+const GPS_SYNCHRONISED: u8 = 0x20;
+/// The flag of the GPS status byte set when the GPS time is valid
+const GPS_TIME_VALID: u8 = 0x10;
+
+impl ReceiverStatus {
+    /// Decode a 0x34 frame, as returned by [`next_msg`]
+    pub fn from_frame(msg: &[u8]) -> Option<Self> {
+        if msg.len() < 12 || msg[1] != 0x34 {
+            return None;
+        }
+        let mut array = [0u8; 8];
+        array[2..8].copy_from_slice(&msg[2..8]);
+        let nanoseconds = u64::from_be_bytes(array);
+        let settings = msg[9];
+        let gps_timestamps = settings & 0x10 != 0;
+        let gnss_timestamp = match gps_timestamps {
+            true => {
+                sensor_metadata(Some(nanoseconds), None, 0, None).gnss_timestamp
+            }
+            false => None,
+        };
+        let gps_status = msg[11];
+        let locked = GPS_SYNCHRONISED | GPS_TIME_VALID;
+        Some(Self {
+            nanoseconds,
+            gnss_timestamp,
+            binary_format: settings & 0x01 != 0,
+            df11_df17_only: settings & 0x02 != 0,
+            mlat_timestamps: settings & 0x04 != 0,
+            crc_disabled: settings & 0x08 != 0,
+            gps_timestamps,
+            rts_handshake: settings & 0x20 != 0,
+            df0_df4_df5_filter: settings & 0x40 != 0,
+            mode_ac: settings & 0x80 != 0,
+            gps_status,
+            utc_timestamps: gps_status & UTC_TIMESTAMPS != 0,
+            gps_lock: gps_timestamps && gps_status & locked == locked,
+            plausible_timestamp: gnss_timestamp.is_some(),
+        })
+    }
+}
+
+/**
+ * The reconnection policy of a Beast source: the delay between two attempts
+ * doubles after each failure, from `initial` to `max` (in seconds).
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// The delay before the first attempt to reconnect (in seconds)
+    pub initial: f64,
+    /// The maximum delay between two attempts (in seconds)
+    pub max: f64,
+    /// The maximum number of consecutive attempts (None for no limit)
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: 1.,
+            max: 60.,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay (in seconds) before a given attempt (starting at 1), None
+    /// if there should be no more attempts
+    pub fn delay(&self, attempt: u32) -> Option<f64> {
+        if self.max_attempts.is_some_and(|max| attempt > max) {
+            return None;
+        }
+        let factor = 2_f64.powi(attempt.saturating_sub(1).min(30) as i32);
+        Some((self.initial * factor).min(self.max))
+    }
+}
+
+/// Connect to a Beast source. TCP addresses which cannot be reached are
+/// bound as UDP endpoints, unless `fallback` is false.
+async fn connect(
+    address: &BeastSource,
+    fallback: bool,
+) -> io::Result<DataSource> {
+    match address {
+        BeastSource::Tcp(address) => match TcpStream::connect(address).await {
             Ok(stream) => {
                 info!("Connected to TCP stream: {}", address);
-                DataSource::Tcp(stream)
+                Ok(DataSource::Tcp(stream))
             }
-            Err(error) => {
+            Err(error) if fallback => {
                 info!(
                     "Failed to connect to TCP {} ({}), trying in UDP",
                     address,
                     error.to_string()
                 );
-                DataSource::Udp(UdpSocket::bind(&address).await?)
+                Ok(DataSource::Udp(UdpSocket::bind(address).await?))
             }
+            Err(error) => Err(error),
         },
         BeastSource::Udp(address) => {
-            DataSource::Udp(UdpSocket::bind(&address).await?)
+            Ok(DataSource::Udp(UdpSocket::bind(address).await?))
         }
         BeastSource::Websocket(address) => {
             info!("Connecting to websocket: {}", address);
-            let (stream, _) = connect_async(&address)
-                .await
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            let (stream, _) =
+                connect_async(address).await.map_err(io::Error::other)?;
             info!("Connected to websocket: {}", address);
             let (_, rx) = stream.split();
-            DataSource::Websocket(rx)
+            Ok(DataSource::Websocket(rx))
+        }
+    }
+}
+
+/// The duration after which a connection is deemed stable: the backoff
+/// policy starts over when such a connection is lost
+const STABLE_CONNECTION: Duration = Duration::from_secs(30);
+
+/// How a connection ended, see [`keep_connected`]
+pub(crate) enum Session {
+    /// The connection was lost, after transferring data (`active`) or not
+    Lost { active: bool },
+    /// There is nothing more to transfer, e.g. the channel is closed
+    Done,
+}
+
+/**
+ * Run `session` over the connections established with `connect`, and
+ * reconnect to `address` following the `backoff` policy when they are lost.
+ * Changes of the connection state are sent to `state`, together with the
+ * serial number of the source.
+ *
+ * The count of attempts starts over only after an active or stable
+ * connection: a peer accepting connections then closing them right away
+ * does not defeat the backoff policy.
+ *
+ * The function returns when the session is done, or with an error after
+ * the last failed attempt.
+ */
+pub(crate) async fn keep_connected<T, C, CF, S, SF>(
+    address: impl fmt::Display,
+    backoff: Backoff,
+    serial: u64,
+    state: Option<mpsc::Sender<(u64, ConnectionState)>>,
+    mut connect: C,
+    mut session: S,
+) -> io::Result<()>
+where
+    C: FnMut() -> CF,
+    CF: Future<Output = io::Result<T>>,
+    S: FnMut(T) -> SF,
+    SF: Future<Output = Session>,
+{
+    let report = |value: ConnectionState| {
+        let state = state.clone();
+        async move {
+            if let Some(state) = state {
+                let _ = state.send((serial, value)).await;
+            }
         }
     };
 
-    let msg_stream = beast::next_msg(msg_stream).await;
-    pin_mut!(msg_stream); // needed for iteration
-    'receive: loop {
-        while let Some(msg) = msg_stream.next().await {
-            let tmsg = process_radarcape(&msg, serial, name.clone());
-            info!("Received {}", tmsg);
-            if tx.send(tmsg).await.is_err() {
-                break 'receive;
+    let mut attempt = 0;
+    loop {
+        match connect().await {
+            Ok(connection) => {
+                report(ConnectionState::Connected).await;
+                let start = Instant::now();
+                match session(connection).await {
+                    Session::Done => return Ok(()),
+                    Session::Lost { active } => {
+                        info!("Disconnected from {}", address);
+                        if active || start.elapsed() >= STABLE_CONNECTION {
+                            attempt = 0;
+                        }
+                    }
+                }
+            }
+            Err(error) => {
+                error!("Failed to connect to {}: {}", address, error);
+                // Invalid addresses should not be retried
+                if error.kind() == io::ErrorKind::InvalidInput {
+                    report(ConnectionState::Failed).await;
+                    return Err(error);
+                }
+            }
+        }
+        attempt += 1;
+        match backoff.delay(attempt) {
+            Some(delay) => {
+                info!("Reconnecting to {} in {}s", address, delay);
+                report(ConnectionState::Retrying { attempt, delay }).await;
+                sleep(Duration::from_secs_f64(delay)).await;
+            }
+            None => {
+                report(ConnectionState::Failed).await;
+                return Err(io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    format!("Failed to reconnect to {address}"),
+                ));
+            }
+        }
+    }
+}
+
+/**
+ * Receive messages from a Beast source and send them to `tx`.
+ *
+ * The receiver reconnects when the connection is lost (e.g. when dump1090 or
+ * a Radarcape restarts), following the `backoff` policy. Changes of the
+ * connection state are sent to `state`, and the receiver status (0x34
+ * frames) to `status`, together with the serial number of the source.
+ *
+ * The function returns when `tx` is closed, or with an error after the last
+ * failed attempt.
+ */
+pub async fn receiver(
+    address: BeastSource,
+    tx: mpsc::Sender<TimedMessage>,
+    serial: u64,
+    name: Option<String>,
+    backoff: Backoff,
+    state: Option<mpsc::Sender<(u64, ConnectionState)>>,
+    status: Option<mpsc::Sender<(u64, ReceiverStatus)>>,
+) -> io::Result<()> {
+    // Fall back to UDP only if the TCP source was never reached
+    let fallback = AtomicBool::new(true);
+    let connect = || {
+        let (address, fallback) = (&address, &fallback);
+        async move {
+            let source =
+                connect(address, fallback.load(Ordering::Relaxed)).await?;
+            fallback.store(false, Ordering::Relaxed);
+            Ok(source)
+        }
+    };
+    let session = |source| {
+        let (tx, status, name) = (&tx, &status, &name);
+        async move {
+            let mut active = false;
+            let msg_stream = beast::next_msg(source).await;
+            pin_mut!(msg_stream); // needed for iteration
+            while let Some(msg) = msg_stream.next().await {
+                active = true;
+                if msg[1] == 0x34 {
+                    if let (Some(tx_status), Some(value)) =
+                        (status, ReceiverStatus::from_frame(&msg))
+                    {
+                        debug!("Receiver status {:?}", value);
+                        let _ = tx_status.send((serial, value)).await;
+                    }
+                    continue;
+                }
+                let tmsg = process_radarcape(&msg, serial, name.clone());
+                info!("Received {}", tmsg);
+                if tx.send(tmsg).await.is_err() {
+                    return Session::Done;
+                }
+            }
+            Session::Lost { active }
+        }
+    };
+    keep_connected(&address, backoff, serial, state, connect, session).await
+}
+
+/**
+ * Encode a message in the Beast binary format, i.e. the inverse of
+ * [`next_msg`] and of the decoding of the timestamp and signal level.
+ *
+ * The MLAT timestamp and the signal level are taken from the first sensor
+ * which timestamped the message (or the first sensor if none did), and set
+ * to 0 and 0xff respectively when unknown; 0x1a bytes are escaped (doubled)
+ * after the message type.
+ *
+ * Frames which are neither Mode A/C (2 bytes) nor Mode S (7 or 14 bytes)
+ * are not encoded (None).
+ */
+pub fn encode(msg: &TimedMessage) -> Option<Vec<u8>> {
+    let msg_type = match msg.frame.len() {
+        2 => 0x31,
+        7 => 0x32,
+        14 => 0x33,
+        _ => return None,
+    };
+    let metadata = msg
+        .metadata
+        .iter()
+        .find(|meta| meta.nanoseconds.is_some())
+        .or(msg.metadata.first());
+    let timestamp = metadata.and_then(|meta| meta.nanoseconds).unwrap_or(0);
+    // 0xff means no signal level information
+    let signal = metadata
+        .and_then(|meta| meta.rssi)
+        .map(|rssi| (10_f64.powf(rssi as f64 / 20.) * 255.).round())
+        .map_or(0xff, |v| v.clamp(0., 254.) as u8);
+
+    // 48-bit MLAT timestamp
+    let mut payload = timestamp.to_be_bytes()[2..].to_vec();
+    payload.push(signal);
+    payload.extend_from_slice(&msg.frame);
+
+    let mut data = vec![0x1a, msg_type];
+    for byte in payload {
+        data.push(byte);
+        if byte == 0x1a {
+            data.push(byte);
+        }
+    }
+    Some(data)
+}
+
+/**
+ * Serve Beast encoded messages (see [`encode`]) to all clients connecting to
+ * the `listener`, e.g. tar1090, readsb or mlat clients.
+ *
+ * Each client receives the messages sent to `tx` after the connection; slow
+ * clients may miss messages.
+ */
+pub async fn serve(
+    listener: TcpListener,
+    tx: broadcast::Sender<Vec<u8>>,
+) -> io::Result<()> {
+    loop {
+        let (mut socket, peer) = listener.accept().await?;
+        info!("New Beast client: {}", peer);
+        let mut rx = tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(data) => {
+                        if let Err(e) = socket.write_all(&data).await {
+                            info!("Beast client {} disconnected: {}", peer, e);
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Beast client {} missed {} messages", peer, n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+/**
+ * Push Beast encoded messages (see [`encode`]) to a remote host over TCP.
+ *
+ * The connection is reestablished when lost, following the `backoff`
+ * policy. The function returns when `tx` is closed, or with an error after
+ * the last failed attempt.
+ */
+pub async fn push(
+    address: String,
+    tx: broadcast::Sender<Vec<u8>>,
+    backoff: Backoff,
+) -> io::Result<()> {
+    let connect = || TcpStream::connect(address.as_str());
+    let session = |mut stream: TcpStream| {
+        let (address, tx) = (&address, &tx);
+        async move {
+            info!("Pushing Beast messages to {}", address);
+            let mut active = false;
+            let mut rx = tx.subscribe();
+            loop {
+                match rx.recv().await {
+                    Ok(data) => {
+                        if let Err(e) = stream.write_all(&data).await {
+                            error!("Error writing to {}: {}", address, e);
+                            return Session::Lost { active };
+                        }
+                        active = true;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("{} messages not pushed to {}", n, address);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Session::Done
+                    }
+                }
             }
         }
+    };
+    keep_connected(&address, backoff, 0, None, connect, session).await
+}
+
+impl fmt::Display for BeastSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BeastSource::Tcp(address) => write!(f, "tcp://{address}"),
+            BeastSource::Udp(address) => write!(f, "udp://{address}"),
+            BeastSource::Websocket(address) => write!(f, "{address}"),
+        }
     }
-    Ok(())
 }
 
 fn process_radarcape(
@@ -206,40 +638,238 @@ fn process_radarcape(
     // Copy the bytes from the slice into the array starting from index 2
     let mut array = [0u8; 8];
     array[2..8].copy_from_slice(&msg[2..8]);
-
     let ts_u64 = u64::from_be_bytes(array);
-    let seconds = ts_u64 as u128 >> 30;
-    let nanos = ts_u64 & 0x00003FFFFFFF;
-    let timestamp_in_s =
-        since_today_to_nanos(seconds * 1_000_000_000 + nanos as u128) as f64
-            * 1e-9;
 
-    let system_timestamp = now_in_ns() as f64 * 1e-9;
+    let rssi = if msg[8] == 0xff { None } else { Some(msg[8]) };
+    let metadata = sensor_metadata(Some(ts_u64), rssi, serial, name);
 
-    let gnss_timestamp = match (system_timestamp - timestamp_in_s).abs() {
-        value if value < 3600. => Some(timestamp_in_s),
+    // Mode A/C frames (type "1") are decoded here: they are not Mode S messages
+    let modeac = match msg[1] {
+        0x31 => ModeAC::try_from(&msg[9..]).ok(),
         _ => None,
     };
 
-    let rssi = if msg[8] == 0xff { None } else { Some(msg[8]) };
-    let rssi = rssi.map(|v| v as f64 / 255.);
-    let rssi = rssi.map(|v| 10. * (v * v).log10() as f32);
+    TimedMessage {
+        timestamp: metadata.system_timestamp,
+        frame: msg[9..].to_vec(),
+        message: None,
+        modeac,
+        metadata: vec![metadata],
+        decode_time: None,
+        corrected_bits: None,
+    }
+}
+
+/// Build the metadata of a message from the 48-bit MLAT timestamp and the
+/// signal level (one byte), as provided by Beast and AVR sources.
+pub(crate) fn sensor_metadata(
+    timestamp: Option<u64>,
+    signal: Option<u8>,
+    serial: u64,
+    name: Option<String>,
+) -> SensorMetadata {
+    let system_timestamp = now_in_ns() as f64 * 1e-9;
 
     // In some cases, the timestamp is just the one of dump1090, so forget it!
-    let metadata = SensorMetadata {
+    let gnss_timestamp = timestamp.and_then(|ts_u64| {
+        let seconds = ts_u64 as u128 >> 30;
+        let nanos = ts_u64 & 0x00003FFFFFFF;
+        let timestamp_in_s =
+            since_today_to_nanos(seconds * 1_000_000_000 + nanos as u128)
+                as f64
+                * 1e-9;
+        match (system_timestamp - timestamp_in_s).abs() {
+            value if value < 3600. => Some(timestamp_in_s),
+            _ => None,
+        }
+    });
+
+    let rssi = signal.map(|v| v as f64 / 255.);
+    let rssi = rssi.map(|v| 10. * (v * v).log10() as f32);
+
+    SensorMetadata {
         system_timestamp,
         gnss_timestamp,
-        nanoseconds: Some(ts_u64),
+        nanoseconds: timestamp,
         rssi,
         serial,
         name,
-    };
+    }
+}
 
-    TimedMessage {
-        timestamp: metadata.system_timestamp,
-        frame: msg[9..].to_vec(),
-        message: None,
-        metadata: vec![metadata],
-        decode_time: None,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let backoff = Backoff {
+            max_attempts: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(backoff.delay(1), Some(1.));
+        assert_eq!(backoff.delay(4), Some(8.));
+        assert_eq!(backoff.delay(8), Some(60.));
+        assert_eq!(backoff.delay(9), None);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        // A Beast frame with a DF17 message
+        let mut frame = vec![0x1a, 0x33, 0, 0, 0, 0, 0, 0, 0xff];
+        frame.extend(hex::decode("8D4840D6202CC371C32CE0576098").unwrap());
+
+        let (tx, mut rx) = mpsc::channel(10);
+        let (tx_state, mut rx_state) = mpsc::channel(10);
+        let backoff = Backoff {
+            initial: 0.01,
+            max_attempts: Some(1),
+            ..Default::default()
+        };
+        let handle = tokio::spawn(receiver(
+            BeastSource::Tcp(address),
+            tx,
+            0,
+            None,
+            backoff,
+            Some(tx_state),
+            None,
+        ));
+
+        // The peer closes the connection after each frame
+        for _ in 0..2 {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(&frame).await.unwrap();
+            drop(socket);
+            let msg = rx.recv().await.unwrap();
+            assert_eq!(msg.frame, frame[9..]);
+            assert_eq!(
+                rx_state.recv().await,
+                Some((0, ConnectionState::Connected))
+            );
+            assert!(matches!(
+                rx_state.recv().await,
+                Some((0, ConnectionState::Retrying { attempt: 1, .. }))
+            ));
+        }
+
+        // The peer closes the connection without sending data: the count of
+        // attempts does not start over
+        let (socket, _) = listener.accept().await.unwrap();
+        drop(socket);
+        assert_eq!(
+            rx_state.recv().await,
+            Some((0, ConnectionState::Connected))
+        );
+        assert_eq!(rx_state.recv().await, Some((0, ConnectionState::Failed)));
+        assert!(handle.await.unwrap().is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut frame = vec![0x1a, 0x33, 0, 0x1a, 0, 0, 0, 1, 0x80];
+        frame.extend(hex::decode("8D4840D6202CC371C32CE0576098").unwrap());
+        let mut msg = process_radarcape(&frame, 0, None);
+        assert_eq!(msg.metadata[0].nanoseconds, Some(0x001a_0000_0001));
+
+        let data = encode(&msg).unwrap();
+        assert_eq!(data[..10], [0x1a, 0x33, 0, 0x1a, 0x1a, 0, 0, 0, 1, 0x80]);
+        assert_eq!(data[10..], frame[9..]);
+
+        // Escaped signal level
+        msg.metadata[0].rssi =
+            process_radarcape(&[0x1a; 23], 0, None).metadata[0].rssi;
+        assert_eq!(encode(&msg).unwrap()[9..11], [0x1a, 0x1a]);
+
+        // Unknown signal level
+        msg.metadata[0].rssi = None;
+        assert_eq!(encode(&msg).unwrap()[9], 0xff);
+
+        msg.frame.truncate(10);
+        assert_eq!(encode(&msg), None);
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (tx, _) = broadcast::channel(10);
+        tokio::spawn(serve(listener, tx.clone()));
+
+        let stream = TcpStream::connect(&address).await.unwrap();
+        // Wait for the client to be registered
+        while tx.receiver_count() == 0 {
+            sleep(Duration::from_millis(1)).await;
+        }
+
+        let mut frame = vec![0x1a, 0x33, 0x1a, 0x1a, 0, 0, 0, 0, 0x1a];
+        frame.extend(hex::decode("8D4840D6202CC371C32CE0576098").unwrap());
+        let msg = process_radarcape(&frame, 0, None);
+        for _ in 0..2 {
+            tx.send(encode(&msg).unwrap()).unwrap();
+        }
+
+        let msg_stream = next_msg(DataSource::Tcp(stream)).await;
+        pin_mut!(msg_stream);
+        let received = msg_stream.next().await.unwrap();
+        let received = process_radarcape(&received, 0, None);
+        assert_eq!(received.frame, msg.frame);
+        assert_eq!(received.metadata[0].nanoseconds, Some(0x1a1a_0000_0000));
+        assert_eq!(received.metadata[0].rssi, msg.metadata[0].rssi);
+    }
+
+    #[test]
+    fn test_status() {
+        // A Radarcape status frame: binary format, MLAT and GPS timestamps,
+        // RTS handshake, null timestamp offset, UTC and lock flags
+        let frame =
+            hex::decode("1a34ba8e3a0c2f10003500b00000000000000000000000")
+                .unwrap();
+        let status = ReceiverStatus::from_frame(&frame).unwrap();
+        assert!(status.binary_format && status.mlat_timestamps);
+        assert!(status.gps_timestamps && !status.mode_ac);
+        assert!(!status.df11_df17_only && !status.crc_disabled);
+        assert_eq!(status.gps_status, 0xb0);
+        assert!(status.utc_timestamps && status.gps_lock);
+
+        // A GPS timestamp: seconds since midnight, then nanoseconds
+        let seconds = (now_in_ns() / 1_000_000_000) as u64 % 86400;
+        let timestamp = (seconds << 30).to_be_bytes();
+        let mut frame = vec![0x1a, 0x34];
+        frame.extend(&timestamp[2..]);
+        frame.extend([0x00, 0x15, 0x00, 0xb0]);
+        frame.extend([0; 11]);
+
+        let status = ReceiverStatus::from_frame(&frame).unwrap();
+        assert!(status.gnss_timestamp.is_some());
+        assert!(status.gps_lock && status.plausible_timestamp);
+
+        // Lost synchronisation, while the clock is still plausible
+        frame[11] = 0x90;
+        let status = ReceiverStatus::from_frame(&frame).unwrap();
+        assert!(!status.gps_lock && status.plausible_timestamp);
+
+        // No valid GPS time, while the clock is still plausible
+        frame[11] = 0xa0;
+        let status = ReceiverStatus::from_frame(&frame).unwrap();
+        assert!(!status.gps_lock && status.plausible_timestamp);
+
+        // The timestamp offset is not the GPS status byte
+        frame[10] = 0xb0;
+        frame[11] = 0x00;
+        let status = ReceiverStatus::from_frame(&frame).unwrap();
+        assert_eq!(status.gps_status, 0);
+        assert!(!status.utc_timestamps && !status.gps_lock);
+        frame[11] = 0xb0;
+
+        // 12 MHz counter
+        frame[9] = 0x05;
+        let status = ReceiverStatus::from_frame(&frame).unwrap();
+        assert_eq!(status.gnss_timestamp, None);
+        assert!(!status.gps_lock && !status.plausible_timestamp);
+
+        assert_eq!(ReceiverStatus::from_frame(&frame[..11]), None);
     }
 }
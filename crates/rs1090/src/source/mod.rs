@@ -1,3 +1,6 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod avr;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub mod beast;
 
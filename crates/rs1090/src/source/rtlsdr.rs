@@ -89,8 +89,10 @@ pub async fn receiver<A: Into<Args> + fmt::Display + std::marker::Copy>(
                         timestamp: system_timestamp,
                         frame: data.msg.to_vec(),
                         message: None,
+                        modeac: None,
                         metadata: vec![metadata],
                         decode_time: None,
+                        corrected_bits: None,
                     };
                     if tx.send(tmsg).await.is_err() {
                         break 'receive;
@@ -79,8 +79,10 @@ pub async fn receiver(sero: SeroClient, tx: mpsc::Sender<TimedMessage>) {
                     timestamp: system_timestamp,
                     frame: bytes.to_vec(),
                     message: None,
+                    modeac: None,
                     metadata,
                     decode_time: None,
+                    corrected_bits: None,
                 };
                 if let Err(e) = tx_copy.send(tmsg).await {
                     error!("{}", e.to_string());
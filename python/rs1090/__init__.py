@@ -19,6 +19,9 @@ from ._rust import (
     decode_bds21,
     decode_bds30,
     decode_bds40,
+    decode_bds41,
+    decode_bds42,
+    decode_bds43,
     decode_bds44,
     decode_bds45,
     decode_bds50,
@@ -39,8 +42,15 @@ from .stubs import (
     is_bds20,
     is_bds30,
     is_bds40,
+    is_bds41,
+    is_bds42,
+    is_bds43,
     is_bds44,
     is_bds50,
+    is_bds51,
+    is_bds52,
+    is_bds53,
+    is_bds5f,
     is_bds60,
     is_bds61,
     is_bds62,
@@ -52,8 +62,10 @@ from .stubs import (
     is_df16,
     is_df17,
     is_df18,
+    is_df19,
     is_df20,
     is_df21,
+    is_df24,
 )
 
 try:
@@ -90,6 +102,9 @@ decode_bds20 = unpickle_fun(decode_bds20)
 decode_bds21 = unpickle_fun(decode_bds21)
 decode_bds30 = unpickle_fun(decode_bds30)
 decode_bds40 = unpickle_fun(decode_bds40)
+decode_bds41 = unpickle_fun(decode_bds41)
+decode_bds42 = unpickle_fun(decode_bds42)
+decode_bds43 = unpickle_fun(decode_bds43)
 decode_bds44 = unpickle_fun(decode_bds44)
 decode_bds45 = unpickle_fun(decode_bds45)
 decode_bds50 = unpickle_fun(decode_bds50)
@@ -109,6 +124,9 @@ __all__ = [
     "decode_bds21",
     "decode_bds30",
     "decode_bds40",
+    "decode_bds41",
+    "decode_bds42",
+    "decode_bds43",
     "decode_bds44",
     "decode_bds45",
     "decode_bds50",
@@ -123,8 +141,15 @@ __all__ = [
     "is_bds20",
     "is_bds30",
     "is_bds40",
+    "is_bds41",
+    "is_bds42",
+    "is_bds43",
     "is_bds44",
     "is_bds50",
+    "is_bds51",
+    "is_bds52",
+    "is_bds53",
+    "is_bds5f",
     "is_bds60",
     "is_bds61",
     "is_bds62",
@@ -134,8 +159,10 @@ __all__ = [
     "is_df16",
     "is_df17",
     "is_df18",
+    "is_df19",
     "is_df20",
     "is_df21",
+    "is_df24",
     "is_df4",
     "is_df5",
     "aircraft_information",
@@ -30,6 +30,9 @@ def decode_bds20(mgs: str) -> DF20 | DF21: ...
 def decode_bds21(mgs: str) -> DF20 | DF21: ...
 def decode_bds30(mgs: str) -> DF20 | DF21: ...
 def decode_bds40(mgs: str) -> DF20 | DF21: ...
+def decode_bds41(mgs: str) -> DF20 | DF21: ...
+def decode_bds42(mgs: str) -> DF20 | DF21: ...
+def decode_bds43(mgs: str) -> DF20 | DF21: ...
 def decode_bds44(mgs: str) -> DF20 | DF21: ...
 def decode_bds45(mgs: str) -> DF20 | DF21: ...
 def decode_bds50(mgs: str) -> DF20 | DF21: ...
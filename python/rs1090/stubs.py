@@ -7,6 +7,8 @@ from typing_extensions import (
     TypeGuard,  # python <3.10
 )
 
+Rejection = Literal["accuracy", "range", "speed", "divergence"]
+
 
 class BDS05(TypedDict):
     bds: Literal["05"]
@@ -19,6 +21,12 @@ class BDS05(TypedDict):
     lon_cpr: int
     latitude: NotRequired[float]
     longitude: NotRequired[float]
+    rejected: NotRequired[Rejection]
+    version: NotRequired[int]
+    NIC: NotRequired[int]
+    Rc: NotRequired[float]
+    NACv: NotRequired[int]
+    SIL: NotRequired[int]
 
 
 class BDS10(TypedDict):
@@ -108,6 +116,25 @@ class BDS40(TypedDict):
     ]
 
 
+class BDS41(TypedDict):
+    bds: Literal["41"]
+    waypoint: NotRequired[str]
+
+
+class BDS42(TypedDict):
+    bds: Literal["42"]
+    waypoint_latitude: NotRequired[float]
+    waypoint_longitude: NotRequired[float]
+    waypoint_altitude: NotRequired[int]
+
+
+class BDS43(TypedDict):
+    bds: Literal["43"]
+    waypoint_bearing: NotRequired[float]
+    waypoint_time: NotRequired[float]
+    waypoint_distance: NotRequired[float]
+
+
 class BDS44(TypedDict):
     bds: Literal["44"]
     issued_ra: bool
@@ -141,9 +168,44 @@ class BDS50(TypedDict):
     TAS: None | int
 
 
+class BDS51(TypedDict):
+    bds: Literal["51"]
+    latitude: NotRequired[float]
+    longitude: NotRequired[float]
+    altitude: NotRequired[int]
+
+
+class BDS52(TypedDict):
+    bds: Literal["52"]
+    FOM: NotRequired[int]
+    latitude_fine: NotRequired[float]
+    longitude_fine: NotRequired[float]
+    source: Literal["barometric", "GNSS"]
+    altitude: NotRequired[int]
+
+
+class BDS53(TypedDict):
+    bds: Literal["53"]
+    heading: NotRequired[float]
+    true_heading: NotRequired[float]
+    IAS: NotRequired[int]
+    Mach: NotRequired[float]
+    TAS: NotRequired[float]
+    vrate_barometric: NotRequired[int]
+
+
+class BDS5F(TypedDict):
+    bds: Literal["5F"]
+    selected_altitude: int
+    selected_heading: int
+    fms_selected_altitude: int
+    barometric_setting: int
+
+
 class BDS60(TypedDict):
     bds: Literal["60"]
     heading: NotRequired[float]
+    true_heading: NotRequired[float]
     IAS: NotRequired[int]
     Mach: NotRequired[float]
     vrate_barometric: NotRequired[int]
@@ -183,8 +245,9 @@ class DF16(TypedDict):
     df: Literal["16"]
     vs: int
     sl: int
-    ri: int
+    ri: str
     altitude: int
+    bds30: NotRequired[BDS30]
     icao24: str
 
 
@@ -202,6 +265,12 @@ class DF17_BDS05(TypedDict):
     lon_cpr: int
     latitude: NotRequired[float]
     longitude: NotRequired[float]
+    rejected: NotRequired[Rejection]
+    version: NotRequired[int]
+    NIC: NotRequired[int]
+    Rc: NotRequired[float]
+    NACv: NotRequired[int]
+    SIL: NotRequired[int]
 
 
 class DF17_BDS06(TypedDict):
@@ -217,6 +286,12 @@ class DF17_BDS06(TypedDict):
     lon_cpr: int
     latitude: NotRequired[float]
     longitude: NotRequired[float]
+    rejected: NotRequired[Rejection]
+    version: NotRequired[int]
+    NIC: NotRequired[int]
+    Rc: NotRequired[float]
+    NACv: NotRequired[int]
+    SIL: NotRequired[int]
 
 
 class DF17_BDS08(TypedDict):
@@ -257,6 +332,7 @@ class DF17_BDS09(TypedDict):
     IAS: NotRequired[float]
     track: NotRequired[float]
     heading: NotRequired[float]
+    true_heading: NotRequired[float]
     vrate_src: str
     vertical_rate: int
     geo_minus_baro: None | int
@@ -267,7 +343,7 @@ class DF17_BDS61(TypedDict):
     df: str
     icao24: str
     bds: Literal["61"]
-    subtype: Literal["emergency_priority", "acas_ra"]
+    subtype: Literal["emergency_priority"]
     emergency_state: Literal[
         "none",
         "general",
@@ -281,6 +357,31 @@ class DF17_BDS61(TypedDict):
     squawk: str
 
 
+class DF17_BDS61_RA(TypedDict):
+    timestamp: float
+    df: str
+    icao24: str
+    bds: Literal["61"]
+    subtype: Literal["acas_ra"]
+    issued_ra: bool
+    corrective: NotRequired[bool]
+    downward_sense: NotRequired[bool]
+    increased_rate: NotRequired[bool]
+    sense_reversal: NotRequired[bool]
+    altitude_crossing: NotRequired[bool]
+    positive: NotRequired[bool]
+    no_below: NotRequired[bool]
+    no_above: NotRequired[bool]
+    no_left: NotRequired[bool]
+    no_right: NotRequired[bool]
+    terminated: bool
+    multiple: bool
+    threat_identity: NotRequired[str]
+    threat_altitude: NotRequired[int]
+    threat_range: NotRequired[float]
+    threat_bearing: NotRequired[int]
+
+
 class DF17_BDS62(TypedDict):
     timestamp: float
     df: Literal["17"]
@@ -326,6 +427,34 @@ class DF17_BDS65(TypedDict):
     SILs: int
 
 
+class DF17_ID23(TypedDict):
+    timestamp: float
+    df: Literal["17"]
+    icao24: str
+    bds: Literal["id23"]
+    subtype: int
+    squawk: NotRequired[str]
+    payload: NotRequired[str]
+
+
+class DF17_ID24(TypedDict):
+    timestamp: float
+    df: Literal["17"]
+    icao24: str
+    bds: Literal["id24"]
+    subtype: int
+    payload: str
+
+
+class DF17_ID30(TypedDict):
+    timestamp: float
+    df: Literal["17"]
+    icao24: str
+    bds: Literal["id30"]
+    subtype: int
+    payload: str
+
+
 class DF17_Unknown(TypedDict):
     timestamp: float
     df: Literal["17"]
@@ -356,6 +485,12 @@ class DF18_BDS06(TypedDict):
     lon_cpr: int
     latitude: NotRequired[float]
     longitude: NotRequired[float]
+    rejected: NotRequired[Rejection]
+    version: NotRequired[int]
+    NIC: NotRequired[int]
+    Rc: NotRequired[float]
+    NACv: NotRequired[int]
+    SIL: NotRequired[int]
 
 
 class DF18_BDS08(TypedDict):
@@ -449,6 +584,21 @@ class DF18_Unknown(TypedDict):
     bds: Literal["?"]
 
 
+class DF19_ES(TypedDict):
+    timestamp: float
+    df: Literal["19"]
+    af: Literal[0]
+    icao24: str
+    bds: str
+
+
+class DF19_Reserved(TypedDict):
+    timestamp: float
+    df: Literal["19"]
+    af: int
+    payload: str
+
+
 class DF20(TypedDict):
     timestamp: float
     df: Literal["20"]
@@ -463,10 +613,18 @@ class DF20(TypedDict):
     bds21: None | BDS21
     bds30: None | BDS30
     bds40: None | BDS40
+    bds41: None | BDS41
+    bds42: None | BDS42
+    bds43: None | BDS43
     bds44: None | BDS44
     bds45: None | BDS45
     bds50: None | BDS50
+    bds51: None | BDS51
+    bds52: None | BDS52
+    bds53: None | BDS53
+    bds5f: None | BDS5F
     bds60: None | BDS60
+    bds_confidence: None | float
 
 
 class DF21(TypedDict):
@@ -482,10 +640,26 @@ class DF21(TypedDict):
     bds21: None | BDS21
     bds30: None | BDS30
     bds40: None | BDS40
+    bds41: None | BDS41
+    bds42: None | BDS42
+    bds43: None | BDS43
     bds44: None | BDS44
     bds45: None | BDS45
     bds50: None | BDS50
+    bds51: None | BDS51
+    bds52: None | BDS52
+    bds53: None | BDS53
+    bds5f: None | BDS5F
     bds60: None | BDS60
+    bds_confidence: None | float
+
+
+class DF24(TypedDict):
+    timestamp: float
+    df: Literal["24"]
+    icao24: str
+    nd: int
+    md: str
 
 
 DF17 = Union[
@@ -495,8 +669,12 @@ DF17 = Union[
     DF17_BDS08,
     DF17_BDS09,
     DF17_BDS61,
+    DF17_BDS61_RA,
     DF17_BDS62,
     DF17_BDS65,
+    DF17_ID23,
+    DF17_ID24,
+    DF17_ID30,
 ]
 
 DF18 = Union[
@@ -506,8 +684,12 @@ DF18 = Union[
     DF18_BDS65,
 ]
 
+DF19 = Union[DF19_ES, DF19_Reserved]
 
-Message = Union[DF0, DF4, DF5, DF11, DF16, DF17, DF18, DF20, DF21]
+
+Message = Union[
+    DF0, DF4, DF5, DF11, DF16, DF17, DF18, DF19, DF20, DF21, DF24
+]
 
 
 def is_df0(message: Message) -> TypeGuard[DF0]:
@@ -538,6 +720,10 @@ def is_df18(message: Message) -> TypeGuard[DF18]:
     return message["df"] == "18"
 
 
+def is_df19(message: Message) -> TypeGuard[DF19]:
+    return message["df"] == "19"
+
+
 def is_df20(message: Message) -> TypeGuard[DF20]:
     return message["df"] == "20"
 
@@ -546,6 +732,10 @@ def is_df21(message: Message) -> TypeGuard[DF21]:
     return message["df"] == "21"
 
 
+def is_df24(message: Message) -> TypeGuard[DF24]:
+    return message["df"] == "24"
+
+
 def is_bds05(message: DF17) -> TypeGuard[DF17_BDS05]:
     return message.get("bds", None) == "05" or "bds05" in message
 
@@ -590,6 +780,18 @@ def is_bds40(message: DF20 | DF21) -> bool:
     return message.get("bds", None) == "40" or "bds40" in message
 
 
+def is_bds41(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "41" or "bds41" in message
+
+
+def is_bds42(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "42" or "bds42" in message
+
+
+def is_bds43(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "43" or "bds43" in message
+
+
 def is_bds44(message: DF20 | DF21) -> bool:
     return message.get("bds", None) == "44" or "bds44" in message
 
@@ -598,11 +800,29 @@ def is_bds50(message: DF20 | DF21) -> bool:
     return message.get("bds", None) == "50" or "bds50" in message
 
 
+def is_bds51(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "51" or "bds51" in message
+
+
+def is_bds52(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "52" or "bds52" in message
+
+
+def is_bds53(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "53" or "bds53" in message
+
+
+def is_bds5f(message: DF20 | DF21) -> bool:
+    return message.get("bds", None) == "5F" or "bds5f" in message
+
+
 def is_bds60(message: DF20 | DF21) -> bool:
     return message.get("bds", None) == "60" or "bds60" in message
 
 
-def is_bds61(message: DF17) -> TypeGuard[DF17_BDS61]:
+def is_bds61(
+    message: DF17,
+) -> TypeGuard[DF17_BDS61 | DF17_BDS61_RA]:
     return message.get("bds", None) == "61" or "bds61" in message
 
 
@@ -2,8 +2,9 @@
 
 use std::collections::HashMap;
 
-use pyo3::exceptions::{PyAssertionError, PyValueError};
+use pyo3::exceptions::{PyAssertionError, PyBaseException, PyValueError};
 use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
 use rayon::prelude::*;
 use regex::Regex;
 use rs1090::data::patterns::PATTERNS;
@@ -17,13 +18,17 @@ use rs1090::decode::bds::bds20::AircraftIdentification;
 use rs1090::decode::bds::bds21::AircraftAndAirlineRegistrationMarkings;
 use rs1090::decode::bds::bds30::ACASResolutionAdvisory;
 use rs1090::decode::bds::bds40::SelectedVerticalIntention;
+use rs1090::decode::bds::bds41::NextWaypointIdentifier;
+use rs1090::decode::bds::bds42::NextWaypointPosition;
+use rs1090::decode::bds::bds43::NextWaypointInformation;
 use rs1090::decode::bds::bds44::MeteorologicalRoutineAirReport;
 use rs1090::decode::bds::bds45::MeteorologicalHazardReport;
 use rs1090::decode::bds::bds50::TrackAndTurnReport;
 use rs1090::decode::bds::bds60::HeadingAndSpeedReport;
 use rs1090::decode::bds::bds65::AircraftOperationStatus;
-use rs1090::decode::cpr::{decode_positions, Position};
+use rs1090::decode::cpr::{decode_positions, Position, PositionFilter};
 use rs1090::decode::flarm::Flarm;
+use rs1090::decode::Error;
 use rs1090::prelude::*;
 
 #[pyfunction]
@@ -36,17 +41,91 @@ fn decode_1090(msg: String) -> PyResult<Vec<u8>> {
         Ok([128, 4, 78, 46].to_vec()) // None
     }
 }
-struct DecodeError(DekuError);
+struct DecodeError(Error);
 
+impl DecodeError {
+    fn hypothesis(bds: &'static str, error: DekuError) -> Self {
+        DecodeError(Error::hypothesis(bds, error))
+    }
+}
+
+/// Payloads with values rejected by the decoder raise an AssertionError,
+/// payloads which cannot be parsed at all raise a ValueError.
+///
+/// The exception carries the details of the error as attributes: `reason`
+/// (e.g. "range" or "reserved", see `Error::reason`), `bds` (the rejected
+/// register, if any), `field` and `value` (tuples for inconsistent fields),
+/// so that callers do not need to match the message.
 impl From<DecodeError> for PyErr {
     fn from(error: DecodeError) -> Self {
-        match error.0 {
-            DekuError::Assertion(msg) => PyAssertionError::new_err(msg),
+        let (bds, reason) = match &error.0 {
+            Error::Hypothesis { bds, reason } => (Some(*bds), reason.as_ref()),
+            error => (None, error),
+        };
+        let err = match reason {
+            Error::UnknownTypecode { .. }
+            | Error::Reserved { .. }
+            | Error::Status { .. }
+            | Error::Range { .. }
+            | Error::Text { .. }
+            | Error::Inconsistent { .. }
+            | Error::Deku(DekuError::Assertion(_)) => {
+                PyAssertionError::new_err(error.0.to_string())
+            }
             _ => PyValueError::new_err(error.0.to_string()),
-        }
+        };
+        Python::with_gil(|py| {
+            // Setting attributes on a new exception does not fail
+            let _ = set_attributes(err.value(py), bds, reason);
+        });
+        err
     }
 }
 
+fn set_attributes(
+    exception: &Bound<'_, PyBaseException>,
+    bds: Option<&str>,
+    reason: &Error,
+) -> PyResult<()> {
+    let py = exception.py();
+    let (field, value) = match reason {
+        Error::Length { actual, .. } => {
+            ("length".into_py_any(py)?, actual.into_py_any(py)?)
+        }
+        Error::Crc { crc, .. } => {
+            ("crc".into_py_any(py)?, crc.into_py_any(py)?)
+        }
+        Error::UnknownFormat { df } => {
+            ("df".into_py_any(py)?, df.into_py_any(py)?)
+        }
+        Error::UnknownTypecode { tc, subtype: None } => {
+            ("typecode".into_py_any(py)?, tc.into_py_any(py)?)
+        }
+        Error::UnknownTypecode {
+            subtype: Some(subtype),
+            ..
+        } => ("subtype".into_py_any(py)?, subtype.into_py_any(py)?),
+        Error::Reserved { field, value } | Error::Status { field, value } => {
+            (field.into_py_any(py)?, value.into_py_any(py)?)
+        }
+        Error::Range { field, value } => {
+            (field.into_py_any(py)?, value.into_py_any(py)?)
+        }
+        Error::Text { field, value } => {
+            (field.into_py_any(py)?, value.into_py_any(py)?)
+        }
+        Error::Inconsistent { fields, values } => {
+            (fields.into_py_any(py)?, values.into_py_any(py)?)
+        }
+        Error::Hypothesis { .. } | Error::Deku(_) => (py.None(), py.None()),
+    };
+    exception.setattr("reason", reason.reason())?;
+    exception.setattr("bds", bds)?;
+    exception.setattr("field", field)?;
+    exception.setattr("value", value)?;
+    Ok(())
+}
+
 #[pyfunction]
 fn decode_bds05(msg: String) -> PyResult<Vec<u8>> {
     let bytes = hex::decode(msg).unwrap();
@@ -58,14 +137,15 @@ fn decode_bds05(msg: String) -> PyResult<Vec<u8>> {
                     serde_pickle::to_vec(&msg, Default::default()).unwrap();
                 Ok(pkl)
             }
-            Err(e) => Err(DecodeError(e).into()),
+            Err(e) => Err(DecodeError::hypothesis("05", e).into()),
         }
     } else {
-        let msg = format!(
-            "Invalid typecode {} for BDS 0,5 (9 to 18 or 20 to 22)",
-            tc
-        );
-        Err(PyAssertionError::new_err(msg))
+        let reason = Error::UnknownTypecode { tc, subtype: None };
+        Err(DecodeError(Error::Hypothesis {
+            bds: "05",
+            reason: Box::new(reason),
+        })
+        .into())
     }
 }
 
@@ -77,7 +157,7 @@ fn decode_bds10(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("10", e).into()),
     }
 }
 
@@ -89,7 +169,7 @@ fn decode_bds17(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("17", e).into()),
     }
 }
 
@@ -101,7 +181,7 @@ fn decode_bds18(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("18", e).into()),
     }
 }
 
@@ -113,7 +193,7 @@ fn decode_bds19(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("19", e).into()),
     }
 }
 
@@ -125,7 +205,7 @@ fn decode_bds20(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("20", e).into()),
     }
 }
 
@@ -137,7 +217,7 @@ fn decode_bds21(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("21", e).into()),
     }
 }
 
@@ -149,7 +229,7 @@ fn decode_bds30(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("30", e).into()),
     }
 }
 
@@ -161,7 +241,43 @@ fn decode_bds40(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("40", e).into()),
+    }
+}
+
+#[pyfunction]
+fn decode_bds41(msg: String) -> PyResult<Vec<u8>> {
+    let bytes = hex::decode(msg).unwrap();
+    match NextWaypointIdentifier::from_bytes((&bytes[4..], 0)) {
+        Ok((_, msg)) => {
+            let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
+            Ok(pkl)
+        }
+        Err(e) => Err(DecodeError::hypothesis("41", e).into()),
+    }
+}
+
+#[pyfunction]
+fn decode_bds42(msg: String) -> PyResult<Vec<u8>> {
+    let bytes = hex::decode(msg).unwrap();
+    match NextWaypointPosition::from_bytes((&bytes[4..], 0)) {
+        Ok((_, msg)) => {
+            let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
+            Ok(pkl)
+        }
+        Err(e) => Err(DecodeError::hypothesis("42", e).into()),
+    }
+}
+
+#[pyfunction]
+fn decode_bds43(msg: String) -> PyResult<Vec<u8>> {
+    let bytes = hex::decode(msg).unwrap();
+    match NextWaypointInformation::from_bytes((&bytes[4..], 0)) {
+        Ok((_, msg)) => {
+            let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
+            Ok(pkl)
+        }
+        Err(e) => Err(DecodeError::hypothesis("43", e).into()),
     }
 }
 
@@ -173,7 +289,7 @@ fn decode_bds44(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("44", e).into()),
     }
 }
 #[pyfunction]
@@ -184,7 +300,7 @@ fn decode_bds45(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("45", e).into()),
     }
 }
 
@@ -196,7 +312,7 @@ fn decode_bds50(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("50", e).into()),
     }
 }
 
@@ -208,7 +324,7 @@ fn decode_bds60(msg: String) -> PyResult<Vec<u8>> {
             let pkl = serde_pickle::to_vec(&msg, Default::default()).unwrap();
             Ok(pkl)
         }
-        Err(e) => Err(DecodeError(e).into()),
+        Err(e) => Err(DecodeError::hypothesis("60", e).into()),
     }
 }
 
@@ -225,13 +341,20 @@ fn decode_bds65(msg: String) -> PyResult<Vec<u8>> {
                         serde_pickle::to_vec(&msg, Default::default()).unwrap();
                     Ok(pkl)
                 }
-                Err(e) => Err(DecodeError(e).into()),
+                Err(e) => Err(DecodeError::hypothesis("65", e).into()),
             }
         }
-        _ => Err(PyAssertionError::new_err(format!(
-            "Invalid typecode {} (31) or category {} (0 or 1) for BDS 6,5",
-            tc, enum_id
-        ))),
+        _ => {
+            let reason = Error::UnknownTypecode {
+                tc,
+                subtype: (tc == 31).then_some(enum_id),
+            };
+            Err(DecodeError(Error::Hypothesis {
+                bds: "65",
+                reason: Box::new(reason),
+            })
+            .into())
+        }
     }
 }
 
@@ -277,8 +400,10 @@ fn decode_1090t_vec(
                             timestamp,
                             frame: bytes,
                             message: Some(message),
+                            modeac: None,
                             metadata: vec![],
                             decode_time: None,
+                            corrected_bits: None,
                         })
                     } else {
                         None
@@ -293,7 +418,7 @@ fn decode_1090t_vec(
         latitude,
         longitude,
     });
-    decode_positions(&mut res, position, &None);
+    decode_positions(&mut res, position, &None, &PositionFilter::default());
 
     let pkl = serde_pickle::to_vec(&res, Default::default()).unwrap();
     Ok(pkl)
@@ -343,13 +468,7 @@ fn decode_flarm_vec(
                 .zip(reference)
                 .filter_map(|((msg, timestamp), reference)| {
                     let bytes = hex::decode(msg).unwrap();
-                    if let Ok(flarm) =
-                        Flarm::from_record(timestamp, &reference, &bytes)
-                    {
-                        Some(flarm)
-                    } else {
-                        None
-                    }
+                    Flarm::from_record(timestamp, &reference, &bytes).ok()
                 })
                 .collect()
         })
@@ -447,6 +566,9 @@ fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(decode_bds21, m)?)?;
     m.add_function(wrap_pyfunction!(decode_bds30, m)?)?;
     m.add_function(wrap_pyfunction!(decode_bds40, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_bds41, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_bds42, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_bds43, m)?)?;
     m.add_function(wrap_pyfunction!(decode_bds44, m)?)?;
     m.add_function(wrap_pyfunction!(decode_bds45, m)?)?;
     m.add_function(wrap_pyfunction!(decode_bds50, m)?)?;
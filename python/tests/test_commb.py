@@ -1,4 +1,4 @@
-from pytest import approx
+from pytest import approx, raises
 
 import rs1090
 
@@ -48,3 +48,12 @@ def test_bds60() -> None:
     assert bds60["Mach"] == 0.42
     assert bds60["vrate_barometric"] == -1920
     assert bds60["vrate_inertial"] == -1920
+
+
+def test_decode_error() -> None:
+    with raises(AssertionError) as error:
+        rs1090.decode_bds44("A0000000FFFFFFFFFFFFFF000000")
+    assert error.value.reason == "range"  # type: ignore
+    assert error.value.bds == "44"  # type: ignore
+    assert error.value.field == "wind_speed"  # type: ignore
+    assert error.value.value == 511  # type: ignore